@@ -4,9 +4,19 @@
 //! Main client implementation for SecureNotify SDK
 
 use async_trait::async_trait;
+use std::collections::HashMap;
 use std::sync::Arc;
 use crate::managers::*;
-use crate::utils::http::HttpClient;
+use crate::utils::http::{BatchPolicy, FieldCase, HttpClient, HttpClientConfig, HttpVersion};
+use crate::utils::signing::RequestSigner;
+use crate::utils::jwt::ApiKeyClaims;
+use crate::utils::quota::Operation;
+use crate::utils::retry::RetryPreset;
+use crate::utils::timing::{timed, Timed};
+use crate::utils::interceptor::{
+    InterceptorChain, MetadataValidator, MetadataValidatorFn, RequestCompleteHook,
+    RequestIdGenerator, RequestInterceptor, RequestOutcome, RetriesExhaustedFn, RetriesExhaustedHook,
+};
 use crate::utils::connection::SseMessage;
 use crate::{Result, SecureNotifyError, MessagePriority};
 
@@ -57,6 +67,118 @@ impl SecureNotifyClient {
         self.http_client.config().base_url.clone()
     }
 
+    /// Establish a pooled connection before the first real request
+    ///
+    /// Removes the TCP and TLS handshake cost from the first API call.
+    /// Failing to warm up leaves the client fully usable, so callers can
+    /// treat an error as a warning.
+    pub async fn warm_up(&self) -> Result<()> {
+        self.http_client.warm_up().await
+    }
+
+    /// Get the current adaptive concurrency limit, if enabled
+    pub fn concurrency_limit(&self) -> Option<usize> {
+        self.http_client.concurrency_limit()
+    }
+
+    /// Render request metrics in Prometheus text exposition format
+    ///
+    /// Returns `None` if metrics are disabled.
+    pub fn export_prometheus(&self) -> Option<String> {
+        self.http_client.export_prometheus()
+    }
+
+    /// Receive each request metric sample as it is recorded
+    ///
+    /// Returns `None` if metrics are disabled. See `MetricsCollector::subscribe`.
+    pub fn subscribe_metrics(&self) -> Option<tokio::sync::broadcast::Receiver<crate::utils::metrics::MetricSample>> {
+        self.http_client.subscribe_metrics()
+    }
+
+    /// Get request metrics grouped by the value of a `RequestOptions` tag
+    ///
+    /// Returns `None` if metrics are disabled.
+    pub fn metrics_by_tag(&self, key: &str) -> Option<std::collections::HashMap<String, crate::utils::MetricStats>> {
+        self.http_client.get_metrics_by_tag(key)
+    }
+
+    /// Estimate the quota units an operation will consume
+    pub fn estimated_cost(&self, operation: &Operation) -> u32 {
+        operation.cost()
+    }
+
+    /// Get the quota units consumed by this client so far
+    ///
+    /// Each request sent counts as one unit and each subscription opened over
+    /// SSE as one more. Responses served from the cache are not counted.
+    pub fn quota_consumed(&self) -> u64 {
+        self.http_client.quota_consumed()
+    }
+
+    /// Publish a message, also reporting how long it took and how many attempts it made
+    ///
+    /// Takes the same arguments as `publish_message`. The timing is measured
+    /// for this call alone, without enabling the metrics collector.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn timed_publish(
+        &self,
+        channel: &str,
+        message: &str,
+        priority: Option<MessagePriority>,
+        sender: Option<&str>,
+        cache: Option<bool>,
+        encrypted: Option<bool>,
+        signature: Option<&str>,
+    ) -> Result<Timed<crate::types::api::MessagePublishResponse>> {
+        timed(self.publish_message(channel, message, priority, sender, cache, encrypted, signature)).await
+    }
+
+    /// Decode the claims of a JWT API key without verifying its signature
+    ///
+    /// Lets callers check `exp` or `scope` before making a request that would
+    /// be rejected. Returns a `ValidationError` if the key is not a JWT.
+    pub fn api_key_claims(&self) -> Result<ApiKeyClaims> {
+        ApiKeyClaims::decode(&self.http_client.config().api_key)
+    }
+
+    /// Decode the claims of a JWT API key, verifying its HS256 signature with `key`
+    pub fn api_key_claims_verified(&self, key: &[u8]) -> Result<ApiKeyClaims> {
+        ApiKeyClaims::decode_verified(&self.http_client.config().api_key, key)
+    }
+
+    /// Get the permissions the API key is known to have, if any
+    pub fn known_permissions(&self) -> Option<Vec<crate::types::api::Permission>> {
+        self.http_client.known_permissions()
+    }
+
+    /// Record the permissions the API key has, e.g. from a fetched `ApiKeyInfo`
+    ///
+    /// Used by `enforce_local_permissions`; `None` marks them as unknown.
+    pub fn set_known_permissions(&self, permissions: Option<Vec<crate::types::api::Permission>>) {
+        self.http_client.set_known_permissions(permissions)
+    }
+
+    /// Abort every request in flight, e.g. during shutdown
+    ///
+    /// Each resolves with `SecureNotifyError::Cancelled`, without waiting for
+    /// a response or retry, and streaming listings end with that error.
+    /// Requests made afterwards proceed normally. Subscriptions are not
+    /// affected; use `unsubscribe` for those.
+    pub fn cancel_all_requests(&self) {
+        self.http_client.cancel_all_requests()
+    }
+
+    /// Get the local connection health of a channel's subscription
+    ///
+    /// Combines the connection state, the time since the last message and
+    /// the reconnect count as seen by this client, unlike the server's view
+    /// from `list_subscriptions`. Returns `None` if the channel has no
+    /// running SSE subscription opened through this client; long-poll-only
+    /// subscriptions are not reported.
+    pub async fn subscription_health(&self, channel_id: &str) -> Option<crate::utils::SubscriptionHealth> {
+        self.http_client.subscriptions().health(channel_id).await
+    }
+
     /// Get the API key (masked)
     pub fn api_key_masked(&self) -> String {
         let api_key = &self.http_client.config().api_key;
@@ -81,6 +203,32 @@ pub struct ClientBuilder {
     enable_metrics: bool,
     enable_cache: bool,
     enable_deduplication: bool,
+    priority_rate_limits: HashMap<MessagePriority, (f64, u32)>,
+    default_priority: Option<MessagePriority>,
+    default_headers: http::HeaderMap,
+    interceptors: InterceptorChain,
+    on_request_complete: Option<RequestCompleteHook>,
+    on_retries_exhausted: Option<RetriesExhaustedHook>,
+    http_version: HttpVersion,
+    signer: Option<Arc<dyn RequestSigner>>,
+    retry_serialization_errors: bool,
+    warm_up_path: String,
+    strict_warm_up: bool,
+    metadata_validator: Option<MetadataValidator>,
+    longpoll_fallback_after: Option<u32>,
+    adaptive_concurrency: Option<(usize, usize)>,
+    pinned_certificate: Option<[u8; 32]>,
+    root_certificates: Vec<Vec<u8>>,
+    ordered_publish: bool,
+    trace_propagation: bool,
+    batch_policy: BatchPolicy,
+    safe_post_retries: bool,
+    publish_dedup_window: Option<std::time::Duration>,
+    response_verification_key: Option<[u8; 32]>,
+    field_case: FieldCase,
+    enforce_local_permissions: bool,
+    request_id_generator: Option<RequestIdGenerator>,
+    check_content_type: bool,
 }
 
 impl Default for ClientBuilder {
@@ -103,6 +251,32 @@ impl ClientBuilder {
             enable_metrics: false,
             enable_cache: false,
             enable_deduplication: false,
+            priority_rate_limits: HashMap::new(),
+            default_priority: None,
+            default_headers: http::HeaderMap::new(),
+            interceptors: InterceptorChain::new(),
+            on_request_complete: None,
+            on_retries_exhausted: None,
+            http_version: HttpVersion::Auto,
+            signer: None,
+            retry_serialization_errors: false,
+            warm_up_path: String::new(),
+            strict_warm_up: false,
+            metadata_validator: None,
+            longpoll_fallback_after: None,
+            adaptive_concurrency: None,
+            pinned_certificate: None,
+            root_certificates: Vec::new(),
+            ordered_publish: false,
+            trace_propagation: false,
+            batch_policy: BatchPolicy::BestEffort,
+            safe_post_retries: false,
+            publish_dedup_window: None,
+            response_verification_key: None,
+            field_case: FieldCase::Snake,
+            enforce_local_permissions: false,
+            request_id_generator: None,
+            check_content_type: true,
         }
     }
 
@@ -148,27 +322,377 @@ impl ClientBuilder {
         self
     }
 
-    /// Build the client
-    pub fn build(self) -> Result<SecureNotifyClient> {
+    /// Apply a retry preset, replacing the retry count, delays and multiplier
+    pub fn retry_preset(mut self, preset: RetryPreset) -> Self {
+        let config = preset.config();
+        self.max_retries = config.max_retries;
+        self.initial_delay_ms = config.initial_delay.as_millis() as u64;
+        self.max_delay_ms = config.max_delay.as_millis() as u64;
+        self.backoff_multiplier = config.backoff_multiplier;
+        self
+    }
+
+    /// Limit publishing for a message priority with a token bucket
+    ///
+    /// `rate` is the number of messages allowed per second and `burst` the
+    /// number that may be sent at once. Priorities without a limit (including
+    /// `Critical`, unless set here) are never throttled.
+    pub fn priority_rate_limit(mut self, priority: MessagePriority, rate: f64, burst: u32) -> Self {
+        self.priority_rate_limits.insert(priority, (rate, burst));
+        self
+    }
+
+    /// Set the priority used when a message is published without one
+    ///
+    /// An explicit per-call priority still takes precedence.
+    pub fn default_priority(mut self, priority: MessagePriority) -> Self {
+        self.default_priority = Some(priority);
+        self
+    }
+
+    /// Add a header sent with every request
+    pub fn header(mut self, name: http::HeaderName, value: http::HeaderValue) -> Self {
+        self.default_headers.insert(name, value);
+        self
+    }
+
+    /// Add headers sent with every request
+    pub fn headers(mut self, headers: http::HeaderMap) -> Self {
+        self.default_headers.extend(headers);
+        self
+    }
+
+    /// Add an interceptor run on every outgoing request
+    ///
+    /// Interceptors run in the order they are added and may modify the
+    /// request method, URI and headers.
+    pub fn interceptor(mut self, interceptor: impl RequestInterceptor + 'static) -> Self {
+        self.interceptors.push(Arc::new(interceptor));
+        self
+    }
+
+    /// Set a callback invoked once per completed request
+    ///
+    /// The callback runs after all retries with the final outcome, and is
+    /// intended for custom telemetry beyond the built-in metrics.
+    pub fn on_request_complete(mut self, hook: Arc<dyn Fn(&RequestOutcome) + Send + Sync>) -> Self {
+        self.on_request_complete = Some(RequestCompleteHook(hook));
+        self
+    }
+
+    /// Set a callback invoked when a request fails after retrying
+    ///
+    /// Called with the endpoint path, the error of the final attempt and
+    /// the number of attempts made, for raising alerts. It fires only when
+    /// the retry budget runs out; a non-retryable error ends the request
+    /// without invoking it.
+    pub fn on_retries_exhausted(mut self, hook: Arc<RetriesExhaustedFn>) -> Self {
+        self.on_retries_exhausted = Some(RetriesExhaustedHook(hook));
+        self
+    }
+
+    /// Set the HTTP protocol version (default: `HttpVersion::Auto`)
+    pub fn http_version(mut self, version: HttpVersion) -> Self {
+        self.http_version = version;
+        self
+    }
+
+    /// Sign every request, e.g. with an `HmacSigner` for API gateways
+    ///
+    /// Each attempt, including retries, is signed right before it is sent.
+    pub fn signer(mut self, signer: Arc<dyn RequestSigner>) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+
+    /// Retry requests whose 2xx response body fails to deserialize (default: false)
+    ///
+    /// Useful when a server under load occasionally sends truncated bodies.
+    /// Leave it off to surface schema mismatches immediately.
+    pub fn retry_serialization_errors(mut self, enabled: bool) -> Self {
+        self.retry_serialization_errors = enabled;
+        self
+    }
+
+    /// Set the path probed by `warm_up`, e.g. a health endpoint (default: the base URL)
+    pub fn warm_up_path(mut self, path: impl Into<String>) -> Self {
+        self.warm_up_path = path.into();
+        self
+    }
+
+    /// Make `warm_up` fail on non-success responses (default: false)
+    ///
+    /// By default any response counts, since it proves the connection is open.
+    pub fn strict_warm_up(mut self, strict: bool) -> Self {
+        self.strict_warm_up = strict;
+        self
+    }
+
+    /// Validate metadata locally before `create_channel` and `register_public_key`
+    ///
+    /// The validator runs only when metadata is present. Returning `Err`
+    /// rejects the call with a `ValidationError` without contacting the server.
+    pub fn metadata_validator(mut self, validator: Arc<MetadataValidatorFn>) -> Self {
+        self.metadata_validator = Some(MetadataValidator(validator));
+        self
+    }
+
+    /// Generate `X-Request-Id` values with `generator` instead of random UUIDs
+    ///
+    /// The generator is called once per logical request; retries of that
+    /// request reuse its ID. Values that are not valid header values are
+    /// not sent.
+    pub fn request_id_generator(mut self, generator: Arc<dyn Fn() -> String + Send + Sync>) -> Self {
+        self.request_id_generator = Some(RequestIdGenerator(generator));
+        self
+    }
+
+    /// Check the `Content-Type` of responses expected to be JSON (default: enabled)
+    ///
+    /// A non-empty response declaring another type, such as a proxy's HTML
+    /// error page served with 200, then fails with
+    /// `SecureNotifyError::UnexpectedContentType` carrying the start of the
+    /// body instead of a JSON parse error. Responses without a
+    /// `Content-Type` are decoded as usual. Disable for servers that
+    /// mislabel JSON bodies.
+    pub fn check_content_type(mut self, enabled: bool) -> Self {
+        self.check_content_type = enabled;
+        self
+    }
+
+    /// Fall back to long-polling when SSE fails to connect `attempts` times
+    ///
+    /// Applies to all `subscribe*` methods. Useful where proxies block SSE.
+    pub fn longpoll_fallback_after(mut self, attempts: u32) -> Self {
+        self.longpoll_fallback_after = Some(attempts.max(1));
+        self
+    }
+
+    /// Adapt the number of concurrent requests between `min` and `max`
+    ///
+    /// The limit starts at `max`, halves on timeouts, 5xx responses and
+    /// latency spikes, and grows back gradually while requests are healthy.
+    pub fn adaptive_concurrency(mut self, min: usize, max: usize) -> Self {
+        self.adaptive_concurrency = Some((min, max));
+        self
+    }
+
+    /// Pin the server's leaf certificate by its SHA-256 fingerprint
+    ///
+    /// The fingerprint is checked during the TLS handshake, so a server whose
+    /// certificate does not hash to `sha256` fails with a `ConnectionError`
+    /// before any request, API key included, is sent. This covers SSE
+    /// connections too. The regular certificate chain validation still
+    /// applies, against the Mozilla root set plus any roots added with
+    /// [`add_root_certificate`](Self::add_root_certificate). Pinned clients
+    /// use rustls and refuse plain HTTP URLs. Requires the `cert-pinning`
+    /// feature.
+    #[cfg(feature = "cert-pinning")]
+    pub fn pin_certificate(mut self, sha256: [u8; 32]) -> Self {
+        self.pinned_certificate = Some(sha256);
+        self
+    }
+
+    /// Trust a DER-encoded root certificate in addition to the default roots
+    ///
+    /// Useful for private CAs and self-signed test servers. Applies to SSE
+    /// connections as well.
+    pub fn add_root_certificate(mut self, der: Vec<u8>) -> Self {
+        self.root_certificates.push(der);
+        self
+    }
+
+    /// Publish to each channel strictly in call order
+    ///
+    /// Publishes to the same channel wait for the previous one, including its
+    /// retries, to finish. Publishes to different channels still run
+    /// concurrently.
+    pub fn ordered_publish(mut self, enabled: bool) -> Self {
+        self.ordered_publish = enabled;
+        self
+    }
+
+    /// Send W3C trace context headers with every request
+    ///
+    /// Each request carries a `traceparent` header. Inside
+    /// [`TraceContext::scope`](crate::utils::TraceContext::scope) requests
+    /// join the ambient trace and forward its `tracestate`; otherwise each
+    /// request starts a new trace. The trace id is reported on
+    /// [`RequestOutcome`](crate::utils::RequestOutcome).
+    pub fn trace_propagation(mut self, enabled: bool) -> Self {
+        self.trace_propagation = enabled;
+        self
+    }
+
+    /// Choose how batch operations handle failing items
+    ///
+    /// With [`BatchPolicy::FailFast`], `publish_fanout` returns the first
+    /// error and cancels the publishes still in flight instead of reporting
+    /// one result per channel. Publishes already sent are not rolled back.
+    pub fn batch_policy(mut self, policy: BatchPolicy) -> Self {
+        self.batch_policy = policy;
+        self
+    }
+
+    /// Retry POST requests only when they provably never reached the server
+    ///
+    /// POSTs are then retried only if the connection could not be
+    /// established (refused, DNS or TLS failure, connect timeout). Error
+    /// responses, timeouts after sending and interrupted responses are
+    /// returned as-is, so a publish is never sent twice. Other methods keep
+    /// the normal retry behavior.
+    pub fn safe_post_retries(mut self, enabled: bool) -> Self {
+        self.safe_post_retries = enabled;
+        self
+    }
+
+    /// Suppress identical publishes repeated within `window`
+    ///
+    /// A publish with the same channel and message fields as one that
+    /// succeeded less than `window` ago returns the earlier response
+    /// without contacting the server, guarding against accidental
+    /// double-sends such as a double-clicked button.
+    pub fn publish_dedup_window(mut self, window: std::time::Duration) -> Self {
+        self.publish_dedup_window = Some(window);
+        self
+    }
+
+    /// Require responses to be signed with this Ed25519 public key
+    ///
+    /// Every successful response must carry an `X-Signature` header holding
+    /// the base64 signature of its body. A missing or invalid signature
+    /// fails the call with `SecureNotifyError::SignatureVerificationError`
+    /// before the body is parsed. Streaming listings and calls that discard
+    /// the body are not verified.
+    pub fn response_verification_key(mut self, public_key: [u8; 32]) -> Self {
+        self.response_verification_key = Some(public_key);
+        self
+    }
+
+    /// Set the casing of request body field names (default: snake_case)
+    ///
+    /// Use `FieldCase::Camel` for API versions that expect camelCase
+    /// fields. Only the top-level fields of a request are renamed, so
+    /// user-supplied `metadata` keys are sent unchanged.
+    pub fn field_case(mut self, case: FieldCase) -> Self {
+        self.field_case = case;
+        self
+    }
+
+    /// Reject operations the API key is known not to permit before sending
+    ///
+    /// Permissions come from the `scope` claim of a JWT key or from
+    /// `SecureNotifyClient::set_known_permissions`. A publish without the
+    /// publish permission, or a subscribe without the subscribe permission,
+    /// then fails with `SecureNotifyError::AuthError` locally. While the
+    /// permissions are unknown, operations are sent as usual.
+    pub fn enforce_local_permissions(mut self, enabled: bool) -> Self {
+        self.enforce_local_permissions = enabled;
+        self
+    }
+
+    /// Enable per-endpoint request metrics
+    pub fn enable_metrics(mut self, enabled: bool) -> Self {
+        self.enable_metrics = enabled;
+        self
+    }
+
+    /// Check every setting, collecting all problems rather than stopping at the first
+    fn validate(&self) -> Result<()> {
+        let mut problems = Vec::new();
+
         if self.api_key.is_empty() {
-            return Err(SecureNotifyError::AuthError(
-                "API key is required".to_string(),
+            problems.push("API key is required".to_string());
+        }
+        match url::Url::parse(&self.base_url) {
+            Ok(url) if !matches!(url.scheme(), "http" | "https") => {
+                problems.push(format!("base URL `{}` must use http or https", self.base_url));
+            }
+            Ok(_) => {}
+            Err(e) => problems.push(format!("base URL `{}` is invalid: {}", self.base_url, e)),
+        }
+        if self.timeout.is_zero() {
+            problems.push("timeout must be greater than zero".to_string());
+        }
+        if !self.backoff_multiplier.is_finite() || self.backoff_multiplier < 1.0 {
+            problems.push(format!("backoff multiplier must be at least 1.0, got {}", self.backoff_multiplier));
+        }
+        if self.initial_delay_ms > self.max_delay_ms {
+            problems.push(format!(
+                "initial retry delay ({} ms) exceeds the maximum delay ({} ms)",
+                self.initial_delay_ms, self.max_delay_ms
             ));
         }
+        if let Some((min, max)) = self.adaptive_concurrency {
+            if max == 0 || min > max {
+                problems.push(format!("adaptive concurrency bounds ({}, {}) must satisfy min <= max and max > 0", min, max));
+            }
+        }
+        let mut rate_problems: Vec<String> = self
+            .priority_rate_limits
+            .iter()
+            .filter(|(_, (rate, burst))| !rate.is_finite() || *rate <= 0.0 || *burst == 0)
+            .map(|(priority, _)| format!("rate limit for {:?} priority must have a positive rate and burst", priority))
+            .collect();
+        rate_problems.sort();
+        problems.extend(rate_problems);
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(SecureNotifyError::ValidationError(format!(
+                "Invalid client configuration: {}",
+                problems.join("; ")
+            )))
+        }
+    }
+
+    /// Build the client
+    ///
+    /// All settings are validated first; if any are invalid, a single
+    /// `ValidationError` lists every problem.
+    pub fn build(self) -> Result<SecureNotifyClient> {
+        self.validate()?;
 
         Ok(SecureNotifyClient {
-            http_client: Arc::new(HttpClient::with_config(
-                &self.base_url,
-                &self.api_key,
-                self.timeout,
-                self.max_retries,
-                self.initial_delay_ms,
-                self.max_delay_ms,
-                self.backoff_multiplier,
-                self.enable_metrics,
-                self.enable_cache,
-                self.enable_deduplication,
-            )?),
+            http_client: Arc::new(HttpClient::from_config(HttpClientConfig {
+                base_url: self.base_url,
+                api_key: self.api_key,
+                timeout: self.timeout,
+                max_retries: self.max_retries,
+                initial_delay_ms: self.initial_delay_ms,
+                max_delay_ms: self.max_delay_ms,
+                backoff_multiplier: self.backoff_multiplier,
+                enable_metrics: self.enable_metrics,
+                enable_cache: self.enable_cache,
+                enable_deduplication: self.enable_deduplication,
+                priority_rate_limits: self.priority_rate_limits,
+                default_priority: self.default_priority,
+                default_headers: self.default_headers,
+                interceptors: self.interceptors,
+                on_request_complete: self.on_request_complete,
+                on_retries_exhausted: self.on_retries_exhausted,
+                http_version: self.http_version,
+                signer: self.signer,
+                retry_serialization_errors: self.retry_serialization_errors,
+                warm_up_path: self.warm_up_path,
+                strict_warm_up: self.strict_warm_up,
+                metadata_validator: self.metadata_validator,
+                longpoll_fallback_after: self.longpoll_fallback_after,
+                adaptive_concurrency: self.adaptive_concurrency,
+                pinned_certificate: self.pinned_certificate,
+                root_certificates: self.root_certificates,
+                ordered_publish: self.ordered_publish,
+                trace_propagation: self.trace_propagation,
+                batch_policy: self.batch_policy,
+                safe_post_retries: self.safe_post_retries,
+                publish_dedup_window: self.publish_dedup_window,
+                response_verification_key: self.response_verification_key,
+                field_case: self.field_case,
+                enforce_local_permissions: self.enforce_local_permissions,
+                request_id_generator: self.request_id_generator,
+                check_content_type: self.check_content_type,
+            })?),
         })
     }
 }
@@ -190,6 +714,17 @@ macro_rules! implement_managers {
                     .await
             }
 
+            async fn register_public_key_auto(
+                &self,
+                public_key: &str,
+                algorithm: &str,
+                metadata: Option<serde_json::Value>,
+            ) -> Result<String> {
+                KeyManagerImpl::new(self.http_client.clone())
+                    .register_public_key_auto(public_key, algorithm, metadata)
+                    .await
+            }
+
             async fn get_public_key(&self, channel_id: &str) -> Result<crate::types::api::PublicKeyInfo> {
                 KeyManagerImpl::new(self.http_client.clone())
                     .get_public_key(channel_id)
@@ -206,6 +741,15 @@ macro_rules! implement_managers {
                     .await
             }
 
+            async fn list_all_public_keys(
+                &self,
+                page_size: u32,
+            ) -> Result<Vec<crate::types::api::PublicKeyInfo>> {
+                KeyManagerImpl::new(self.http_client.clone())
+                    .list_all_public_keys(page_size)
+                    .await
+            }
+
             async fn revoke_public_key(&self, channel_id: &str) -> Result<()> {
                 KeyManagerImpl::new(self.http_client.clone())
                     .revoke_public_key(channel_id)
@@ -227,6 +771,18 @@ macro_rules! implement_managers {
                     .await
             }
 
+            async fn create_channel_if_absent(
+                &self,
+                name: &str,
+                channel_type: &str,
+                description: Option<&str>,
+                metadata: Option<serde_json::Value>,
+            ) -> Result<crate::types::api::ChannelCreateResponse> {
+                ChannelManagerImpl::new(self.http_client.clone())
+                    .create_channel_if_absent(name, channel_type, description, metadata)
+                    .await
+            }
+
             async fn get_channel(&self, channel_id: &str) -> Result<crate::types::api::ChannelInfo> {
                 ChannelManagerImpl::new(self.http_client.clone())
                     .get_channel(channel_id)
@@ -244,11 +800,62 @@ macro_rules! implement_managers {
                     .await
             }
 
+            async fn list_all_channels(
+                &self,
+                channel_type: Option<&str>,
+                page_size: u32,
+            ) -> Result<Vec<crate::types::api::ChannelInfo>> {
+                ChannelManagerImpl::new(self.http_client.clone())
+                    .list_all_channels(channel_type, page_size)
+                    .await
+            }
+
+            fn list_channels_streaming(
+                &self,
+                channel_type: Option<&str>,
+                limit: Option<u32>,
+                offset: Option<u32>,
+            ) -> futures::stream::BoxStream<'static, Result<crate::types::api::ChannelInfo>> {
+                ChannelManagerImpl::new(self.http_client.clone())
+                    .list_channels_streaming(channel_type, limit, offset)
+            }
+
+            async fn update_channel(
+                &self,
+                channel_id: &str,
+                name: Option<&str>,
+                description: Option<&str>,
+                metadata: Option<serde_json::Value>,
+            ) -> Result<crate::types::api::ChannelInfo> {
+                ChannelManagerImpl::new(self.http_client.clone())
+                    .update_channel(channel_id, name, description, metadata)
+                    .await
+            }
+
             async fn delete_channel(&self, channel_id: &str) -> Result<()> {
                 ChannelManagerImpl::new(self.http_client.clone())
                     .delete_channel(channel_id)
                     .await
             }
+
+            async fn transfer_channel(
+                &self,
+                channel_id: &str,
+                new_owner: &str,
+            ) -> Result<crate::types::api::ChannelInfo> {
+                ChannelManagerImpl::new(self.http_client.clone())
+                    .transfer_channel(channel_id, new_owner)
+                    .await
+            }
+
+            async fn check_channel_access(
+                &self,
+                channel_id: &str,
+            ) -> Result<crate::types::api::ChannelAccess> {
+                ChannelManagerImpl::new(self.http_client.clone())
+                    .check_channel_access(channel_id)
+                    .await
+            }
         }
 
         #[async_trait]
@@ -268,15 +875,75 @@ macro_rules! implement_managers {
                     .await
             }
 
+            async fn publish_fanout(
+                &self,
+                channels: &[&str],
+                message: &str,
+                priority: Option<MessagePriority>,
+            ) -> Result<Vec<Result<crate::types::api::MessagePublishResponse>>> {
+                PublishManagerImpl::new(self.http_client.clone())
+                    .publish_fanout(channels, message, priority)
+                    .await
+            }
+
+            async fn publish_batch_signed(
+                &self,
+                signer: &crate::utils::signing::MessageSigner,
+                items: &[(&str, &str)],
+                priority: Option<MessagePriority>,
+            ) -> Result<Vec<Result<crate::types::api::MessagePublishResponse>>> {
+                PublishManagerImpl::new(self.http_client.clone())
+                    .publish_batch_signed(signer, items, priority)
+                    .await
+            }
+
             async fn get_queue_status(&self, channel: &str) -> Result<crate::types::api::QueueStatus> {
                 PublishManagerImpl::new(self.http_client.clone())
                     .get_queue_status(channel)
                     .await
             }
 
-            async fn get_message(&self, channel: &str, message_id: &str) -> Result<crate::types::api::MessageInfo> {
+            async fn get_message(
+                &self,
+                channel: &str,
+                message_id: &str,
+                validators: Option<&crate::types::api::Validators>,
+            ) -> Result<crate::types::api::Conditional<crate::types::api::MessageInfo>> {
+                PublishManagerImpl::new(self.http_client.clone())
+                    .get_message(channel, message_id, validators)
+                    .await
+            }
+
+            async fn list_messages(
+                &self,
+                channel: &str,
+                limit: Option<u32>,
+            ) -> Result<Vec<crate::types::api::MessageInfo>> {
                 PublishManagerImpl::new(self.http_client.clone())
-                    .get_message(channel, message_id)
+                    .list_messages(channel, limit)
+                    .await
+            }
+
+            async fn list_messages_after(
+                &self,
+                channel: &str,
+                after_id: &str,
+                limit: Option<u32>,
+            ) -> Result<Vec<crate::types::api::MessageInfo>> {
+                PublishManagerImpl::new(self.http_client.clone())
+                    .list_messages_after(channel, after_id, limit)
+                    .await
+            }
+
+            async fn ack_messages(&self, channel: &str, message_ids: &[&str]) -> Result<()> {
+                PublishManagerImpl::new(self.http_client.clone())
+                    .ack_messages(channel, message_ids)
+                    .await
+            }
+
+            async fn nack_messages(&self, channel: &str, message_ids: &[&str]) -> Result<()> {
+                PublishManagerImpl::new(self.http_client.clone())
+                    .nack_messages(channel, message_ids)
                     .await
             }
         }
@@ -292,17 +959,83 @@ macro_rules! implement_managers {
                     .await
             }
 
+            async fn subscribe_filtered(
+                &self,
+                channel_id: &str,
+                event_types: &[crate::SseEventType],
+            ) -> Result<tokio::sync::mpsc::Receiver<SseMessage>> {
+                SubscribeManagerImpl::new(self.http_client.clone())
+                    .subscribe_filtered(channel_id, event_types)
+                    .await
+            }
+
+            async fn subscribe_with_filter(
+                &self,
+                channel_id: &str,
+                filter: &str,
+            ) -> Result<tokio::sync::mpsc::Receiver<SseMessage>> {
+                SubscribeManagerImpl::new(self.http_client.clone())
+                    .subscribe_with_filter(channel_id, filter)
+                    .await
+            }
+
+            async fn subscribe_with_history(
+                &self,
+                channel_id: &str,
+                history_limit: u32,
+            ) -> Result<tokio::sync::mpsc::Receiver<SseMessage>> {
+                SubscribeManagerImpl::new(self.http_client.clone())
+                    .subscribe_with_history(channel_id, history_limit)
+                    .await
+            }
+
+            async fn subscribe_ready(
+                &self,
+                channel_id: &str,
+                timeout: std::time::Duration,
+            ) -> Result<tokio::sync::mpsc::Receiver<SseMessage>> {
+                SubscribeManagerImpl::new(self.http_client.clone())
+                    .subscribe_ready(channel_id, timeout)
+                    .await
+            }
+
+            async fn subscribe_longpoll(
+                &self,
+                channel_id: &str,
+            ) -> Result<tokio::sync::mpsc::Receiver<SseMessage>> {
+                SubscribeManagerImpl::new(self.http_client.clone())
+                    .subscribe_longpoll(channel_id)
+                    .await
+            }
+
             async fn unsubscribe(&self, channel_id: &str) -> Result<()> {
                 SubscribeManagerImpl::new(self.http_client.clone())
                     .unsubscribe(channel_id)
                     .await
             }
 
+            async fn unsubscribe_all(&self) -> Result<()> {
+                SubscribeManagerImpl::new(self.http_client.clone())
+                    .unsubscribe_all()
+                    .await
+            }
+
             async fn list_subscriptions(&self) -> Result<Vec<crate::types::api::SubscriptionInfo>> {
                 SubscribeManagerImpl::new(self.http_client.clone())
                     .list_subscriptions()
                     .await
             }
+
+            async fn list_subscription_events(
+                &self,
+                subscription_id: &str,
+                limit: Option<u32>,
+                offset: Option<u32>,
+            ) -> Result<Vec<crate::types::api::SubscriptionEvent>> {
+                SubscribeManagerImpl::new(self.http_client.clone())
+                    .list_subscription_events(subscription_id, limit, offset)
+                    .await
+            }
         }
 
         #[async_trait]
@@ -319,6 +1052,18 @@ macro_rules! implement_managers {
                     .await
             }
 
+            async fn create_api_key_expiring_in(
+                &self,
+                name: &str,
+                ttl: std::time::Duration,
+                user_id: Option<&str>,
+                permissions: Option<Vec<&str>>,
+            ) -> Result<crate::types::api::ApiKeyCreateResponse> {
+                ApiKeyManagerImpl::new(self.http_client.clone())
+                    .create_api_key_expiring_in(name, ttl, user_id, permissions)
+                    .await
+            }
+
             async fn get_api_key(&self, key_id: &str) -> Result<crate::types::api::ApiKeyInfo> {
                 ApiKeyManagerImpl::new(self.http_client.clone())
                     .get_api_key(key_id)
@@ -335,11 +1080,29 @@ macro_rules! implement_managers {
                     .await
             }
 
+            async fn list_all_api_keys(
+                &self,
+                page_size: u32,
+            ) -> Result<Vec<crate::types::api::ApiKeyInfo>> {
+                ApiKeyManagerImpl::new(self.http_client.clone())
+                    .list_all_api_keys(page_size)
+                    .await
+            }
+
             async fn revoke_api_key(&self, key_id: &str) -> Result<()> {
                 ApiKeyManagerImpl::new(self.http_client.clone())
                     .revoke_api_key(key_id)
                     .await
             }
+
+            async fn list_accessible_channels(
+                &self,
+                api_key_id: &str,
+            ) -> Result<Vec<crate::types::api::ChannelInfo>> {
+                ApiKeyManagerImpl::new(self.http_client.clone())
+                    .list_accessible_channels(api_key_id)
+                    .await
+            }
         }
     };
 }
@@ -38,6 +38,13 @@
 use uniffi::prelude::*;
 
 /// FFI-safe error type for SecureNotify operations
+///
+/// Network, connection, timeout and serialization errors keep the error that
+/// caused them as an [`types::error::ErrorSource`], available through
+/// `std::error::Error::source()`, [`SecureNotifyError::error_source`] and
+/// [`SecureNotifyError::chain`]. The type is `Clone` but deliberately not
+/// `Copy`: the underlying errors are shared via `Arc` rather than flattened
+/// into strings, so the full cause chain survives for debugging.
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum SecureNotifyError {
     #[error("API error: {code} - {message}")]
@@ -45,33 +52,83 @@ pub enum SecureNotifyError {
         code: String,
         message: String,
         status: u16,
+        /// Structured details reported by the server, if any
+        details: Option<Box<crate::types::api::ApiErrorDetails>>,
     },
     #[error("Network error: {0}")]
-    NetworkError(String),
+    NetworkError(String, #[source] Option<crate::types::error::ErrorSource>),
     #[error("Connection error: {0}")]
-    ConnectionError(String),
+    ConnectionError(String, #[source] Option<crate::types::error::ErrorSource>),
     #[error("Timeout error: {0}")]
-    TimeoutError(String),
+    TimeoutError(String, #[source] Option<crate::types::error::ErrorSource>),
     #[error("Serialization error: {0}")]
-    SerializationError(String),
+    SerializationError(String, #[source] Option<crate::types::error::ErrorSource>),
+    #[error("Request serialization error: {0}")]
+    RequestSerializationError(String, #[source] Option<crate::types::error::ErrorSource>),
     #[error("Authentication error: {0}")]
     AuthError(String),
+    #[error("Response signature verification failed: {0}")]
+    SignatureVerificationError(String),
+    #[error("Conflict on {resource}: {message}")]
+    Conflict {
+        resource: String,
+        message: String,
+    },
+    #[error("Not found: {resource}: {message}")]
+    NotFound {
+        resource: String,
+        message: String,
+    },
+    #[error("Payload too large: {message}")]
+    PayloadTooLarge {
+        message: String,
+        /// Largest accepted payload in bytes, if the server said
+        limit: Option<u64>,
+    },
+    #[error("Unexpected content type {content_type} (expected JSON): {snippet}")]
+    UnexpectedContentType {
+        content_type: String,
+        /// The start of the response body
+        snippet: String,
+    },
+    #[error("Service unavailable: {message}")]
+    ServiceUnavailable {
+        message: String,
+        retry_after: Option<std::time::Duration>,
+    },
+    #[error("Validation error: {0}")]
+    ValidationError(String),
+    #[error("Request cancelled")]
+    Cancelled,
+    #[error("Retries exhausted after {attempts} attempts in {elapsed:?}: {source}")]
+    RetriesExhausted {
+        source: Box<SecureNotifyError>,
+        attempts: u32,
+        elapsed: std::time::Duration,
+    },
     #[error("Unknown error: {0}")]
     Unknown(String),
 }
 
-// Note: SecureNotifyError implements Clone via derive macro
-// For FFI compatibility, this is sufficient
-
 impl SecureNotifyError {
     pub fn code(&self) -> String {
         match self {
             Self::ApiError { code, .. } => code.clone(),
-            Self::NetworkError(msg) => format!("NETWORK_ERROR: {}", msg),
-            Self::ConnectionError(msg) => format!("CONNECTION_ERROR: {}", msg),
-            Self::TimeoutError(msg) => format!("TIMEOUT_ERROR: {}", msg),
-            Self::SerializationError(msg) => format!("SERIALIZATION_ERROR: {}", msg),
+            Self::NetworkError(msg, _) => format!("NETWORK_ERROR: {}", msg),
+            Self::ConnectionError(msg, _) => format!("CONNECTION_ERROR: {}", msg),
+            Self::TimeoutError(msg, _) => format!("TIMEOUT_ERROR: {}", msg),
+            Self::SerializationError(msg, _) => format!("SERIALIZATION_ERROR: {}", msg),
+            Self::RequestSerializationError(msg, _) => format!("REQUEST_SERIALIZATION_ERROR: {}", msg),
             Self::AuthError(msg) => format!("AUTH_ERROR: {}", msg),
+            Self::SignatureVerificationError(msg) => format!("SIGNATURE_VERIFICATION_ERROR: {}", msg),
+            Self::Conflict { resource, .. } => format!("CONFLICT: {}", resource),
+            Self::NotFound { resource, .. } => format!("NOT_FOUND: {}", resource),
+            Self::PayloadTooLarge { message, .. } => format!("PAYLOAD_TOO_LARGE: {}", message),
+            Self::UnexpectedContentType { content_type, .. } => format!("UNEXPECTED_CONTENT_TYPE: {}", content_type),
+            Self::ServiceUnavailable { message, .. } => format!("SERVICE_UNAVAILABLE: {}", message),
+            Self::ValidationError(msg) => format!("VALIDATION_ERROR: {}", msg),
+            Self::Cancelled => "CANCELLED".to_string(),
+            Self::RetriesExhausted { source, .. } => source.code(),
             Self::Unknown(msg) => format!("UNKNOWN_ERROR: {}", msg),
         }
     }
@@ -83,16 +140,80 @@ impl SecureNotifyError {
     pub fn status(&self) -> u16 {
         match self {
             Self::ApiError { status, .. } => *status,
+            Self::Conflict { .. } => 409,
+            Self::NotFound { .. } => 404,
+            Self::PayloadTooLarge { .. } => 413,
+            Self::ServiceUnavailable { .. } => 503,
+            Self::RetriesExhausted { source, .. } => source.status(),
             _ => 0,
         }
     }
 
     pub fn is_api_error(&self) -> bool {
-        matches!(self, Self::ApiError { .. })
+        matches!(self.root_cause(), Self::ApiError { .. })
     }
 
     pub fn is_network_error(&self) -> bool {
-        matches!(self, Self::NetworkError(..))
+        matches!(self.root_cause(), Self::NetworkError(..))
+    }
+
+    /// Check if the resource already exists (409 Conflict)
+    pub fn is_conflict(&self) -> bool {
+        matches!(self.root_cause(), Self::Conflict { .. })
+    }
+
+    /// Check if the resource does not exist (404 Not Found)
+    pub fn is_not_found(&self) -> bool {
+        matches!(self.root_cause(), Self::NotFound { .. })
+    }
+
+    /// Check if the request body was rejected as too large (413)
+    pub fn is_payload_too_large(&self) -> bool {
+        matches!(self.root_cause(), Self::PayloadTooLarge { .. })
+    }
+
+    /// Check if a JSON response arrived with another content type, such as a proxy's HTML error page
+    pub fn is_unexpected_content_type(&self) -> bool {
+        matches!(self.root_cause(), Self::UnexpectedContentType { .. })
+    }
+
+    /// Check if the request was aborted by `cancel_all_requests`
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self.root_cause(), Self::Cancelled)
+    }
+
+    /// Structured details reported by the server for an API error, if any
+    pub fn details(&self) -> Option<&crate::types::api::ApiErrorDetails> {
+        match self.root_cause() {
+            Self::ApiError { details, .. } => details.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Server-suggested delay before retrying, if any
+    pub fn retry_after(&self) -> Option<std::time::Duration> {
+        match self.root_cause() {
+            Self::ServiceUnavailable { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// Get the error that caused this one, if any
+    pub fn error_source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        std::error::Error::source(self)
+    }
+
+    /// Iterate over this error followed by each of its causes
+    pub fn chain(&self) -> impl Iterator<Item = &(dyn std::error::Error + 'static)> {
+        std::iter::successors(Some(self as &(dyn std::error::Error + 'static)), |e| e.source())
+    }
+
+    /// Get the underlying error, unwrapping `RetriesExhausted`
+    pub fn root_cause(&self) -> &SecureNotifyError {
+        match self {
+            Self::RetriesExhausted { source, .. } => source.root_cause(),
+            other => other,
+        }
     }
 }
 
@@ -105,33 +226,91 @@ impl SecureNotifyError {
             code,
             message,
             status,
+            details: None,
         }
     }
 
     #[uniffi::constructor]
     pub fn network_error(message: String) -> Self {
-        Self::NetworkError(message)
+        Self::NetworkError(message, None)
     }
 
     #[uniffi::constructor]
     pub fn connection_error(message: String) -> Self {
-        Self::ConnectionError(message)
+        Self::ConnectionError(message, None)
     }
 
     #[uniffi::constructor]
     pub fn timeout_error(message: String) -> Self {
-        Self::TimeoutError(message)
+        Self::TimeoutError(message, None)
     }
 
     #[uniffi::constructor]
     pub fn serialization_error(message: String) -> Self {
-        Self::SerializationError(message)
+        Self::SerializationError(message, None)
+    }
+
+    #[uniffi::constructor]
+    pub fn request_serialization_error(message: String) -> Self {
+        Self::RequestSerializationError(message, None)
     }
 
     #[uniffi::constructor]
     pub fn auth_error(message: String) -> Self {
         Self::AuthError(message)
     }
+
+    #[uniffi::constructor]
+    pub fn signature_verification_error(message: String) -> Self {
+        Self::SignatureVerificationError(message)
+    }
+
+    #[uniffi::constructor]
+    pub fn validation_error(message: String) -> Self {
+        Self::ValidationError(message)
+    }
+
+    #[uniffi::constructor]
+    pub fn conflict(resource: String, message: String) -> Self {
+        Self::Conflict { resource, message }
+    }
+
+    #[uniffi::constructor]
+    pub fn not_found(resource: String, message: String) -> Self {
+        Self::NotFound { resource, message }
+    }
+
+    #[uniffi::constructor]
+    pub fn payload_too_large(message: String, limit: Option<u64>) -> Self {
+        Self::PayloadTooLarge { message, limit }
+    }
+
+    #[uniffi::constructor]
+    pub fn unexpected_content_type(content_type: String, snippet: String) -> Self {
+        Self::UnexpectedContentType { content_type, snippet }
+    }
+
+    #[uniffi::constructor]
+    pub fn cancelled() -> Self {
+        Self::Cancelled
+    }
+
+    #[uniffi::constructor]
+    pub fn service_unavailable(message: String, retry_after_ms: Option<u64>) -> Self {
+        Self::ServiceUnavailable {
+            message,
+            retry_after: retry_after_ms.map(std::time::Duration::from_millis),
+        }
+    }
+
+    #[uniffi::constructor]
+    pub fn retries_exhausted(source: std::sync::Arc<SecureNotifyError>, attempts: u32, elapsed_ms: u64) -> Self {
+        Self::RetriesExhausted {
+            source: Box::new(source.as_ref().clone()),
+            attempts,
+            elapsed: std::time::Duration::from_millis(elapsed_ms),
+        }
+    }
 }
 
 /// Result type alias
@@ -161,6 +340,16 @@ impl MessagePriority {
             _ => Self::Bulk,
         }
     }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Critical => "critical",
+            Self::High => "high",
+            Self::Normal => "normal",
+            Self::Low => "low",
+            Self::Bulk => "bulk",
+        }
+    }
 }
 
 #[cfg(feature = "uniffi")]
@@ -313,7 +502,7 @@ pub mod client;
 
 
 // Re-export types from api module
-pub use types::api::{SseEvent, SseEventType};
+pub use types::api::{Conditional, MessageEnvelope, SseEvent, SseEventType, Validators};
 
 // Re-export ClientBuilder and SecureNotifyClient from client module
 pub use client::{ClientBuilder, SecureNotifyClient};
@@ -322,7 +511,7 @@ pub use client::{ClientBuilder, SecureNotifyClient};
 pub use utils::connection::SseMessage;
 
 
-/// SecureNotify Client for Rust
+/// Create a new client (convenience function for FFI)
 ///
 /// This is the main client for interacting with the SecureNotify API.
 /// It provides methods for key management, channel management, message publishing,
@@ -345,8 +534,6 @@ pub use utils::connection::SseMessage;
 ///     Ok(())
 /// }
 /// ```
-
-/// Create a new client (convenience function for FFI)
 // #[uniffi::export]
 pub fn create_client(base_url: String, api_key: String) -> Result<SecureNotifyClient> {
     SecureNotifyClient::builder()
@@ -361,6 +548,15 @@ pub fn create_client_with_defaults(api_key: String) -> Result<SecureNotifyClient
     create_client("https://api.securenotify.dev".to_string(), api_key)
 }
 
+/// Version of this SDK, sent in the `User-Agent` header as `SecureNotify-Rust/<version>`
+pub const SDK_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Get the SDK version (convenience function for FFI)
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+pub fn sdk_version() -> String {
+    SDK_VERSION.to_string()
+}
+
 // uniffi::include_scaffolding!("securenotify");
 
 #[cfg(test)]
@@ -4,8 +4,9 @@
 //! API Key manager for SecureNotify SDK
 
 use async_trait::async_trait;
-use crate::Result;
+use crate::{Result, SecureNotifyError};
 use crate::types::api::*;
+use crate::utils::query::QueryBuilder;
 
 /// Trait for API key management operations
 #[async_trait]
@@ -19,6 +20,19 @@ pub trait ApiKeyManager {
         expires_at: Option<&str>,
     ) -> Result<ApiKeyCreateResponse>;
 
+    /// Create a new API key that expires `ttl` from now
+    ///
+    /// The expiry is computed on the server's clock when a previous
+    /// response's `Date` header revealed it, otherwise on the local clock,
+    /// and sent as an RFC 3339 `expires_at`.
+    async fn create_api_key_expiring_in(
+        &self,
+        name: &str,
+        ttl: std::time::Duration,
+        user_id: Option<&str>,
+        permissions: Option<Vec<&str>>,
+    ) -> Result<ApiKeyCreateResponse>;
+
     /// Get API key information
     async fn get_api_key(&self, key_id: &str) -> Result<ApiKeyInfo>;
 
@@ -29,8 +43,16 @@ pub trait ApiKeyManager {
         offset: Option<u32>,
     ) -> Result<Vec<ApiKeyInfo>>;
 
+    /// List every API key, fetching `page_size` keys per request
+    async fn list_all_api_keys(&self, page_size: u32) -> Result<Vec<ApiKeyInfo>>;
+
     /// Revoke an API key
     async fn revoke_api_key(&self, key_id: &str) -> Result<()>;
+
+    /// List the channels an API key can publish or subscribe to
+    ///
+    /// An unknown key id fails with `SecureNotifyError::NotFound`.
+    async fn list_accessible_channels(&self, api_key_id: &str) -> Result<Vec<ChannelInfo>>;
 }
 
 /// Implementation of ApiKeyManager
@@ -61,12 +83,30 @@ impl ApiKeyManager for ApiKeyManagerImpl {
             expires_at: expires_at.map(|s| s.to_string()),
         };
 
-        self.http_client.post("api/keys", &request).await.map_err(|e| e.into())
+        self.http_client.post("api/keys", &request).await
+    }
+
+    async fn create_api_key_expiring_in(
+        &self,
+        name: &str,
+        ttl: std::time::Duration,
+        user_id: Option<&str>,
+        permissions: Option<Vec<&str>>,
+    ) -> Result<ApiKeyCreateResponse> {
+        let ttl = time::Duration::try_from(ttl)
+            .map_err(|_| SecureNotifyError::ValidationError(format!("API key lifetime {:?} is too long", ttl)))?;
+        let expires_at = self
+            .http_client
+            .server_now()
+            .checked_add(ttl)
+            .and_then(|at| at.format(&time::format_description::well_known::Rfc3339).ok())
+            .ok_or_else(|| SecureNotifyError::ValidationError("API key expiry is out of range".to_string()))?;
+        self.create_api_key(name, user_id, permissions, Some(&expires_at)).await
     }
 
     async fn get_api_key(&self, key_id: &str) -> Result<ApiKeyInfo> {
         let endpoint = format!("api/keys/{}", key_id);
-        self.http_client.get(&endpoint).await.map_err(|e| e.into())
+        self.http_client.get(&endpoint).await
     }
 
     async fn list_api_keys(
@@ -74,26 +114,38 @@ impl ApiKeyManager for ApiKeyManagerImpl {
         limit: Option<u32>,
         offset: Option<u32>,
     ) -> Result<Vec<ApiKeyInfo>> {
-        let mut endpoint = "api/keys".to_string();
-        let mut params = Vec::new();
-
-        if let Some(limit) = limit {
-            params.push(format!("limit={}", limit));
-        }
-        if let Some(offset) = offset {
-            params.push(format!("offset={}", offset));
-        }
-
-        if !params.is_empty() {
-            endpoint.push('?');
-            endpoint.push_str(&params.join("&"));
-        }
-
-        self.http_client.get(&endpoint).await.map_err(|e| e.into())
+        let endpoint = QueryBuilder::new()
+            .optional("limit", limit)
+            .optional("offset", offset)
+            .build("api/keys");
+
+        self.http_client.get(&endpoint).await
+    }
+
+    async fn list_all_api_keys(&self, page_size: u32) -> Result<Vec<ApiKeyInfo>> {
+        self.http_client
+            .get_all_pages(page_size, |limit, offset| {
+                QueryBuilder::new()
+                    .optional("limit", Some(limit))
+                    .optional("offset", Some(offset))
+                    .build("api/keys")
+            })
+            .await
     }
 
     async fn revoke_api_key(&self, key_id: &str) -> Result<()> {
         let endpoint = format!("api/keys/{}/revoke", key_id);
         self.http_client.post_empty(&endpoint).await
     }
+
+    async fn list_accessible_channels(&self, api_key_id: &str) -> Result<Vec<ChannelInfo>> {
+        let endpoint = format!("api/keys/{}/channels", api_key_id);
+        self.http_client.get(&endpoint).await.map_err(|e| match e {
+            SecureNotifyError::ApiError { status: 404, message, .. } => SecureNotifyError::NotFound {
+                resource: format!("keys/{}", api_key_id),
+                message,
+            },
+            other => other,
+        })
+    }
 }
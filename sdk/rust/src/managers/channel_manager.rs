@@ -4,8 +4,10 @@
 //! Channel manager for SecureNotify SDK
 
 use async_trait::async_trait;
-use crate::Result;
+use futures::stream::BoxStream;
+use crate::{Result, SecureNotifyError};
 use crate::types::api::*;
+use crate::utils::query::QueryBuilder;
 
 /// Trait for channel management operations
 #[async_trait]
@@ -19,6 +21,19 @@ pub trait ChannelManager {
         metadata: Option<serde_json::Value>,
     ) -> Result<ChannelCreateResponse>;
 
+    /// Create a channel only if it does not exist yet
+    ///
+    /// Sends `If-None-Match: *`, so the server refuses to create a channel
+    /// that already exists. That refusal (412) is returned as
+    /// `SecureNotifyError::Conflict`.
+    async fn create_channel_if_absent(
+        &self,
+        name: &str,
+        channel_type: &str,
+        description: Option<&str>,
+        metadata: Option<serde_json::Value>,
+    ) -> Result<ChannelCreateResponse>;
+
     /// Get channel information
     async fn get_channel(&self, channel_id: &str) -> Result<ChannelInfo>;
 
@@ -30,8 +45,58 @@ pub trait ChannelManager {
         offset: Option<u32>,
     ) -> Result<Vec<ChannelInfo>>;
 
+    /// List every channel, fetching `page_size` channels per request
+    ///
+    /// A page that fails with a retryable error such as 429 is retried with
+    /// backoff, and the listing resumes from that page.
+    async fn list_all_channels(
+        &self,
+        channel_type: Option<&str>,
+        page_size: u32,
+    ) -> Result<Vec<ChannelInfo>>;
+
+    /// List channels, yielding each channel as it is received
+    ///
+    /// Unlike `list_channels`, the response is parsed incrementally so large
+    /// listings are never fully buffered in memory.
+    fn list_channels_streaming(
+        &self,
+        channel_type: Option<&str>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> BoxStream<'static, Result<ChannelInfo>>;
+
+    /// Update a channel's name, description or metadata
+    ///
+    /// Only the fields given as `Some` are sent, so the others keep their
+    /// current values. Returns the updated channel, or a `ValidationError`
+    /// without sending anything if no field is given.
+    async fn update_channel(
+        &self,
+        channel_id: &str,
+        name: Option<&str>,
+        description: Option<&str>,
+        metadata: Option<serde_json::Value>,
+    ) -> Result<ChannelInfo>;
+
     /// Delete/deactivate a channel
     async fn delete_channel(&self, channel_id: &str) -> Result<()>;
+
+    /// Transfer a channel to another user
+    ///
+    /// Returns the channel with `creator` set to the new owner. A 403
+    /// response, when the caller may not transfer the channel, is returned
+    /// as `SecureNotifyError::AuthError`.
+    async fn transfer_channel(&self, channel_id: &str, new_owner: &str) -> Result<ChannelInfo>;
+
+    /// Check whether a channel can be published to and subscribed to
+    ///
+    /// Fetches the channel and combines its state with the permissions the
+    /// API key is known to have. A channel past its expiry, judged by the
+    /// server's clock, is reported as inactive. While the permissions are
+    /// unknown, an active channel counts as writable and readable. A missing
+    /// channel yields an all-false `ChannelAccess` rather than an error.
+    async fn check_channel_access(&self, channel_id: &str) -> Result<ChannelAccess>;
 }
 
 /// Implementation of ChannelManager
@@ -44,6 +109,32 @@ impl ChannelManagerImpl {
     pub fn new(http_client: std::sync::Arc<crate::utils::http::HttpClient>) -> Self {
         Self { http_client }
     }
+
+    /// Validate the metadata and build a channel creation request
+    fn create_request(
+        &self,
+        name: &str,
+        channel_type: &str,
+        description: Option<&str>,
+        metadata: Option<serde_json::Value>,
+    ) -> Result<ChannelCreateRequest> {
+        self.http_client.validate_metadata(metadata.as_ref())?;
+        Ok(ChannelCreateRequest {
+            name: name.to_string(),
+            channel_type: channel_type.to_string(),
+            description: description.map(|s| s.to_string()),
+            metadata,
+        })
+    }
+
+    /// Build the endpoint for listing channels
+    fn list_endpoint(channel_type: Option<&str>, limit: Option<u32>, offset: Option<u32>) -> String {
+        QueryBuilder::new()
+            .optional("type", channel_type)
+            .optional("limit", limit)
+            .optional("offset", offset)
+            .build("api/channels")
+    }
 }
 
 #[async_trait]
@@ -55,19 +146,26 @@ impl ChannelManager for ChannelManagerImpl {
         description: Option<&str>,
         metadata: Option<serde_json::Value>,
     ) -> Result<ChannelCreateResponse> {
-        let request = ChannelCreateRequest {
-            name: name.to_string(),
-            channel_type: channel_type.to_string(),
-            description: description.map(|s| s.to_string()),
-            metadata,
-        };
+        let request = self.create_request(name, channel_type, description, metadata)?;
+        self.http_client.post("api/channels", &request).await
+    }
 
-        self.http_client.post("api/channels", &request).await.map_err(|e| e.into())
+    async fn create_channel_if_absent(
+        &self,
+        name: &str,
+        channel_type: &str,
+        description: Option<&str>,
+        metadata: Option<serde_json::Value>,
+    ) -> Result<ChannelCreateResponse> {
+        let request = self.create_request(name, channel_type, description, metadata)?;
+        let mut headers = http::HeaderMap::new();
+        headers.insert(http::header::IF_NONE_MATCH, http::HeaderValue::from_static("*"));
+        self.http_client.post_with_headers("api/channels", &request, headers).await
     }
 
     async fn get_channel(&self, channel_id: &str) -> Result<ChannelInfo> {
         let endpoint = format!("api/channels/{}", channel_id);
-        self.http_client.get(&endpoint).await.map_err(|e| e.into())
+        self.http_client.get(&endpoint).await
     }
 
     async fn list_channels(
@@ -76,29 +174,95 @@ impl ChannelManager for ChannelManagerImpl {
         limit: Option<u32>,
         offset: Option<u32>,
     ) -> Result<Vec<ChannelInfo>> {
-        let mut endpoint = "api/channels".to_string();
-        let mut params = Vec::new();
+        let endpoint = Self::list_endpoint(channel_type, limit, offset);
+        self.http_client.get(&endpoint).await
+    }
 
-        if let Some(channel_type) = channel_type {
-            params.push(format!("type={}", channel_type));
-        }
-        if let Some(limit) = limit {
-            params.push(format!("limit={}", limit));
-        }
-        if let Some(offset) = offset {
-            params.push(format!("offset={}", offset));
-        }
+    async fn list_all_channels(
+        &self,
+        channel_type: Option<&str>,
+        page_size: u32,
+    ) -> Result<Vec<ChannelInfo>> {
+        self.http_client
+            .get_all_pages(page_size, |limit, offset| {
+                Self::list_endpoint(channel_type, Some(limit), Some(offset))
+            })
+            .await
+    }
 
-        if !params.is_empty() {
-            endpoint.push('?');
-            endpoint.push_str(&params.join("&"));
-        }
+    fn list_channels_streaming(
+        &self,
+        channel_type: Option<&str>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> BoxStream<'static, Result<ChannelInfo>> {
+        let endpoint = Self::list_endpoint(channel_type, limit, offset);
+        self.http_client.clone().get_stream(&endpoint)
+    }
 
-        self.http_client.get(&endpoint).await.map_err(|e| e.into())
+    async fn update_channel(
+        &self,
+        channel_id: &str,
+        name: Option<&str>,
+        description: Option<&str>,
+        metadata: Option<serde_json::Value>,
+    ) -> Result<ChannelInfo> {
+        if name.is_none() && description.is_none() && metadata.is_none() {
+            return Err(SecureNotifyError::ValidationError(
+                "Channel update must change at least one field".to_string(),
+            ));
+        }
+        self.http_client.validate_metadata(metadata.as_ref())?;
+        let request = ChannelUpdateRequest {
+            name: name.map(|s| s.to_string()),
+            description: description.map(|s| s.to_string()),
+            metadata,
+        };
+        let endpoint = format!("api/channels/{}", channel_id);
+        self.http_client.patch(&endpoint, &request).await
     }
 
     async fn delete_channel(&self, channel_id: &str) -> Result<()> {
         let endpoint = format!("api/channels/{}", channel_id);
         self.http_client.delete(&endpoint).await
     }
+
+    async fn transfer_channel(&self, channel_id: &str, new_owner: &str) -> Result<ChannelInfo> {
+        let endpoint = format!("api/channels/{}/transfer", channel_id);
+        let request = ChannelTransferRequest {
+            new_owner: new_owner.to_string(),
+        };
+        self.http_client.post(&endpoint, &request).await.map_err(|e| match e {
+            SecureNotifyError::ApiError { status: 403, message, .. } => SecureNotifyError::AuthError(format!(
+                "Not permitted to transfer channel {}: {}",
+                channel_id, message
+            )),
+            other => other,
+        })
+    }
+
+    async fn check_channel_access(&self, channel_id: &str) -> Result<ChannelAccess> {
+        let channel = match self.get_channel(channel_id).await {
+            Ok(channel) => channel,
+            Err(error) if error.status() == 404 => return Ok(ChannelAccess::default()),
+            Err(error) => return Err(error),
+        };
+        let expired = channel
+            .expires_at
+            .as_deref()
+            .and_then(|at| time::OffsetDateTime::parse(at, &time::format_description::well_known::Rfc3339).ok())
+            .is_some_and(|at| at <= self.http_client.server_now());
+        let is_active = channel.is_active && !expired;
+        let known = self.http_client.known_permissions();
+        let granted = |permission: Permission| match &known {
+            Some(granted) => permission.is_granted_by(granted),
+            None => true,
+        };
+        Ok(ChannelAccess {
+            exists: true,
+            is_active,
+            writable: is_active && granted(Permission::Publish),
+            readable: is_active && granted(Permission::Subscribe),
+        })
+    }
 }
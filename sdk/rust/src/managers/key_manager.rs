@@ -4,8 +4,41 @@
 //! Key manager for SecureNotify SDK
 
 use async_trait::async_trait;
-use crate::Result;
+use base64::Engine;
+use sha2::{Digest, Sha256};
+use crate::{Result, SecureNotifyError};
 use crate::types::api::*;
+use crate::utils::query::QueryBuilder;
+
+/// Prefix of channel IDs derived from public key fingerprints
+pub const FINGERPRINT_CHANNEL_PREFIX: &str = "key-";
+
+/// Derive a channel ID from a PEM-encoded public key
+///
+/// The scheme is stable: the PEM armor lines are removed, the base64 body is
+/// decoded to DER, and the channel ID is `key-` followed by the lowercase hex
+/// SHA-256 digest of the DER bytes (68 characters in total).
+pub fn channel_id_from_public_key(public_key: &str) -> Result<String> {
+    let body: String = public_key
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("-----"))
+        .collect();
+
+    if body.is_empty() {
+        return Err(SecureNotifyError::SerializationError(
+            "Public key is not PEM encoded".to_string(),
+            None,
+        ));
+    }
+
+    let der = base64::engine::general_purpose::STANDARD
+        .decode(body.as_bytes())
+        .map_err(|e| SecureNotifyError::SerializationError(format!("Invalid PEM body: {}", e), None))?;
+
+    let digest = Sha256::digest(&der);
+    Ok(format!("{}{:x}", FINGERPRINT_CHANNEL_PREFIX, digest))
+}
 
 /// Trait for key management operations
 #[async_trait]
@@ -19,6 +52,19 @@ pub trait KeyManager {
         metadata: Option<serde_json::Value>,
     ) -> Result<RegisterPublicKeyResponse>;
 
+    /// Register a public key under a channel ID derived from its fingerprint
+    ///
+    /// See [`channel_id_from_public_key`] for the derivation scheme.
+    ///
+    /// # Returns
+    /// The channel ID the key was registered under
+    async fn register_public_key_auto(
+        &self,
+        public_key: &str,
+        algorithm: &str,
+        metadata: Option<serde_json::Value>,
+    ) -> Result<String>;
+
     /// Get public key information for a channel
     async fn get_public_key(&self, channel_id: &str) -> Result<PublicKeyInfo>;
 
@@ -29,6 +75,9 @@ pub trait KeyManager {
         offset: Option<u32>,
     ) -> Result<Vec<PublicKeyInfo>>;
 
+    /// List every public key, fetching `page_size` keys per request
+    async fn list_all_public_keys(&self, page_size: u32) -> Result<Vec<PublicKeyInfo>>;
+
     /// Revoke a public key
     async fn revoke_public_key(&self, channel_id: &str) -> Result<()>;
 }
@@ -54,6 +103,7 @@ impl KeyManager for KeyManagerImpl {
         algorithm: &str,
         metadata: Option<serde_json::Value>,
     ) -> Result<RegisterPublicKeyResponse> {
+        self.http_client.validate_metadata(metadata.as_ref())?;
         let request = RegisterPublicKeyRequest {
             public_key: public_key.to_string(),
             algorithm: algorithm.to_string(),
@@ -61,12 +111,24 @@ impl KeyManager for KeyManagerImpl {
         };
 
         let endpoint = format!("api/register/{}", channel_id);
-        self.http_client.post(&endpoint, &request).await.map_err(|e| e.into())
+        self.http_client.post(&endpoint, &request).await
+    }
+
+    async fn register_public_key_auto(
+        &self,
+        public_key: &str,
+        algorithm: &str,
+        metadata: Option<serde_json::Value>,
+    ) -> Result<String> {
+        let channel_id = channel_id_from_public_key(public_key)?;
+        self.register_public_key(&channel_id, public_key, algorithm, metadata)
+            .await?;
+        Ok(channel_id)
     }
 
     async fn get_public_key(&self, channel_id: &str) -> Result<PublicKeyInfo> {
         let endpoint = format!("api/register/{}", channel_id);
-        self.http_client.get(&endpoint).await.map_err(|e| e.into())
+        self.http_client.get(&endpoint).await
     }
 
     async fn list_public_keys(
@@ -74,22 +136,23 @@ impl KeyManager for KeyManagerImpl {
         limit: Option<u32>,
         offset: Option<u32>,
     ) -> Result<Vec<PublicKeyInfo>> {
-        let mut endpoint = "api/register".to_string();
-        let mut params = Vec::new();
-
-        if let Some(limit) = limit {
-            params.push(format!("limit={}", limit));
-        }
-        if let Some(offset) = offset {
-            params.push(format!("offset={}", offset));
-        }
-
-        if !params.is_empty() {
-            endpoint.push('?');
-            endpoint.push_str(&params.join("&"));
-        }
-
-        self.http_client.get(&endpoint).await.map_err(|e| e.into())
+        let endpoint = QueryBuilder::new()
+            .optional("limit", limit)
+            .optional("offset", offset)
+            .build("api/register");
+
+        self.http_client.get(&endpoint).await
+    }
+
+    async fn list_all_public_keys(&self, page_size: u32) -> Result<Vec<PublicKeyInfo>> {
+        self.http_client
+            .get_all_pages(page_size, |limit, offset| {
+                QueryBuilder::new()
+                    .optional("limit", Some(limit))
+                    .optional("offset", Some(offset))
+                    .build("api/register")
+            })
+            .await
     }
 
     async fn revoke_public_key(&self, channel_id: &str) -> Result<()> {
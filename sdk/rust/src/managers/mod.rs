@@ -9,7 +9,7 @@ pub mod publish_manager;
 pub mod subscribe_manager;
 pub mod apikey_manager;
 
-pub use key_manager::{KeyManager, KeyManagerImpl};
+pub use key_manager::{KeyManager, KeyManagerImpl, channel_id_from_public_key};
 pub use channel_manager::{ChannelManager, ChannelManagerImpl};
 pub use publish_manager::{PublishManager, PublishManagerImpl};
 pub use subscribe_manager::{SubscribeManager, SubscribeManagerImpl};
@@ -4,13 +4,25 @@
 //! Publish manager for SecureNotify SDK
 
 use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
 use crate::{Result, MessagePriority};
 use crate::types::api::*;
+use crate::utils::http::BatchPolicy;
+use crate::utils::signing::MessageSigner;
+use crate::utils::query::QueryBuilder;
+
+/// Maximum number of publishes in flight during a fan-out
+pub const FANOUT_CONCURRENCY: usize = 8;
 
 /// Trait for message publishing operations
 #[async_trait]
 pub trait PublishManager {
     /// Publish a message to a channel
+    ///
+    /// Waits for a permit when a rate limit is configured for the message priority.
+    /// With `ordered_publish` enabled, publishes to the same channel are sent
+    /// one at a time in call order, retries included.
+    #[allow(clippy::too_many_arguments)]
     async fn publish_message(
         &self,
         channel: &str,
@@ -22,11 +34,69 @@ pub trait PublishManager {
         signature: Option<&str>,
     ) -> Result<MessagePublishResponse>;
 
+    /// Publish the same message to several channels
+    ///
+    /// Up to [`FANOUT_CONCURRENCY`] publishes run at once. Returns one result
+    /// per channel, in the order given, so a failure on one channel does not
+    /// affect the others. Under [`BatchPolicy::FailFast`] the first failure is
+    /// returned instead and the remaining publishes are cancelled.
+    async fn publish_fanout(
+        &self,
+        channels: &[&str],
+        message: &str,
+        priority: Option<MessagePriority>,
+    ) -> Result<Vec<Result<MessagePublishResponse>>>;
+
+    /// Sign and publish a batch of `(channel, message)` pairs
+    ///
+    /// All messages are signed up front with
+    /// [`MessageSigner::sign_batch`], then published like
+    /// [`publish_fanout`](Self::publish_fanout): up to
+    /// [`FANOUT_CONCURRENCY`] at once, one result per item in the order
+    /// given, honouring the configured [`BatchPolicy`].
+    async fn publish_batch_signed(
+        &self,
+        signer: &MessageSigner,
+        items: &[(&str, &str)],
+        priority: Option<MessagePriority>,
+    ) -> Result<Vec<Result<MessagePublishResponse>>>;
+
     /// Get message queue status
     async fn get_queue_status(&self, channel: &str) -> Result<QueueStatus>;
 
     /// Get a specific message
-    async fn get_message(&self, channel: &str, message_id: &str) -> Result<MessageInfo>;
+    ///
+    /// Without validators the fetch is unconditional and returns
+    /// `Conditional::Modified` with the validators the server sent. To
+    /// revalidate, pass the validators from the last `Conditional::Modified`
+    /// result; the server's 304 is then returned as `Conditional::NotModified`.
+    async fn get_message(
+        &self,
+        channel: &str,
+        message_id: &str,
+        validators: Option<&Validators>,
+    ) -> Result<Conditional<MessageInfo>>;
+
+    /// List recent messages in a channel
+    async fn list_messages(&self, channel: &str, limit: Option<u32>) -> Result<Vec<MessageInfo>>;
+
+    /// List messages published after the given message ID
+    ///
+    /// Pass the ID of the last message received to fetch the next page.
+    /// Unlike offset paging, this never skips or repeats messages when new
+    /// ones are published between requests.
+    async fn list_messages_after(
+        &self,
+        channel: &str,
+        after_id: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<MessageInfo>>;
+
+    /// Acknowledge messages as consumed
+    async fn ack_messages(&self, channel: &str, message_ids: &[&str]) -> Result<()>;
+
+    /// Reject messages so they are delivered again
+    async fn nack_messages(&self, channel: &str, message_ids: &[&str]) -> Result<()>;
 }
 
 /// Implementation of PublishManager
@@ -53,6 +123,8 @@ impl PublishManager for PublishManagerImpl {
         encrypted: Option<bool>,
         signature: Option<&str>,
     ) -> Result<MessagePublishResponse> {
+        self.http_client.check_permission(Permission::Publish)?;
+        let priority = priority.or(self.http_client.config().default_priority);
         let request = MessagePublishRequest {
             message: message.to_string(),
             priority: priority.map(|p| p as u8),
@@ -62,17 +134,134 @@ impl PublishManager for PublishManagerImpl {
             signature: signature.map(|s| s.to_string()),
         };
 
-        let endpoint = format!("api/publish/{}", channel);
-        self.http_client.post(&endpoint, &request).await.map_err(|e| e.into())
+        let send = async {
+            let _order = self.http_client.acquire_publish_order(channel).await;
+            self.http_client
+                .acquire_publish_permit(priority.unwrap_or(MessagePriority::Normal))
+                .await;
+
+            let endpoint = format!("api/publish/{}", channel);
+            self.http_client.post(&endpoint, &request).await
+        };
+        self.http_client.throttle_publish(channel, &request, send).await
+    }
+
+    async fn publish_fanout(
+        &self,
+        channels: &[&str],
+        message: &str,
+        priority: Option<MessagePriority>,
+    ) -> Result<Vec<Result<MessagePublishResponse>>> {
+        // Collected up front: a lazy `map` closure in the stream trips async-trait's `Send` check
+        let publishes: Vec<_> = channels
+            .iter()
+            .map(|channel| self.publish_message(channel, message, priority, None, None, None, None))
+            .collect();
+        run_batch(self.http_client.config().batch_policy, publishes).await
+    }
+
+    async fn publish_batch_signed(
+        &self,
+        signer: &MessageSigner,
+        items: &[(&str, &str)],
+        priority: Option<MessagePriority>,
+    ) -> Result<Vec<Result<MessagePublishResponse>>> {
+        let signatures = signer.sign_batch(items);
+        let publishes: Vec<_> = items
+            .iter()
+            .zip(&signatures)
+            .map(|((channel, message), signature)| {
+                self.publish_message(channel, message, priority, None, None, None, Some(signature))
+            })
+            .collect();
+        run_batch(self.http_client.config().batch_policy, publishes).await
     }
 
     async fn get_queue_status(&self, channel: &str) -> Result<QueueStatus> {
-        let endpoint = format!("api/publish/{}?status=true", channel);
-        self.http_client.get(&endpoint).await.map_err(|e| e.into())
+        let endpoint = QueryBuilder::new()
+            .param("status", true)
+            .build(&format!("api/publish/{}", channel));
+        self.http_client.get(&endpoint).await
     }
 
-    async fn get_message(&self, channel: &str, message_id: &str) -> Result<MessageInfo> {
+    async fn get_message(
+        &self,
+        channel: &str,
+        message_id: &str,
+        validators: Option<&Validators>,
+    ) -> Result<Conditional<MessageInfo>> {
         let endpoint = format!("api/publish/{}/{}", channel, message_id);
-        self.http_client.get(&endpoint).await.map_err(|e| e.into())
+        let validators = validators.cloned().unwrap_or_default();
+        self.http_client.get_conditional(&endpoint, &validators).await
+    }
+
+    async fn list_messages(&self, channel: &str, limit: Option<u32>) -> Result<Vec<MessageInfo>> {
+        let endpoint = QueryBuilder::new()
+            .optional("limit", limit)
+            .build(&format!("api/publish/{}/messages", channel));
+        self.http_client.get(&endpoint).await
+    }
+
+    async fn list_messages_after(
+        &self,
+        channel: &str,
+        after_id: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<MessageInfo>> {
+        let endpoint = QueryBuilder::new()
+            .param("after", after_id)
+            .optional("limit", limit)
+            .build(&format!("api/publish/{}/messages", channel));
+        self.http_client.get(&endpoint).await
+    }
+
+    async fn ack_messages(&self, channel: &str, message_ids: &[&str]) -> Result<()> {
+        let request = MessageAckRequest {
+            message_ids: message_ids.iter().map(|s| s.to_string()).collect(),
+        };
+
+        let endpoint = format!("api/publish/{}/ack", channel);
+        self.http_client.post_no_content(&endpoint, &request).await
+    }
+
+    async fn nack_messages(&self, channel: &str, message_ids: &[&str]) -> Result<()> {
+        let request = MessageAckRequest {
+            message_ids: message_ids.iter().map(|s| s.to_string()).collect(),
+        };
+
+        let endpoint = format!("api/publish/{}/nack", channel);
+        self.http_client.post_no_content(&endpoint, &request).await
+    }
+}
+
+/// Run batch publishes with up to [`FANOUT_CONCURRENCY`] in flight
+///
+/// Returns one result per publish in order, or under
+/// [`BatchPolicy::FailFast`] the first failure, cancelling the rest.
+async fn run_batch<F>(policy: BatchPolicy, publishes: Vec<F>) -> Result<Vec<Result<MessagePublishResponse>>>
+where
+    F: std::future::Future<Output = Result<MessagePublishResponse>>,
+{
+    if policy == BatchPolicy::BestEffort {
+        let results = stream::iter(publishes)
+            .buffered(FANOUT_CONCURRENCY)
+            .collect()
+            .await;
+        return Ok(results);
+    }
+
+    // Unordered so the earliest failure wins; dropping the stream cancels the rest
+    let count = publishes.len();
+    let indexed: Vec<_> = publishes
+        .into_iter()
+        .enumerate()
+        .map(|(index, publish)| async move { (index, publish.await) })
+        .collect();
+    let mut completed = stream::iter(indexed).buffer_unordered(FANOUT_CONCURRENCY);
+    let mut responses = Vec::with_capacity(count);
+    while let Some((index, result)) = completed.next().await {
+        responses.push((index, result?));
     }
+    responses.sort_by_key(|(index, _)| *index);
+    Ok(responses.into_iter().map(|(_, response)| Ok(response)).collect())
 }
@@ -3,10 +3,23 @@
 
 //! Subscribe manager for SecureNotify SDK
 
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
 use async_trait::async_trait;
-use crate::{Result, SseMessage};
+use tokio::sync::mpsc;
+use tokio::task::AbortHandle;
+use crate::{Result, SecureNotifyError, SseEventType, SseMessage};
+use crate::managers::{PublishManager, PublishManagerImpl};
 use crate::types::api::*;
-use crate::utils::connection::{SseConnection, SseConfig};
+use crate::types::error::ManagerError;
+use crate::utils::query::QueryBuilder;
+use crate::utils::connection::{DisconnectReason, ReconnectAttempt, SseConnection, SseConfig};
+use crate::utils::http::HttpClient;
+use crate::utils::quota::Operation;
+
+/// How long the server may hold a long-poll request open
+pub const LONGPOLL_WAIT: Duration = Duration::from_secs(30);
 
 /// Trait for subscription operations
 #[async_trait]
@@ -17,11 +30,85 @@ pub trait SubscribeManager {
         channel_id: &str,
     ) -> Result<tokio::sync::mpsc::Receiver<SseMessage>>;
 
+    /// Subscribe to a channel, returning once the connection is established
+    ///
+    /// Waits for the connection's `Connected` message, which is consumed, so
+    /// no messages are missed while the stream is being set up. If the first
+    /// connection attempt fails, or `timeout` passes before it succeeds, the
+    /// subscription is stopped and the error returned.
+    async fn subscribe_ready(
+        &self,
+        channel_id: &str,
+        timeout: Duration,
+    ) -> Result<tokio::sync::mpsc::Receiver<SseMessage>>;
+
+    /// Subscribe to a channel and receive only the given event types
+    ///
+    /// Messages of other types are dropped before they reach the receiver.
+    async fn subscribe_filtered(
+        &self,
+        channel_id: &str,
+        event_types: &[SseEventType],
+    ) -> Result<tokio::sync::mpsc::Receiver<SseMessage>>;
+
+    /// Subscribe to a channel with a server-side filter expression
+    ///
+    /// The expression (e.g. `priority>=75`) is passed to the server as the
+    /// `filter` query parameter. If the server rejects it, the receiver gets
+    /// a single error and the connection is not retried.
+    async fn subscribe_with_filter(
+        &self,
+        channel_id: &str,
+        filter: &str,
+    ) -> Result<tokio::sync::mpsc::Receiver<SseMessage>>;
+
+    /// Subscribe to a channel, receiving recent history before live events
+    ///
+    /// The last `history_limit` messages are fetched with `list_messages` and
+    /// delivered oldest first as message events whose data is a serialized
+    /// [`MessageEnvelope`]. Live events for messages already delivered from
+    /// history are dropped.
+    async fn subscribe_with_history(
+        &self,
+        channel_id: &str,
+        history_limit: u32,
+    ) -> Result<tokio::sync::mpsc::Receiver<SseMessage>>;
+
+    /// Subscribe to a channel using long-polling instead of SSE
+    ///
+    /// For networks that block SSE entirely. Repeatedly requests
+    /// `api/subscribe/{channel}?wait=30`, which returns a JSON array of
+    /// [`MessageEnvelope`]s, and delivers them as message events on the same
+    /// kind of receiver as `subscribe`.
+    async fn subscribe_longpoll(
+        &self,
+        channel_id: &str,
+    ) -> Result<tokio::sync::mpsc::Receiver<SseMessage>>;
+
     /// Unsubscribe from a channel
+    ///
+    /// Stops the channel's subscriptions opened through this client, which
+    /// closes their receivers, then unsubscribes on the server.
     async fn unsubscribe(&self, channel_id: &str) -> Result<()>;
 
+    /// Close every subscription opened through this client
+    ///
+    /// Stops each subscription's connection, which closes its receiver, then
+    /// unsubscribes from each channel on the server. All channels are
+    /// attempted even if some fail; a single failure is returned as-is and
+    /// several are combined into one error listing each channel.
+    async fn unsubscribe_all(&self) -> Result<()>;
+
     /// Get active subscriptions
     async fn list_subscriptions(&self) -> Result<Vec<SubscriptionInfo>>;
+
+    /// Get a page of a subscription's connect, disconnect and message-count events
+    async fn list_subscription_events(
+        &self,
+        subscription_id: &str,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> Result<Vec<SubscriptionEvent>>;
 }
 
 /// Implementation of SubscribeManager
@@ -34,6 +121,185 @@ impl SubscribeManagerImpl {
     pub fn new(http_client: std::sync::Arc<crate::utils::http::HttpClient>) -> Self {
         Self { http_client }
     }
+
+    /// Open a subscription over SSE, falling back to long-polling if configured
+    ///
+    /// With `longpoll_fallback_after` set, the SSE connection is attempted that
+    /// many times. If it never connects, the subscription continues over
+    /// long-polling on the same receiver.
+    fn connect(&self, channel_id: &str, config: SseConfig) -> Result<mpsc::Receiver<SseMessage>> {
+        Ok(self.open(channel_id, config)?.0)
+    }
+
+    /// Stop a subscription opened with `open` and drop it from the registry
+    fn close(&self, tasks: &[AbortHandle]) {
+        tasks.iter().for_each(AbortHandle::abort);
+        self.http_client.subscriptions().untrack(tasks);
+    }
+
+    /// Open a subscription as `connect` does, also returning its tasks
+    fn open(&self, channel_id: &str, config: SseConfig) -> Result<(mpsc::Receiver<SseMessage>, Vec<AbortHandle>)> {
+        self.http_client.check_permission(Permission::Subscribe)?;
+        self.http_client.consume_quota(Operation::Subscribe.cost());
+
+        let Some(attempts) = self.http_client.config().longpoll_fallback_after else {
+            let (connection, receiver) = SseConnection::new(config);
+            let tasks = vec![connection.abort_handle()];
+            self.http_client
+                .subscriptions()
+                .track_with_health(channel_id, tasks.clone(), Some(connection.health_probe()));
+            return Ok((receiver, tasks));
+        };
+
+        // Filter here rather than in the connection so `Connected` is always seen
+        let mut sse_config = config.clone().with_max_reconnect_attempts(attempts.saturating_sub(1));
+        sse_config.event_filter = None;
+        let (connection, mut sse) = SseConnection::new(sse_config);
+
+        let (tx, rx) = mpsc::channel(config.channel_buffer.max(1));
+        let http_client = self.http_client.clone();
+        let channel = channel_id.to_string();
+        let forward = tokio::spawn(async move {
+            let mut connected = false;
+            while let Some(message) = sse.recv().await {
+                connected |= matches!(message, SseMessage::Connected);
+                // Long-polling takes over a stream that never connected
+                if !connected && matches!(message, SseMessage::Disconnected(_)) {
+                    continue;
+                }
+                if config.accepts(&message) && tx.send(message).await.is_err() {
+                    return;
+                }
+            }
+            if !connected {
+                run_longpoll(http_client, channel, config, tx).await;
+            }
+        });
+        let tasks = vec![connection.abort_handle(), forward.abort_handle()];
+        self.http_client
+            .subscriptions()
+            .track_with_health(channel_id, tasks.clone(), Some(connection.health_probe()));
+        Ok((rx, tasks))
+    }
+}
+
+/// Poll for messages until the receiver is dropped or polling keeps failing
+///
+/// Failures are retried with the same backoff and attempt limit as SSE
+/// reconnects, taken from `config`.
+pub(crate) async fn run_longpoll(
+    http_client: Arc<HttpClient>,
+    channel_id: String,
+    config: SseConfig,
+    tx: mpsc::Sender<SseMessage>,
+) {
+    let send = |message: SseMessage| {
+        let tx = tx.clone();
+        let accepted = config.accepts(&message);
+        async move { !accepted || tx.send(message).await.is_ok() }
+    };
+
+    let mut last_id: Option<String> = None;
+    let mut failures = 0u32;
+    let mut connected = false;
+
+    while !tx.is_closed() {
+        let endpoint = QueryBuilder::new()
+            .param("wait", LONGPOLL_WAIT.as_secs())
+            .optional("after", last_id.as_ref())
+            .optional("filter", config.filter.as_ref())
+            .build(&format!("api/subscribe/{}", channel_id));
+
+        match http_client.long_poll::<Vec<MessageEnvelope>>(&endpoint, LONGPOLL_WAIT).await {
+            Ok(batch) => {
+                failures = 0;
+                if !connected {
+                    connected = true;
+                    if !send(SseMessage::Connected).await {
+                        return;
+                    }
+                }
+                for envelope in batch {
+                    last_id = Some(envelope.message_id.clone());
+                    let message = match envelope.to_sse_event() {
+                        Ok(event) => SseMessage::Event(event),
+                        Err(e) => SseMessage::Error(e, None),
+                    };
+                    if !send(message).await {
+                        return;
+                    }
+                }
+            }
+            Err(error) => {
+                connected = false;
+                let rejected = (error.status() == 400 && config.filter.is_some())
+                    || config.fail_fast_statuses.contains(&error.status());
+                let reconnect = (!rejected && failures < config.max_reconnect_attempts).then(|| ReconnectAttempt {
+                    attempt: failures + 1,
+                    max_attempts: config.max_reconnect_attempts,
+                });
+                if !send(SseMessage::Error(error, reconnect)).await {
+                    return;
+                }
+                if rejected {
+                    send(SseMessage::Disconnected(DisconnectReason::Error)).await;
+                    return;
+                }
+                if failures >= config.max_reconnect_attempts {
+                    if send(SseMessage::Error(SecureNotifyError::ConnectionError(
+                        "Max reconnect attempts reached".to_string(),
+                        None,
+                    ), None))
+                    .await
+                    {
+                        send(SseMessage::Disconnected(DisconnectReason::MaxRetriesExceeded)).await;
+                    }
+                    return;
+                }
+                failures += 1;
+                tokio::time::sleep(SseConnection::reconnect_delay(&config, failures, None)).await;
+            }
+        }
+    }
+}
+
+/// Deliver history messages, then forward live messages not already delivered
+pub(crate) fn merge_history(
+    history: Vec<MessageEnvelope>,
+    mut live: mpsc::Receiver<SseMessage>,
+) -> mpsc::Receiver<SseMessage> {
+    let (tx, rx) = mpsc::channel(history.len().max(100));
+
+    tokio::spawn(async move {
+        let mut delivered = HashSet::new();
+        for envelope in history {
+            let event = match envelope.to_sse_event() {
+                Ok(event) => event,
+                Err(_) => continue,
+            };
+            delivered.insert(envelope.message_id);
+            if tx.send(SseMessage::Event(event)).await.is_err() {
+                return;
+            }
+        }
+
+        while let Some(message) = live.recv().await {
+            if let SseMessage::Event(event) = &message {
+                let message_id = MessageEnvelope::try_from(event)
+                    .map(|envelope| envelope.message_id)
+                    .ok()
+                    .or_else(|| event.id.clone());
+                if message_id.is_some_and(|id| delivered.remove(&id)) {
+                    continue;
+                }
+            }
+            if tx.send(message).await.is_err() {
+                return;
+            }
+        }
+    });
+
+    rx
 }
 
 #[async_trait]
@@ -42,28 +308,156 @@ impl SubscribeManager for SubscribeManagerImpl {
         &self,
         channel_id: &str,
     ) -> Result<tokio::sync::mpsc::Receiver<SseMessage>> {
-        let url = format!(
-            "{}/api/subscribe/{}",
-            self.http_client.config().base_url,
-            channel_id
-        );
+        let config = self.http_client.sse_config_for(channel_id);
+        self.connect(channel_id, config)
+    }
+
+    async fn subscribe_ready(
+        &self,
+        channel_id: &str,
+        timeout: Duration,
+    ) -> Result<tokio::sync::mpsc::Receiver<SseMessage>> {
+        let (mut receiver, tasks) = self.open(channel_id, self.http_client.sse_config_for(channel_id))?;
+        let ready = async {
+            while let Some(message) = receiver.recv().await {
+                match message {
+                    SseMessage::Connected => return Ok(()),
+                    SseMessage::Error(error, _) => return Err(error),
+                    _ => {}
+                }
+            }
+            Err(SecureNotifyError::ConnectionError(
+                format!("Subscription to {} closed before connecting", channel_id),
+                None,
+            ))
+        };
+        let result = match tokio::time::timeout(timeout, ready).await {
+            Ok(result) => result,
+            Err(_) => Err(SecureNotifyError::TimeoutError(
+                format!("Subscription to {} did not connect within {:?}", channel_id, timeout),
+                None,
+            )),
+        };
+        match result {
+            Ok(()) => Ok(receiver),
+            Err(error) => {
+                self.close(&tasks);
+                Err(error)
+            }
+        }
+    }
+
+    async fn subscribe_filtered(
+        &self,
+        channel_id: &str,
+        event_types: &[SseEventType],
+    ) -> Result<tokio::sync::mpsc::Receiver<SseMessage>> {
+        let config = self.http_client.sse_config_for(channel_id).with_event_filter(event_types);
+        self.connect(channel_id, config)
+    }
+
+    async fn subscribe_with_filter(
+        &self,
+        channel_id: &str,
+        filter: &str,
+    ) -> Result<tokio::sync::mpsc::Receiver<SseMessage>> {
+        let config = self.http_client.sse_config_for(channel_id).with_filter(filter);
+        self.connect(channel_id, config)
+    }
 
-        let config = SseConfig::new(url, self.http_client.config().api_key.clone());
-        let (_connection, receiver) = SseConnection::new(config);
+    async fn subscribe_with_history(
+        &self,
+        channel_id: &str,
+        history_limit: u32,
+    ) -> Result<tokio::sync::mpsc::Receiver<SseMessage>> {
+        // Connect first so live events arriving during the fetch are buffered
+        let (live, tasks) = self.open(channel_id, self.http_client.sse_config_for(channel_id))?;
 
-        // Store the connection for later cleanup
-        // In a real implementation, you'd want to track these connections
+        let messages = match PublishManagerImpl::new(self.http_client.clone())
+            .list_messages(channel_id, Some(history_limit))
+            .await
+        {
+            Ok(messages) => messages,
+            Err(error) => {
+                self.close(&tasks);
+                return Err(error);
+            }
+        };
+        let mut history: Vec<MessageEnvelope> = messages
+            .into_iter()
+            .map(MessageEnvelope::from)
+            .collect();
+        history.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
 
-        Ok(receiver)
+        Ok(merge_history(history, live))
+    }
+
+    async fn subscribe_longpoll(
+        &self,
+        channel_id: &str,
+    ) -> Result<tokio::sync::mpsc::Receiver<SseMessage>> {
+        self.http_client.check_permission(Permission::Subscribe)?;
+        let config = self.http_client.sse_config_for(channel_id);
+        let (tx, rx) = mpsc::channel(config.channel_buffer.max(1));
+        let task = tokio::spawn(run_longpoll(
+            self.http_client.clone(),
+            channel_id.to_string(),
+            config,
+            tx,
+        ));
+        self.http_client
+            .subscriptions()
+            .track(channel_id, vec![task.abort_handle()]);
+        Ok(rx)
     }
 
     async fn unsubscribe(&self, channel_id: &str) -> Result<()> {
+        self.http_client.subscriptions().cancel(channel_id);
         let endpoint = format!("api/subscribe/{}", channel_id);
-        self.http_client.delete(&endpoint).await.map_err(|e| e.into())
+        self.http_client.delete(&endpoint).await
+    }
+
+    async fn unsubscribe_all(&self) -> Result<()> {
+        let channels = self.http_client.subscriptions().cancel_all();
+
+        let mut errors = Vec::new();
+        for channel_id in &channels {
+            if let Err(e) = self.unsubscribe(channel_id).await {
+                errors.push((channel_id, e));
+            }
+        }
+
+        if errors.len() <= 1 {
+            return errors.pop().map_or(Ok(()), |(_, e)| Err(e));
+        }
+        let details: Vec<String> = errors
+            .iter()
+            .map(|(channel_id, e)| format!("{}: {}", channel_id, e))
+            .collect();
+        Err(ManagerError::SubscribeManager(format!(
+            "Failed to unsubscribe from {} channels: {}",
+            errors.len(),
+            details.join("; ")
+        ))
+        .into())
     }
 
     async fn list_subscriptions(&self) -> Result<Vec<SubscriptionInfo>> {
         let endpoint = "api/subscribe";
-        self.http_client.get(endpoint).await.map_err(|e| e.into())
+        self.http_client.get(endpoint).await
+    }
+
+    async fn list_subscription_events(
+        &self,
+        subscription_id: &str,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> Result<Vec<SubscriptionEvent>> {
+        let endpoint = QueryBuilder::new()
+            .optional("limit", limit)
+            .optional("offset", offset)
+            .build(&format!("api/subscribe/{}/events", subscription_id));
+
+        self.http_client.get(&endpoint).await
     }
 }
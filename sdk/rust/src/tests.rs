@@ -2,14 +2,174 @@
 // Copyright (c) 2026 KirkyX. All rights reserved.
 
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
     use crate::{SecureNotifyClient, SecureNotifyError};
     use crate::MessagePriority;
     use crate::ChannelType;
     use crate::EncryptionAlgorithm;
     use crate::ConnectionState;
+    use crate::managers::{
+        channel_id_from_public_key, ApiKeyManager, ChannelManager, KeyManager, PublishManager,
+        SubscribeManager,
+    };
+    use crate::{MessageEnvelope, SseEvent, SseEventType, SseMessage};
+    use crate::managers::subscribe_manager::merge_history;
+    use crate::utils::connection::{DisconnectReason, ReconnectAttempt, SseConfig, SseConnection, SseParser, SseState};
+    use crate::utils::http::{HttpClient, HttpClientConfig};
+    use crate::types::api::ChannelInfo;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
     use tokio::time::Duration;
 
+    /// Canned HTTP response served by the mock server
+    #[derive(Clone)]
+    struct MockResponse {
+        status: u16,
+        headers: Vec<(String, String)>,
+        body: String,
+    }
+
+    impl MockResponse {
+        fn json(status: u16, body: &str) -> Self {
+            Self {
+                status,
+                headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+                body: body.to_string(),
+            }
+        }
+
+        fn sse(body: &str) -> Self {
+            Self {
+                status: 200,
+                headers: vec![("Content-Type".to_string(), "text/event-stream".to_string())],
+                body: body.to_string(),
+            }
+        }
+
+        fn header(mut self, name: &str, value: &str) -> Self {
+            self.headers.push((name.to_string(), value.to_string()));
+            self
+        }
+    }
+
+    /// Spawn a minimal HTTP server answering one request per connection.
+    ///
+    /// Responses are served in order and the last one is repeated once the list
+    /// is exhausted. Returns the base URL and the raw requests received.
+    async fn spawn_mock_server(responses: Vec<MockResponse>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let captured = requests.clone();
+
+        tokio::spawn(async move {
+            let mut index = 0;
+            loop {
+                let (mut socket, _) = match listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(_) => break,
+                };
+                let response = responses[index.min(responses.len() - 1)].clone();
+                index += 1;
+
+                let mut raw = Vec::new();
+                let mut buf = [0u8; 4096];
+                loop {
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    if n == 0 {
+                        break;
+                    }
+                    raw.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&raw).to_string();
+                    if let Some(head_end) = text.find("\r\n\r\n") {
+                        let content_length = text[..head_end]
+                            .lines()
+                            .find_map(|l| {
+                                let (name, value) = l.split_once(':')?;
+                                name.eq_ignore_ascii_case("content-length")
+                                    .then(|| value.trim().parse::<usize>().unwrap_or(0))
+                            })
+                            .unwrap_or(0);
+                        if raw.len() >= head_end + 4 + content_length {
+                            break;
+                        }
+                    }
+                }
+                captured.lock().unwrap().push(String::from_utf8_lossy(&raw).to_string());
+
+                let mut head = format!("HTTP/1.1 {} Mock\r\n", response.status);
+                for (name, value) in &response.headers {
+                    head.push_str(&format!("{}: {}\r\n", name, value));
+                }
+                head.push_str(&format!(
+                    "Content-Length: {}\r\nConnection: close\r\n\r\n",
+                    response.body.len()
+                ));
+                let _ = socket.write_all(head.as_bytes()).await;
+                let _ = socket.write_all(response.body.as_bytes()).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+
+        (format!("http://{}", addr), requests)
+    }
+
+    const CHANNEL_BODY: &str = r#"{"id":"ch-1","name":"news","type":"public","created_at":"2026-01-01T00:00:00Z","is_active":true}"#;
+
+    /// Create an HTTP client with the response cache enabled
+    fn cached_http_client(base_url: &str) -> HttpClient {
+        HttpClient::from_config(HttpClientConfig {
+            base_url: base_url.to_string(),
+            api_key: "test-key".to_string(),
+            max_retries: 0,
+            enable_cache: true,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    /// Spawn an h2c (HTTP/2 prior knowledge) server answering every request with `body`
+    async fn spawn_h2_server(body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut connection = match h2::server::handshake(socket).await {
+                        Ok(connection) => connection,
+                        Err(_) => return,
+                    };
+                    while let Some(Ok((_request, mut respond))) = connection.accept().await {
+                        let response = http02::Response::builder()
+                            .status(200)
+                            .header("content-type", "application/json")
+                            .body(())
+                            .unwrap();
+                        if let Ok(mut stream) = respond.send_response(response, false) {
+                            let _ = stream.send_data(bytes::Bytes::from_static(body.as_bytes()), true);
+                        }
+                    }
+                });
+            }
+        });
+
+        format!("http://{}", addr)
+    }
+
+    /// Drain a subscription receiver until the stream closes
+    async fn collect_messages(mut receiver: tokio::sync::mpsc::Receiver<SseMessage>) -> Vec<SseMessage> {
+        let mut messages = Vec::new();
+        while let Ok(Some(message)) =
+            tokio::time::timeout(Duration::from_secs(5), receiver.recv()).await
+        {
+            messages.push(message);
+        }
+        messages
+    }
+
     #[tokio::test]
     async fn test_client_builder() {
         let client = SecureNotifyClient::builder()
@@ -32,10 +192,38 @@ mod tests {
 
         assert!(client.is_err());
         match client {
-            Err(SecureNotifyError::AuthError(msg)) => {
+            Err(SecureNotifyError::ValidationError(msg)) => {
                 assert!(msg.contains("API key"));
             }
-            _ => panic!("Expected AuthError"),
+            _ => panic!("Expected ValidationError"),
+        }
+    }
+
+    #[test]
+    fn test_client_builder_reports_all_problems() {
+        let error = SecureNotifyClient::builder()
+            .base_url("not a url")
+            .timeout(Duration::ZERO)
+            .backoff_multiplier(0.5)
+            .initial_delay_ms(5000)
+            .max_delay_ms(1000)
+            .priority_rate_limit(MessagePriority::High, 0.0, 10)
+            .build()
+            .err()
+            .expect("build should fail");
+
+        let SecureNotifyError::ValidationError(message) = &error else {
+            panic!("expected ValidationError, got {:?}", error);
+        };
+        for problem in [
+            "API key is required",
+            "base URL `not a url` is invalid",
+            "timeout must be greater than zero",
+            "backoff multiplier must be at least 1.0",
+            "initial retry delay (5000 ms) exceeds the maximum delay (1000 ms)",
+            "rate limit for High priority",
+        ] {
+            assert!(message.contains(problem), "missing `{}` in: {}", problem, message);
         }
     }
 
@@ -85,16 +273,4284 @@ mod tests {
             code: "INVALID_KEY".to_string(),
             message: "The key is invalid".to_string(),
             status: 400,
+            details: None,
         };
         assert!(api_error.is_api_error());
         assert_eq!(api_error.code(), "INVALID_KEY");
         assert_eq!(api_error.status(), 400);
 
-        let network_error = SecureNotifyError::NetworkError("Connection refused".to_string());
+        let network_error = SecureNotifyError::NetworkError("Connection refused".to_string(), None);
         assert!(network_error.is_network_error());
         assert!(network_error.code().starts_with("NETWORK_ERROR"));
 
-        let timeout_error = SecureNotifyError::TimeoutError("Request timed out".to_string());
+        let timeout_error = SecureNotifyError::TimeoutError("Request timed out".to_string(), None);
         assert!(timeout_error.code().starts_with("TIMEOUT_ERROR"));
     }
+
+    #[test]
+    fn test_metrics_context_records_once() {
+        use crate::utils::{MetricsCollector, MetricsContext};
+
+        let collector = MetricsCollector::new(100);
+        let mut ctx = MetricsContext::new(&collector, "api/test");
+        ctx.mark_success();
+        ctx.record();
+        drop(MetricsContext::new(&collector, "api/test"));
+
+        let stats = collector.get_stats("api/test").unwrap();
+        assert_eq!(stats.count, 2);
+        assert_eq!(stats.success_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_performance_report() {
+        let body = r#"{"id":"ch-1","name":"news","type":"public","created_at":"2026-01-01T00:00:00Z","is_active":true}"#;
+        let (base_url, _) = spawn_mock_server(vec![MockResponse::json(200, body)]).await;
+        let client = HttpClient::with_config(
+            &base_url,
+            "test-key",
+            Duration::from_secs(5),
+            0,
+            10,
+            100,
+            2.0,
+            true,
+            true,
+            true,
+        )
+        .unwrap();
+
+        let disabled = HttpClient::new(&base_url, "test-key").unwrap().performance_report().await;
+        assert!(disabled.metrics.is_none());
+        assert!(disabled.cache.is_none());
+        assert!(disabled.deduplication.is_none());
+        assert_eq!(disabled.cache_hit_rate, 0.0);
+
+        for _ in 0..2 {
+            let _: crate::types::api::ChannelInfo = client.get("api/channels/ch-1").await.unwrap();
+        }
+
+        let report = client.performance_report().await;
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["cache"]["hits"], 1);
+        assert_eq!(json["metrics"]["total_requests"], 1);
+
+        let metrics = report.metrics.expect("metrics enabled");
+        assert_eq!(metrics.total_requests, 1);
+        assert_eq!(metrics.total_success, 1);
+        assert_eq!(metrics.success_rate, 1.0);
+
+        let cache = report.cache.expect("cache enabled");
+        assert_eq!(cache.hits, 1);
+        assert_eq!(cache.misses, 1);
+        assert_eq!(cache.entries, 1);
+        assert_eq!(report.cache_hit_rate, 0.5);
+
+        let dedup = report.deduplication.expect("deduplication enabled");
+        assert_eq!(dedup.hits + dedup.misses, 0);
+    }
+
+    #[tokio::test]
+    async fn test_priority_rate_limit() {
+        let body = r#"{"message_id":"msg-1","timestamp":"2026-01-01T00:00:00Z","channel":"ch-1"}"#;
+        let (base_url, _) = spawn_mock_server(vec![MockResponse::json(200, body)]).await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .priority_rate_limit(MessagePriority::Bulk, 4.0, 1)
+            .build()
+            .unwrap();
+
+        let start = std::time::Instant::now();
+        for _ in 0..3 {
+            client
+                .publish_message("ch-1", "hi", Some(MessagePriority::Critical), None, None, None, None)
+                .await
+                .unwrap();
+        }
+        assert!(start.elapsed() < Duration::from_millis(200));
+
+        let start = std::time::Instant::now();
+        for _ in 0..3 {
+            client
+                .publish_message("ch-1", "hi", Some(MessagePriority::Bulk), None, None, None, None)
+                .await
+                .unwrap();
+        }
+        // One token is available up front, the other two refill at 4/s
+        assert!(start.elapsed() >= Duration::from_millis(450));
+    }
+
+    #[test]
+    fn test_sse_retry_field_sets_reconnect_delay() {
+        let config = SseConfig::new("https://api.example.com/sse", "test-key")
+            .with_reconnect_delay(Duration::from_secs(1));
+        let mut parser = SseParser::new();
+
+        // The run loop numbers the first reconnect 1
+        assert_eq!(
+            SseConnection::reconnect_delay(&config, 1, parser.retry()),
+            Duration::from_secs(1)
+        );
+
+        assert!(parser.process_line("retry: 5000").is_none());
+        assert_eq!(parser.retry(), Some(Duration::from_millis(5000)));
+        // The server interval is the first wait and the base of the backoff
+        assert_eq!(
+            SseConnection::reconnect_delay(&config, 1, parser.retry()),
+            Duration::from_secs(5)
+        );
+        assert_eq!(
+            SseConnection::reconnect_delay(&config, 2, parser.retry()),
+            Duration::from_secs(10)
+        );
+
+        // Invalid values are ignored and keep the previous interval
+        parser.process_line("retry: soon");
+        assert_eq!(parser.retry(), Some(Duration::from_millis(5000)));
+
+        // The interval survives a reconnect
+        parser.reset();
+        assert_eq!(parser.retry(), Some(Duration::from_millis(5000)));
+
+        // An excessive interval is capped at the connection timeout
+        parser.process_line("retry: 86400000");
+        let config = config.with_connection_timeout(Duration::from_secs(20));
+        assert_eq!(
+            SseConnection::reconnect_delay(&config, 1, parser.retry()),
+            Duration::from_secs(20)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_default_priority() {
+        let body = r#"{"message_id":"msg-1","timestamp":"2026-01-01T00:00:00Z","channel":"ch-1"}"#;
+        let (base_url, requests) = spawn_mock_server(vec![MockResponse::json(200, body)]).await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .default_priority(MessagePriority::High)
+            .build()
+            .unwrap();
+
+        client
+            .publish_message("ch-1", "hi", None, None, None, None, None)
+            .await
+            .unwrap();
+        client
+            .publish_message("ch-1", "hi", Some(MessagePriority::Low), None, None, None, None)
+            .await
+            .unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert!(requests[0].contains(r#""priority":75"#));
+        assert!(requests[1].contains(r#""priority":25"#));
+    }
+
+    #[tokio::test]
+    async fn test_register_public_key_auto() {
+        let pem = "-----BEGIN PUBLIC KEY-----\nMFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAE\nAAECAwQFBgcICQ==\n-----END PUBLIC KEY-----\n";
+        let rewrapped = "-----BEGIN PUBLIC KEY-----\r\nMFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAEAAECAwQFBgcICQ==\r\n-----END PUBLIC KEY-----";
+        let other = "-----BEGIN PUBLIC KEY-----\nMFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAE\n-----END PUBLIC KEY-----\n";
+
+        let id = channel_id_from_public_key(pem).unwrap();
+        assert!(id.starts_with("key-"));
+        assert_eq!(id.len(), 68);
+        assert_eq!(id, channel_id_from_public_key(pem).unwrap());
+        assert_eq!(id, channel_id_from_public_key(rewrapped).unwrap());
+        assert_ne!(id, channel_id_from_public_key(other).unwrap());
+        assert!(channel_id_from_public_key("not a key!").is_err());
+
+        let body = format!(r#"{{"channel_id":"{}","created_at":"2026-01-01T00:00:00Z"}}"#, id);
+        let (base_url, requests) = spawn_mock_server(vec![MockResponse::json(200, &body)]).await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .build()
+            .unwrap();
+
+        let registered = client.register_public_key_auto(pem, "ECC-SECP256K1", None).await.unwrap();
+        assert_eq!(registered, id);
+        assert!(requests.lock().unwrap()[0].starts_with(&format!("POST /api/register/{} ", id)));
+    }
+
+    #[tokio::test]
+    async fn test_http_crate_headers_and_interceptor() {
+        let body = r#"{"id":"ch-1","name":"news","type":"public","created_at":"2026-01-01T00:00:00Z","is_active":true}"#;
+        let (base_url, requests) = spawn_mock_server(vec![MockResponse::json(200, body)]).await;
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let seen_by_interceptor = seen.clone();
+
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .header(
+                http::HeaderName::from_static("x-tenant"),
+                http::HeaderValue::from_static("acme"),
+            )
+            .interceptor(move |parts: &mut http::request::Parts| {
+                seen_by_interceptor
+                    .lock()
+                    .unwrap()
+                    .push((parts.method.clone(), parts.uri.path().to_string()));
+                parts.headers.insert(
+                    http::HeaderName::from_static("x-intercepted"),
+                    http::HeaderValue::from_static("yes"),
+                );
+            })
+            .build()
+            .unwrap();
+
+        client.get_channel("ch-1").await.unwrap();
+
+        let raw = requests.lock().unwrap()[0].to_lowercase();
+        assert!(raw.contains("x-tenant: acme"));
+        assert!(raw.contains("x-intercepted: yes"));
+        assert!(raw.contains("x-api-key: test-key"));
+        assert_eq!(
+            seen.lock().unwrap().as_slice(),
+            &[(http::Method::GET, "/api/channels/ch-1".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_filtered() {
+        let (base_url, _) = spawn_mock_server(vec![MockResponse::sse(": ping\n\ndata: hello\n\n")]).await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .build()
+            .unwrap();
+
+        let all = collect_messages(client.subscribe("ch-1").await.unwrap()).await;
+        assert!(all.iter().any(|m| matches!(m, SseMessage::Heartbeat)));
+        assert!(all.iter().any(|m| matches!(m, SseMessage::Connected)));
+
+        let receiver = client
+            .subscribe_filtered("ch-1", &[SseEventType::Message])
+            .await
+            .unwrap();
+        let filtered = collect_messages(receiver).await;
+        assert!(filtered
+            .iter()
+            .all(|m| m.event_type() == SseEventType::Message));
+        assert!(!filtered.iter().any(|m| matches!(m, SseMessage::Heartbeat)));
+    }
+
+    #[tokio::test]
+    async fn test_cache_control_no_store() {
+        let response = MockResponse::json(200, CHANNEL_BODY).header("Cache-Control", "no-store");
+        let (base_url, requests) = spawn_mock_server(vec![response]).await;
+        let client = cached_http_client(&base_url);
+
+        for _ in 0..2 {
+            let _: ChannelInfo = client.get("api/channels/ch-1").await.unwrap();
+        }
+
+        assert_eq!(client.get_cache_size(), 0);
+        assert!(client.get_cache_ttl("api/channels/ch-1").is_none());
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_cache_control_max_age() {
+        let response = MockResponse::json(200, CHANNEL_BODY).header("Cache-Control", "public, max-age=120");
+        let (base_url, requests) = spawn_mock_server(vec![response]).await;
+        let client = cached_http_client(&base_url);
+
+        for _ in 0..2 {
+            let _: ChannelInfo = client.get("api/channels/ch-1").await.unwrap();
+        }
+
+        let ttl = client.get_cache_ttl("api/channels/ch-1").unwrap();
+        assert!(ttl > Duration::from_secs(115) && ttl <= Duration::from_secs(120));
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cache_control_absent_uses_default_ttl() {
+        let (base_url, requests) = spawn_mock_server(vec![MockResponse::json(200, CHANNEL_BODY)]).await;
+        let client = cached_http_client(&base_url);
+
+        for _ in 0..2 {
+            let _: ChannelInfo = client.get("api/channels/ch-1").await.unwrap();
+        }
+
+        let ttl = client.get_cache_ttl("api/channels/ch-1").unwrap();
+        assert!(ttl > Duration::from_secs(55) && ttl <= Duration::from_secs(60));
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_on_request_complete_hook() {
+        let (base_url, _) = spawn_mock_server(vec![
+            MockResponse::json(503, "{}"),
+            MockResponse::json(200, CHANNEL_BODY),
+            MockResponse::json(404, r#"{"error":"not found"}"#),
+        ])
+        .await;
+        let outcomes = Arc::new(Mutex::new(Vec::new()));
+        let recorded = outcomes.clone();
+
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .max_retries(1)
+            .initial_delay_ms(10)
+            .on_request_complete(Arc::new(move |outcome: &crate::utils::RequestOutcome| {
+                recorded.lock().unwrap().push(outcome.clone());
+            }))
+            .build()
+            .unwrap();
+
+        client.get_channel("ch-1").await.unwrap();
+        assert!(client.get_channel("missing").await.is_err());
+
+        let outcomes = outcomes.lock().unwrap();
+        assert_eq!(outcomes.len(), 2);
+
+        assert_eq!(outcomes[0].method, http::Method::GET);
+        assert_eq!(outcomes[0].endpoint, "/api/channels/ch-1");
+        assert_eq!(outcomes[0].status, Some(200));
+        assert_eq!(outcomes[0].attempts, 2);
+        assert!(outcomes[0].is_success());
+        assert!(outcomes[0].duration >= Duration::from_millis(10));
+
+        assert_eq!(outcomes[1].endpoint, "/api/channels/missing");
+        assert_eq!(outcomes[1].status, Some(404));
+        assert_eq!(outcomes[1].attempts, 1);
+        assert_eq!(outcomes[1].error_kind, Some(crate::types::error::ErrorKind::Api));
+    }
+
+    #[tokio::test]
+    async fn test_http2_prior_knowledge() {
+        let base_url = spawn_h2_server(CHANNEL_BODY).await;
+
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url.clone())
+            .api_key("test-key")
+            .http_version(crate::utils::HttpVersion::Http2PriorKnowledge)
+            .build()
+            .unwrap();
+        let channel = client.get_channel("ch-1").await.unwrap();
+        assert_eq!(channel.id, "ch-1");
+
+        let http1_client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .max_retries(0)
+            .timeout(Duration::from_secs(2))
+            .http_version(crate::utils::HttpVersion::Http1Only)
+            .build()
+            .unwrap();
+        assert!(http1_client.get_channel("ch-1").await.is_err());
+    }
+
+    #[test]
+    fn test_dedup_key_is_canonical() {
+        let dedup = crate::utils::RequestDeduplicator::default();
+        let first: serde_json::Value =
+            serde_json::from_str(r#"{"channel":"ch-1","options":{"ttl":60,"cache":true},"priority":75}"#).unwrap();
+        let second: serde_json::Value =
+            serde_json::from_str(r#"{"priority":75.0,"options":{"cache":true,"ttl":60.0},"channel":"ch-1"}"#).unwrap();
+        let different = serde_json::json!({"channel": "ch-1", "priority": 50});
+
+        let key = dedup.generate_key("api/publish", &Some(first.clone()));
+        assert_eq!(key, dedup.generate_key("api/publish", &Some(second)));
+        assert_ne!(key, dedup.generate_key("api/publish", &Some(different)));
+        assert_ne!(key, dedup.generate_key("api/channels", &Some(first)));
+        assert_eq!(
+            crate::utils::canonical_json(&serde_json::json!({"b": [1.0, 2.5], "a": null})),
+            r#"{"a":null,"b":[1,2.5]}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_conflict_on_create() {
+        let created = r#"{"id":"ch-1","name":"news","type":"public","created_at":"2026-01-01T00:00:00Z","is_active":true}"#;
+        let (base_url, requests) = spawn_mock_server(vec![
+            MockResponse::json(409, r#"{"error":"Channel already exists"}"#),
+            MockResponse::json(200, created),
+            MockResponse::json(409, r#"{"error":"API key name taken"}"#),
+        ])
+        .await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .build()
+            .unwrap();
+
+        // Get-or-create: fall back to fetching the channel on conflict
+        let channel = match client.create_channel("news", "public", None, None).await {
+            Ok(created) => created.id,
+            Err(e) if e.is_conflict() => client.get_channel("news").await.unwrap().id,
+            Err(e) => panic!("unexpected error: {}", e),
+        };
+        assert_eq!(channel, "ch-1");
+        assert!(requests.lock().unwrap()[1].starts_with("GET /api/channels/news "));
+
+        match client.create_api_key("ci", None, None, None).await {
+            Err(SecureNotifyError::Conflict { resource, message }) => {
+                assert_eq!(resource, "keys");
+                assert!(message.contains("name taken"));
+            }
+            other => panic!("expected Conflict, got {:?}", other.map(|r| r.id)),
+        }
+
+        let conflict = SecureNotifyError::Conflict {
+            resource: "channels".to_string(),
+            message: "exists".to_string(),
+        };
+        assert_eq!(conflict.status(), 409);
+        assert_eq!(conflict.kind(), crate::types::error::ErrorKind::Conflict);
+        assert!(!crate::types::error::is_retryable_error(&conflict));
+    }
+
+    #[tokio::test]
+    async fn test_hmac_request_signing() {
+        use crate::utils::{HmacSigner, RequestSigner};
+
+        let body = r#"{"message_id":"msg-1","timestamp":"2026-01-01T00:00:00Z","channel":"ch-1"}"#;
+        let (base_url, requests) = spawn_mock_server(vec![
+            MockResponse::json(503, "{}"),
+            MockResponse::json(200, body),
+        ])
+        .await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .max_retries(1)
+            .initial_delay_ms(1100)
+            .signer(Arc::new(HmacSigner::new("gateway-key", "secret")))
+            .build()
+            .unwrap();
+
+        client
+            .publish_message("ch-1", "hi", None, None, None, None, None)
+            .await
+            .unwrap();
+
+        // Every attempt carries its own signature and timestamp
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        let authorization = |raw: &str| {
+            raw.lines()
+                .find_map(|l| l.strip_prefix("authorization: "))
+                .map(str::to_string)
+                .expect("signed request")
+        };
+        let first = authorization(&requests[0]);
+        let second = authorization(&requests[1]);
+        assert!(first.starts_with("SN-HMAC-SHA256 Credential=gateway-key, SignedHeaders="));
+        assert!(first.contains("content-type;host;x-sn-content-sha256;x-sn-date"));
+        assert_ne!(first, second);
+
+        // The signature depends on the timestamp
+        let signer = HmacSigner::new("gateway-key", "secret");
+        let sign = |timestamp: std::time::SystemTime| {
+            let (mut parts, _) = http::Request::builder()
+                .method("POST")
+                .uri("https://api.example.com/api/publish/ch-1")
+                .body(())
+                .unwrap()
+                .into_parts();
+            signer.sign_at(&mut parts, b"{}", timestamp);
+            parts.headers[http::header::AUTHORIZATION].to_str().unwrap().to_string()
+        };
+        let epoch = std::time::UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+        assert_eq!(sign(epoch), sign(epoch));
+        assert_ne!(sign(epoch), sign(epoch + Duration::from_secs(1)));
+
+        let (mut parts, _) = http::Request::builder().uri("https://api.example.com/").body(()).unwrap().into_parts();
+        signer.sign(&mut parts, b"");
+        assert!(parts.headers.contains_key("x-sn-date"));
+    }
+
+    #[tokio::test]
+    async fn test_ack_and_nack_messages() {
+        let (base_url, requests) = spawn_mock_server(vec![
+            MockResponse::json(200, ""),
+            MockResponse::json(204, ""),
+            MockResponse::json(404, r#"{"error":"unknown message"}"#),
+        ])
+        .await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .build()
+            .unwrap();
+
+        client.ack_messages("ch-1", &["msg-1", "msg-2"]).await.unwrap();
+        client.nack_messages("ch-1", &["msg-3"]).await.unwrap();
+        let err = client.ack_messages("ch-1", &["missing"]).await.unwrap_err();
+        assert_eq!(err.status(), 404);
+
+        let requests = requests.lock().unwrap();
+        assert!(requests[0].starts_with("POST /api/publish/ch-1/ack "));
+        assert!(requests[0].ends_with(r#"{"message_ids":["msg-1","msg-2"]}"#));
+        assert!(requests[1].starts_with("POST /api/publish/ch-1/nack "));
+        assert!(requests[1].ends_with(r#"{"message_ids":["msg-3"]}"#));
+    }
+
+    #[tokio::test]
+    async fn test_service_unavailable_retry_after() {
+        let (base_url, _) = spawn_mock_server(vec![
+            MockResponse::json(503, "maintenance").header("Retry-After", "120"),
+            MockResponse::json(503, "overloaded"),
+        ])
+        .await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .max_retries(0)
+            .build()
+            .unwrap();
+
+        match client.get_channel("ch-1").await {
+            Err(SecureNotifyError::ServiceUnavailable { message, retry_after }) => {
+                assert_eq!(message, "maintenance");
+                assert_eq!(retry_after, Some(Duration::from_secs(120)));
+            }
+            other => panic!("expected ServiceUnavailable, got {:?}", other.map(|c| c.id)),
+        }
+
+        let err = client.get_channel("ch-1").await.unwrap_err();
+        assert!(matches!(err, SecureNotifyError::ServiceUnavailable { retry_after: None, .. }));
+        assert_eq!(err.status(), 503);
+        assert!(crate::types::error::is_retryable_error(&err));
+
+        let in_a_minute = time::OffsetDateTime::now_utc() + time::Duration::seconds(60);
+        let http_date = in_a_minute
+            .format(time::macros::format_description!(
+                "[weekday repr:short], [day] [month repr:short] [year] [hour]:[minute]:[second] GMT"
+            ))
+            .unwrap();
+        let parsed = crate::types::error::parse_retry_after(&http_date).unwrap();
+        assert!(parsed > Duration::from_secs(55) && parsed <= Duration::from_secs(60));
+        assert_eq!(crate::types::error::parse_retry_after("soon"), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_sse_reconnect_with_paused_time() {
+        let (base_url, requests) = spawn_mock_server(vec![
+            MockResponse::json(500, r#"{"error":"down"}"#),
+            MockResponse::sse("data: hello\n\n"),
+        ])
+        .await;
+        let config = SseConfig::new(format!("{}/api/subscribe/ch-1", base_url), "test-key")
+            .with_reconnect_delay(Duration::from_secs(30))
+            .with_max_reconnect_attempts(1);
+        let expected_backoff = SseConnection::reconnect_delay(&config, 1, None);
+        assert_eq!(expected_backoff, Duration::from_secs(30));
+
+        let real_start = std::time::Instant::now();
+        let (connection, mut receiver) = SseConnection::new(config);
+
+        let first = receiver.recv().await.unwrap();
+        assert!(
+            matches!(first, SseMessage::Error(_, Some(ReconnectAttempt { attempt: 1, max_attempts: 1 }))),
+            "{:?}",
+            first
+        );
+        assert_eq!(connection.reconnect_attempts(), 1);
+        while connection.state().await != SseState::Reconnecting {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(requests.lock().unwrap().len(), 1);
+
+        let backoff_start = tokio::time::Instant::now();
+        tokio::time::advance(expected_backoff + Duration::from_millis(1)).await;
+
+        let reconnected = receiver.recv().await.unwrap();
+        assert!(matches!(reconnected, SseMessage::Connected));
+        assert_eq!(requests.lock().unwrap().len(), 2);
+        assert!(backoff_start.elapsed() >= expected_backoff);
+        assert!(real_start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_message_envelope_from_sse_event() {
+        let event = SseEvent::new(
+            SseEventType::Message,
+            r#"{"message_id":"msg-1","channel":"ch-1","message":"hello","encrypted":true,"sender":"svc","priority":75,"timestamp":"2026-01-01T00:00:00Z"}"#.to_string(),
+            Some("1".to_string()),
+            None,
+        );
+        let envelope = MessageEnvelope::try_from(&event).unwrap();
+        assert_eq!(envelope.message_id, "msg-1");
+        assert_eq!(envelope.channel, "ch-1");
+        assert_eq!(envelope.message, "hello");
+        assert!(envelope.encrypted);
+        assert_eq!(envelope.sender.as_deref(), Some("svc"));
+        assert_eq!(envelope.priority, Some(75));
+        assert_eq!(envelope.timestamp, "2026-01-01T00:00:00Z");
+
+        let malformed = SseEvent::new(
+            SseEventType::Message,
+            r#"{"channel":"ch-1"}"#.to_string(),
+            None,
+            None,
+        );
+        assert!(matches!(
+            MessageEnvelope::try_from(&malformed),
+            Err(SecureNotifyError::SerializationError(..))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_retry_serialization_errors() {
+        let truncated = &CHANNEL_BODY[..CHANNEL_BODY.len() / 2];
+        for enabled in [false, true] {
+            let (base_url, requests) = spawn_mock_server(vec![
+                MockResponse::json(200, truncated),
+                MockResponse::json(200, CHANNEL_BODY),
+            ])
+            .await;
+            let client = SecureNotifyClient::builder()
+                .base_url(base_url)
+                .api_key("test-key")
+                .max_retries(1)
+                .initial_delay_ms(10)
+                .retry_serialization_errors(enabled)
+                .build()
+                .unwrap();
+
+            let result = client.get_channel("ch-1").await;
+            if enabled {
+                assert_eq!(result.unwrap().id, "ch-1");
+                assert_eq!(requests.lock().unwrap().len(), 2);
+            } else {
+                assert!(matches!(result, Err(SecureNotifyError::SerializationError(..))));
+                assert_eq!(requests.lock().unwrap().len(), 1);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_history_precedes_live_events_without_duplicates() {
+        let envelope = |id: &str, timestamp: &str| MessageEnvelope {
+            message_id: id.to_string(),
+            channel: "ch-1".to_string(),
+            message: format!("body of {}", id),
+            encrypted: false,
+            sender: None,
+            priority: None,
+            timestamp: timestamp.to_string(),
+        };
+        let live_event = |envelope: &MessageEnvelope| {
+            SseMessage::Event(SseEvent::new(
+                SseEventType::Message,
+                serde_json::to_string(envelope).unwrap(),
+                None,
+                None,
+            ))
+        };
+
+        let history = vec![
+            envelope("msg-1", "2026-01-01T00:00:01Z"),
+            envelope("msg-2", "2026-01-01T00:00:02Z"),
+        ];
+        let (live_tx, live_rx) = tokio::sync::mpsc::channel(10);
+        live_tx.send(SseMessage::Connected).await.unwrap();
+        live_tx.send(live_event(&history[1])).await.unwrap();
+        live_tx.send(live_event(&envelope("msg-3", "2026-01-01T00:00:03Z"))).await.unwrap();
+        drop(live_tx);
+
+        let messages = collect_messages(merge_history(history, live_rx)).await;
+        let ids: Vec<String> = messages
+            .iter()
+            .filter_map(|m| match m {
+                SseMessage::Event(event) => Some(MessageEnvelope::try_from(event).unwrap().message_id),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(ids, vec!["msg-1", "msg-2", "msg-3"]);
+        assert!(matches!(messages[2], SseMessage::Connected));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retries_exhausted_reports_attempts_and_elapsed() {
+        let config = crate::utils::RetryConfig::new()
+            .with_max_retries(2)
+            .with_initial_delay(Duration::from_secs(1))
+            .with_backoff_multiplier(2.0)
+            .with_jitter(false);
+
+        let result: crate::Result<()> = crate::utils::with_retry(
+            |_| async { Err(SecureNotifyError::NetworkError("reset".to_string(), None)) },
+            &config,
+        )
+        .await;
+        match result {
+            Err(SecureNotifyError::RetriesExhausted { source, attempts, elapsed }) => {
+                assert!(matches!(*source, SecureNotifyError::NetworkError(..)));
+                assert_eq!(attempts, 3);
+                assert_eq!(elapsed, Duration::from_secs(3));
+            }
+            other => panic!("expected RetriesExhausted, got {:?}", other),
+        }
+
+        // A failure that is never retried is returned as is
+        let result: crate::Result<()> = crate::utils::with_retry(
+            |_| async { Err(SecureNotifyError::AuthError("denied".to_string())) },
+            &config,
+        )
+        .await;
+        assert!(matches!(result, Err(SecureNotifyError::AuthError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_warm_up() {
+        let (base_url, requests) = spawn_mock_server(vec![
+            MockResponse::json(200, ""),
+            MockResponse::json(404, r#"{"error":"not found"}"#),
+        ])
+        .await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url.clone())
+            .api_key("test-key")
+            .build()
+            .unwrap();
+        client.warm_up().await.unwrap();
+        assert!(requests.lock().unwrap()[0].starts_with("HEAD / "));
+
+        // Lenient by default: a missing health endpoint still opens the connection
+        let lenient = SecureNotifyClient::builder()
+            .base_url(base_url.clone())
+            .api_key("test-key")
+            .warm_up_path("api/health")
+            .build()
+            .unwrap();
+        lenient.warm_up().await.unwrap();
+        assert!(requests.lock().unwrap()[1].starts_with("HEAD /api/health "));
+
+        let strict = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .warm_up_path("api/health")
+            .strict_warm_up(true)
+            .build()
+            .unwrap();
+        assert_eq!(strict.warm_up().await.unwrap_err().status(), 404);
+
+        let unreachable = SecureNotifyClient::builder()
+            .base_url("http://127.0.0.1:1")
+            .api_key("test-key")
+            .build()
+            .unwrap();
+        assert!(unreachable.warm_up().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_with_filter() {
+        let url = SseConfig::new("https://api.example.com/api/subscribe/ch-1", "test-key")
+            .with_filter("priority>=75 && sender=\"a&b\"")
+            .build_url()
+            .unwrap();
+        let parsed = url::Url::parse(&url).unwrap();
+        assert!(url.contains("filter=priority%3E%3D75+%26%26+sender%3D%22a%26b%22"));
+        assert_eq!(
+            parsed.query_pairs().find(|(k, _)| k == "filter").unwrap().1,
+            "priority>=75 && sender=\"a&b\""
+        );
+
+        let (base_url, requests) =
+            spawn_mock_server(vec![MockResponse::json(400, r#"{"error":"invalid filter"}"#)]).await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .build()
+            .unwrap();
+        let messages = collect_messages(client.subscribe_with_filter("ch-1", "priority>>1").await.unwrap()).await;
+        assert_eq!(messages.len(), 2);
+        assert!(matches!(messages[1], SseMessage::Disconnected(DisconnectReason::Error)));
+        match &messages[0] {
+            SseMessage::Error(e, reconnect) => {
+                assert!(reconnect.is_none());
+                assert_eq!(e.status(), 400);
+                assert!(e.to_string().contains("invalid filter"));
+            }
+            other => panic!("expected error, got {:?}", other),
+        }
+        assert_eq!(requests.lock().unwrap().len(), 1);
+        assert!(requests.lock().unwrap()[0].contains("filter=priority%3E%3E1"));
+    }
+
+    #[tokio::test]
+    async fn test_list_channels_streaming() {
+        use futures::StreamExt;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let release = Arc::new(tokio::sync::Notify::new());
+        let release_server = release.clone();
+        let channel = |i: usize| {
+            format!(
+                r#"{{"id":"ch-{}","name":"news, [{}]","type":"public","created_at":"2026-01-01T00:00:00Z","is_active":true}}"#,
+                i, i
+            )
+        };
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = socket.read(&mut buf).await;
+
+            let write_chunk = |data: String| format!("{:x}\r\n{}\r\n", data.len(), data);
+            let head = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nTransfer-Encoding: chunked\r\n\r\n";
+            socket.write_all(head.as_bytes()).await.unwrap();
+            socket.write_all(write_chunk(format!("[{},", channel(0))).as_bytes()).await.unwrap();
+
+            // Hold back the rest of the body until the client has seen the first item
+            release_server.notified().await;
+            let rest: Vec<String> = (1..1000).map(channel).collect();
+            let rest = format!("{}]", rest.join(","));
+            for part in rest.as_bytes().chunks(777) {
+                let part = String::from_utf8_lossy(part).to_string();
+                socket.write_all(write_chunk(part).as_bytes()).await.unwrap();
+            }
+            socket.write_all(b"0\r\n\r\n").await.unwrap();
+        });
+
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .build()
+            .unwrap();
+        let mut channels = client.list_channels_streaming(None, None, None);
+
+        let first = tokio::time::timeout(Duration::from_secs(5), channels.next())
+            .await
+            .expect("first item should arrive before the body is complete")
+            .unwrap()
+            .unwrap();
+        assert_eq!(first.id, "ch-0");
+        assert_eq!(first.name, "news, [0]");
+
+        release.notify_one();
+        let rest: Vec<ChannelInfo> = channels.map(|c| c.unwrap()).collect().await;
+        assert_eq!(rest.len(), 999);
+        assert_eq!(rest[998].id, "ch-999");
+
+        let mut parser = crate::utils::JsonArrayParser::new();
+        parser.push(b"[1, \"a\\\"]\" ");
+        assert_eq!(parser.next_element().unwrap().unwrap(), b"1");
+        assert_eq!(parser.next_element().unwrap().unwrap(), b"\"a\\\"]\"");
+        assert!(parser.next_element().unwrap().is_none());
+        assert!(parser.finish().is_err());
+        parser.push(b"]");
+        assert!(parser.next_element().unwrap().is_none());
+        assert!(parser.finish().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_metadata_validator() {
+        let (base_url, requests) = spawn_mock_server(vec![MockResponse::json(200, CHANNEL_BODY)]).await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .metadata_validator(Arc::new(|metadata: &serde_json::Value| {
+                match metadata.get("owner") {
+                    Some(_) => Ok(()),
+                    None => Err("missing required key 'owner'".to_string()),
+                }
+            }))
+            .build()
+            .unwrap();
+
+        let err = client
+            .create_channel("news", "public", None, Some(serde_json::json!({"team": "ops"})))
+            .await
+            .unwrap_err();
+        assert!(matches!(&err, SecureNotifyError::ValidationError(msg) if msg.contains("owner")));
+        assert_eq!(err.kind(), crate::types::error::ErrorKind::Validation);
+
+        let err = client
+            .register_public_key("ch-1", "pem", "RSA-2048", Some(serde_json::json!({})))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SecureNotifyError::ValidationError(_)));
+        assert!(requests.lock().unwrap().is_empty());
+
+        client
+            .create_channel("news", "public", None, Some(serde_json::json!({"owner": "ops"})))
+            .await
+            .unwrap();
+        client.create_channel("news", "public", None, None).await.unwrap();
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_error_source_chain() {
+        let client = SecureNotifyClient::builder()
+            .base_url("http://127.0.0.1:1")
+            .api_key("test-key")
+            .max_retries(0)
+            .build()
+            .unwrap();
+
+        let err = client.get_channel("ch-1").await.unwrap_err();
+        assert!(matches!(err, SecureNotifyError::ConnectionError(_, Some(_))));
+
+        let source = err
+            .error_source()
+            .and_then(|s| s.downcast_ref::<crate::types::error::ErrorSource>())
+            .expect("source should be preserved");
+        let reqwest_error = source
+            .inner()
+            .downcast_ref::<reqwest::Error>()
+            .expect("source should be the reqwest error");
+        assert!(reqwest_error.is_connect());
+
+        // The chain continues past the reqwest error to the underlying I/O failure
+        let chain: Vec<String> = err.chain().map(|e| e.to_string()).collect();
+        assert!(chain.len() >= 3, "chain: {:?}", chain);
+        assert_eq!(chain[0], err.to_string());
+
+        // Cloning keeps the chain intact
+        assert_eq!(err.clone().chain().count(), chain.len());
+
+        let plain = SecureNotifyError::NetworkError("reset".to_string(), None);
+        assert!(plain.error_source().is_none());
+        assert_eq!(plain.chain().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_list_messages_after() {
+        let page = r#"[
+            {"id":"msg-2","channel":"ch-1","message":"second","encrypted":false,"created_at":"2026-01-01T00:00:02Z"},
+            {"id":"msg-3","channel":"ch-1","message":"third","encrypted":true,"created_at":"2026-01-01T00:00:03Z","priority":75}
+        ]"#;
+        let (base_url, requests) = spawn_mock_server(vec![MockResponse::json(200, page)]).await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .build()
+            .unwrap();
+
+        let messages = client.list_messages_after("ch-1", "msg-1", Some(2)).await.unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].id, "msg-2");
+        assert_eq!(messages[1].priority, Some(75));
+        assert!(requests.lock().unwrap()[0]
+            .starts_with("GET /api/publish/ch-1/messages?after=msg-1&limit=2 "));
+
+        client.list_messages_after("ch-1", "a/b c", None).await.unwrap();
+        assert!(requests.lock().unwrap()[1]
+            .starts_with("GET /api/publish/ch-1/messages?after=a%2Fb+c "));
+    }
+
+    fn envelope_json(id: &str) -> String {
+        format!(
+            r#"{{"message_id":"{}","channel":"ch-1","message":"hi","encrypted":false,"timestamp":"2026-01-01T00:00:00Z"}}"#,
+            id
+        )
+    }
+
+    async fn next_event_id(receiver: &mut tokio::sync::mpsc::Receiver<SseMessage>) -> Option<String> {
+        loop {
+            match tokio::time::timeout(Duration::from_secs(5), receiver.recv()).await.ok()?? {
+                SseMessage::Event(event) => return Some(MessageEnvelope::try_from(&event).ok()?.message_id),
+                _ => continue,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_longpoll() {
+        let (base_url, requests) = spawn_mock_server(vec![
+            MockResponse::json(200, &format!("[{},{}]", envelope_json("msg-1"), envelope_json("msg-2"))),
+            MockResponse::json(200, &format!("[{}]", envelope_json("msg-3"))),
+            MockResponse::json(200, "[]"),
+        ])
+        .await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .build()
+            .unwrap();
+
+        let mut receiver = client.subscribe_longpoll("ch-1").await.unwrap();
+        assert!(matches!(receiver.recv().await, Some(SseMessage::Connected)));
+        for expected in ["msg-1", "msg-2", "msg-3"] {
+            assert_eq!(next_event_id(&mut receiver).await.as_deref(), Some(expected));
+        }
+        drop(receiver);
+
+        let requests = requests.lock().unwrap();
+        assert!(requests[0].starts_with("GET /api/subscribe/ch-1?wait=30 "));
+        assert!(requests[1].starts_with("GET /api/subscribe/ch-1?wait=30&after=msg-2 "));
+    }
+
+    #[tokio::test]
+    async fn test_longpoll_fallback_after_sse_failures() {
+        let (base_url, requests) = spawn_mock_server(vec![
+            MockResponse::json(500, "blocked"),
+            MockResponse::json(500, "blocked"),
+            MockResponse::json(200, &format!("[{}]", envelope_json("msg-1"))),
+            MockResponse::json(200, "[]"),
+        ])
+        .await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .longpoll_fallback_after(2)
+            .build()
+            .unwrap();
+
+        let mut receiver = client.subscribe("ch-1").await.unwrap();
+        assert_eq!(next_event_id(&mut receiver).await.as_deref(), Some("msg-1"));
+        drop(receiver);
+
+        let requests = requests.lock().unwrap();
+        assert!(requests[0].starts_with("GET /api/subscribe/ch-1?api_key="));
+        assert!(requests[1].starts_with("GET /api/subscribe/ch-1?api_key="));
+        assert!(requests[2].starts_with("GET /api/subscribe/ch-1?wait=30 "));
+    }
+
+    #[test]
+    fn test_adaptive_concurrency_decreases_on_errors_and_recovers() {
+        use crate::utils::AdaptiveConcurrencyLimiter;
+
+        let limiter = AdaptiveConcurrencyLimiter::new(2, 16);
+        let latency = Duration::from_millis(10);
+        assert_eq!(limiter.limit(), 16);
+
+        limiter.record(false, latency);
+        assert_eq!(limiter.limit(), 8);
+        limiter.record(false, latency);
+        limiter.record(false, latency);
+        limiter.record(false, latency);
+        assert_eq!(limiter.limit(), 2);
+
+        for _ in 0..200 {
+            limiter.record(true, latency);
+        }
+        assert_eq!(limiter.limit(), 16);
+
+        // A latency spike counts as overload even when the request succeeded
+        limiter.record(true, Duration::from_secs(1));
+        assert_eq!(limiter.limit(), 8);
+    }
+
+    #[tokio::test]
+    async fn test_client_adaptive_concurrency_limit() {
+        let (base_url, _requests) = spawn_mock_server(vec![
+            MockResponse::json(500, r#"{"error":"overloaded"}"#),
+            MockResponse::json(500, r#"{"error":"overloaded"}"#),
+            MockResponse::json(200, CHANNEL_BODY),
+        ])
+        .await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .max_retries(0)
+            .adaptive_concurrency(1, 8)
+            .build()
+            .unwrap();
+        assert_eq!(client.concurrency_limit(), Some(8));
+
+        assert!(client.get_channel("ch-1").await.is_err());
+        assert!(client.get_channel("ch-1").await.is_err());
+        assert_eq!(client.concurrency_limit(), Some(2));
+        client.get_channel("ch-1").await.unwrap();
+    }
+
+    #[cfg(feature = "cert-pinning")]
+    /// Spawn an HTTPS server for `localhost` with a fresh self-signed certificate.
+    ///
+    /// Returns the base URL, the SHA-256 fingerprint and DER encoding of the
+    /// certificate, and a count of the HTTP requests that reached the server.
+    async fn spawn_tls_server() -> (String, [u8; 32], Vec<u8>, Arc<std::sync::atomic::AtomicUsize>) {
+        use base64::Engine;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use sha2::{Digest, Sha256};
+
+        static CERTIFICATE: std::sync::OnceLock<(String, String)> = std::sync::OnceLock::new();
+        let (cert_pem, key_pem) = CERTIFICATE.get_or_init(|| {
+            let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+            (cert.serialize_pem().unwrap(), cert.serialize_private_key_pem())
+        });
+
+        let der: String = cert_pem.lines().filter(|l| !l.starts_with("-----")).collect();
+        let der = base64::engine::general_purpose::STANDARD.decode(der).unwrap();
+        let fingerprint: [u8; 32] = Sha256::digest(&der).into();
+
+        let identity = tokio_native_tls::native_tls::Identity::from_pkcs8(
+            cert_pem.as_bytes(),
+            key_pem.as_bytes(),
+        )
+        .unwrap();
+        let acceptor: tokio_native_tls::TlsAcceptor =
+            tokio_native_tls::native_tls::TlsAcceptor::new(identity).unwrap().into();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let requests = Arc::new(AtomicUsize::new(0));
+
+        let counter = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                let Ok(mut stream) = acceptor.accept(socket).await else {
+                    continue;
+                };
+                let mut raw = Vec::new();
+                let mut buf = [0u8; 4096];
+                while !raw.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match stream.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => raw.extend_from_slice(&buf[..n]),
+                    }
+                }
+                if !raw.is_empty() {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                }
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    CHANNEL_BODY.len(),
+                    CHANNEL_BODY
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+                let _ = stream.shutdown().await;
+            }
+        });
+
+        (format!("https://localhost:{}", port), fingerprint, der, requests)
+    }
+
+    #[cfg(feature = "cert-pinning")]
+    #[tokio::test]
+    async fn test_pinned_certificate_accepts_matching_fingerprint() {
+        use std::sync::atomic::Ordering;
+
+        let (base_url, fingerprint, der, requests) = spawn_tls_server().await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .max_retries(0)
+            .add_root_certificate(der)
+            .pin_certificate(fingerprint)
+            .build()
+            .unwrap();
+
+        let channel = client.get_channel("ch-1").await.unwrap();
+        assert_eq!(channel.id, "ch-1");
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[cfg(feature = "cert-pinning")]
+    #[tokio::test]
+    async fn test_pinned_certificate_rejects_mismatched_fingerprint() {
+        use std::sync::atomic::Ordering;
+
+        let (base_url, mut fingerprint, der, requests) = spawn_tls_server().await;
+        fingerprint[0] ^= 0xff;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .max_retries(0)
+            .add_root_certificate(der)
+            .pin_certificate(fingerprint)
+            .build()
+            .unwrap();
+
+        let error = client.get_channel("ch-1").await.unwrap_err();
+        assert!(matches!(error, SecureNotifyError::ConnectionError(..)), "{:?}", error);
+        assert!(error.to_string().contains("pinned fingerprint"));
+        // The handshake fails, so the request and its API key are never sent
+        assert_eq!(requests.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_query_builder_encodes_special_characters() {
+        use crate::utils::QueryBuilder;
+
+        let endpoint = QueryBuilder::new()
+            .param("filter", "a&b=c d")
+            .optional("limit", Some(10))
+            .optional("offset", None::<u32>)
+            .build("api/channels");
+        assert_eq!(endpoint, "api/channels?filter=a%26b%3Dc+d&limit=10");
+        assert_eq!(QueryBuilder::new().build("api/keys"), "api/keys");
+    }
+
+    #[tokio::test]
+    async fn test_list_channels_encodes_query_values() {
+        let (base_url, requests) = spawn_mock_server(vec![MockResponse::json(200, "[]")]).await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .build()
+            .unwrap();
+
+        client.list_channels(Some("public&private"), Some(5), None).await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert!(
+            requests[0].starts_with("GET /api/channels?type=public%26private&limit=5 "),
+            "{}",
+            requests[0]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_message_with_validators_reports_not_modified() {
+        use crate::{Conditional, Validators};
+
+        let body = r#"{"id":"msg-1","channel":"ch-1","message":"hi","encrypted":false,"created_at":"2026-01-01T00:00:00Z"}"#;
+        let (base_url, requests) = spawn_mock_server(vec![
+            MockResponse::json(200, body)
+                .header("ETag", "W/\"v1\"")
+                .header("Last-Modified", "Thu, 01 Jan 2026 00:00:00 GMT"),
+            MockResponse::json(304, ""),
+        ])
+        .await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .build()
+            .unwrap();
+
+        let first = client
+            .get_message("ch-1", "msg-1", Some(&Validators::default()))
+            .await
+            .unwrap();
+        let Conditional::Modified { value, validators } = first else {
+            panic!("expected a modified response");
+        };
+        assert_eq!(value.id, "msg-1");
+        assert_eq!(validators.etag.as_deref(), Some("W/\"v1\""));
+
+        let second = client.get_message("ch-1", "msg-1", Some(&validators)).await.unwrap();
+        assert!(second.is_not_modified());
+
+        // Without validators the fetch is unconditional but still reports the validators
+        let (base_url, requests_plain) = spawn_mock_server(vec![
+            MockResponse::json(200, body).header("ETag", "\"v2\""),
+            MockResponse::json(200, "null"),
+        ])
+        .await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .max_retries(0)
+            .build()
+            .unwrap();
+        let plain = client.get_message("ch-1", "msg-1", None).await.unwrap();
+        let Conditional::Modified { value, validators } = plain else {
+            panic!("expected a modified response");
+        };
+        assert_eq!(value.id, "msg-1");
+        assert_eq!(validators.etag.as_deref(), Some("\"v2\""));
+        assert!(!requests_plain.lock().unwrap()[0].to_lowercase().contains("if-none-match"));
+
+        // Only a 304 means unchanged; a 200 without a message is an error
+        let error = client.get_message("ch-1", "msg-1", None).await.unwrap_err();
+        assert!(matches!(error, SecureNotifyError::SerializationError(..)), "{:?}", error);
+
+        let requests = requests.lock().unwrap();
+        let second_request = requests[1].to_lowercase();
+        assert!(!requests[0].to_lowercase().contains("if-none-match"));
+        assert!(second_request.contains("if-none-match: w/\"v1\""));
+        assert!(second_request.contains("if-modified-since: thu, 01 jan 2026 00:00:00 gmt"));
+    }
+
+    #[test]
+    fn test_publish_response_queue_fields() {
+        use crate::types::api::MessagePublishResponse;
+
+        let body = r#"{"message_id":"msg-1","timestamp":"2026-01-01T00:00:00Z","channel":"ch-1","queue_position":3,"estimated_delivery_seconds":12}"#;
+        let response: MessagePublishResponse = serde_json::from_str(body).unwrap();
+        assert_eq!(response.queue_position, Some(3));
+        assert_eq!(response.estimated_delivery_seconds, Some(12));
+
+        let body = r#"{"message_id":"msg-1","timestamp":"2026-01-01T00:00:00Z","channel":"ch-1"}"#;
+        let response: MessagePublishResponse = serde_json::from_str(body).unwrap();
+        assert_eq!(response.message_id, "msg-1");
+        assert_eq!(response.queue_position, None);
+        assert_eq!(response.estimated_delivery_seconds, None);
+    }
+
+    #[tokio::test]
+    async fn test_export_prometheus() {
+        let (base_url, _requests) = spawn_mock_server(vec![MockResponse::json(200, CHANNEL_BODY)]).await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url.clone())
+            .api_key("test-key")
+            .build()
+            .unwrap();
+        assert!(client.export_prometheus().is_none());
+
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .enable_metrics(true)
+            .build()
+            .unwrap();
+        client.get_channel("ch-1").await.unwrap();
+        client.get_channel("ch-1").await.unwrap();
+
+        let text = client.export_prometheus().unwrap();
+        assert!(text.contains("# TYPE securenotify_request_duration_ms summary\n"));
+        assert!(text.contains("securenotify_requests_total 2\n"));
+        assert!(text.contains("securenotify_endpoint_requests_total{endpoint=\"/api/channels/ch-1\"} 2\n"));
+        assert!(text.contains("securenotify_endpoint_success_ratio{endpoint=\"/api/channels/ch-1\"} 1\n"));
+        assert!(text.contains("securenotify_request_duration_ms{endpoint=\"/api/channels/ch-1\",quantile=\"0.99\"} "));
+
+        // Every sample line is `name{labels} value` with a numeric value
+        for line in text.lines().filter(|l| !l.starts_with('#')) {
+            let (series, value) = line.rsplit_once(' ').unwrap();
+            assert!(series.starts_with("securenotify_"), "{}", line);
+            assert!(value.parse::<f64>().is_ok(), "{}", line);
+        }
+    }
+
+    /// Serve SSE streams that stay open with heartbeats, answering DELETE with `null`
+    async fn spawn_sse_stream_server() -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let captured = requests.clone();
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let captured = captured.clone();
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                    captured.lock().unwrap().push(request.clone());
+
+                    if request.starts_with("DELETE ") {
+                        let _ = socket
+                            .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 4\r\nConnection: close\r\n\r\nnull")
+                            .await;
+                        return;
+                    }
+
+                    // Keep the stream open, sending only heartbeats
+                    let _ = socket
+                        .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\r\n")
+                        .await;
+                    loop {
+                        if socket.write_all(b":ping\n\n").await.is_err() {
+                            break;
+                        }
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                    }
+                });
+            }
+        });
+
+        (format!("http://{}", addr), requests)
+    }
+
+    #[tokio::test]
+    async fn test_sse_idle_unsubscribe() {
+        let (base_url, requests) = spawn_sse_stream_server().await;
+
+        let config = SseConfig::new(format!("{}/api/subscribe/ch-1", base_url), "test-key")
+            .with_idle_unsubscribe_after(Some(Duration::from_millis(300)));
+        let (_connection, mut receiver) = SseConnection::new(config);
+
+        let mut heartbeats = 0;
+        loop {
+            let message = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+                .await
+                .expect("idle unsubscribe did not fire")
+                .expect("receiver closed before disconnect");
+            match message {
+                SseMessage::Heartbeat => heartbeats += 1,
+                SseMessage::Disconnected(DisconnectReason::IdleTimeout) => break,
+                SseMessage::Connected => {}
+                other => panic!("unexpected message: {:?}", other),
+            }
+        }
+        assert!(heartbeats > 0, "heartbeats should not reset the idle timer");
+
+        let requests = requests.lock().unwrap();
+        let delete = requests.iter().find(|r| r.starts_with("DELETE /api/subscribe/ch-1 ")).unwrap();
+        assert!(delete.to_lowercase().contains("x-api-key: test-key"));
+    }
+
+    #[tokio::test]
+    async fn test_sse_token_provider_refreshes_on_reconnect() {
+        use futures::FutureExt;
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let (base_url, requests) = spawn_mock_server(vec![
+            MockResponse::json(401, r#"{"error":"token expired"}"#),
+            MockResponse::sse(""),
+        ])
+        .await;
+
+        let issued = Arc::new(AtomicU32::new(0));
+        let counter = issued.clone();
+        let config = SseConfig::new(format!("{}/api/subscribe/ch-1", base_url), "static-key")
+            .with_reconnect_delay(Duration::from_millis(10))
+            .with_token_provider(Arc::new(move || {
+                let n = counter.fetch_add(1, Ordering::SeqCst) + 1;
+                async move { Ok(format!("token-{}", n)) }.boxed()
+            }));
+        let (_connection, mut receiver) = SseConnection::new(config);
+
+        loop {
+            let message = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            if matches!(message, SseMessage::Disconnected(_)) {
+                break;
+            }
+        }
+
+        assert_eq!(issued.load(Ordering::SeqCst), 2);
+        let requests = requests.lock().unwrap();
+        assert!(requests[0].starts_with("GET /api/subscribe/ch-1?api_key=token-1 "));
+        assert!(requests[1].starts_with("GET /api/subscribe/ch-1?api_key=token-2 "));
+    }
+
+    #[tokio::test]
+    async fn test_publish_fanout_reports_per_channel_results() {
+        let body = r#"{"message_id":"msg-1","timestamp":"2026-01-01T00:00:00Z","channel":"ch"}"#;
+        let (base_url, requests) = spawn_mock_server(vec![
+            MockResponse::json(200, body),
+            MockResponse::json(500, r#"{"error":"boom"}"#),
+            MockResponse::json(200, body),
+        ])
+        .await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .max_retries(0)
+            .build()
+            .unwrap();
+
+        let channels = ["ch-a", "ch-b", "ch-c"];
+        let results = client.publish_fanout(&channels, "hello", None).await.unwrap();
+        assert_eq!(results.len(), 3);
+
+        // Publishes run concurrently, so find the channel that received the 500
+        let failed = {
+            let requests = requests.lock().unwrap();
+            assert_eq!(requests.len(), 3);
+            let path = requests[1].split_whitespace().nth(1).unwrap().to_string();
+            path.trim_start_matches("/api/publish/").to_string()
+        };
+        for (channel, result) in channels.iter().zip(&results) {
+            if *channel == failed {
+                assert_eq!(result.as_ref().unwrap_err().status(), 500);
+            } else {
+                assert_eq!(result.as_ref().unwrap().message_id, "msg-1");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ordered_publish_preserves_call_order_across_retries() {
+        let body = r#"{"message_id":"msg-1","timestamp":"2026-01-01T00:00:00Z","channel":"ch-1"}"#;
+        let (base_url, requests) = spawn_mock_server(vec![
+            MockResponse::json(500, r#"{"error":"try again"}"#),
+            MockResponse::json(200, body),
+        ])
+        .await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .max_retries(1)
+            .initial_delay_ms(50)
+            .ordered_publish(true)
+            .build()
+            .unwrap();
+
+        let messages: Vec<String> = (0..5).map(|i| format!("m{}", i)).collect();
+        let publishes = messages
+            .iter()
+            .map(|message| client.publish_message("ch-1", message, None, None, None, None, None));
+        for result in futures::future::join_all(publishes).await {
+            result.unwrap();
+        }
+
+        // The first publish fails once; its retry must still land before the rest
+        let observed: Vec<String> = requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|request| {
+                let body = &request[request.find("\r\n\r\n").unwrap() + 4..];
+                serde_json::from_str::<serde_json::Value>(body).unwrap()["message"]
+                    .as_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(observed, ["m0", "m0", "m1", "m2", "m3", "m4"]);
+    }
+
+    /// Build an HS256 JWT with the given claims
+    fn make_jwt(claims: &serde_json::Value, secret: &[u8]) -> String {
+        use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        use base64::Engine;
+        use hmac::{Hmac, Mac};
+
+        let header = URL_SAFE_NO_PAD.encode(br#"{"alg":"HS256","typ":"JWT"}"#);
+        let payload = URL_SAFE_NO_PAD.encode(claims.to_string());
+        let signing_input = format!("{}.{}", header, payload);
+        let mut mac = Hmac::<sha2::Sha256>::new_from_slice(secret).unwrap();
+        mac.update(signing_input.as_bytes());
+        format!("{}.{}", signing_input, URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes()))
+    }
+
+    #[test]
+    fn test_api_key_claims_valid_jwt() {
+        let exp = time::OffsetDateTime::now_utc().unix_timestamp() + 3600;
+        let token = make_jwt(
+            &serde_json::json!({"exp": exp, "scope": "publish subscribe", "sub": "svc-1"}),
+            b"secret",
+        );
+        let client = SecureNotifyClient::builder().api_key(token).build().unwrap();
+
+        let claims = client.api_key_claims().unwrap();
+        assert_eq!(claims.exp, Some(exp));
+        assert_eq!(claims.sub.as_deref(), Some("svc-1"));
+        assert_eq!(claims.scopes().collect::<Vec<_>>(), ["publish", "subscribe"]);
+        assert!(!claims.is_expired());
+
+        assert_eq!(client.api_key_claims_verified(b"secret").unwrap(), claims);
+        let error = client.api_key_claims_verified(b"wrong").unwrap_err();
+        assert!(matches!(error, SecureNotifyError::AuthError(_)));
+    }
+
+    #[test]
+    fn test_api_key_claims_expired_jwt() {
+        let token = make_jwt(&serde_json::json!({"exp": 1_000_000_000, "sub": "svc-1"}), b"secret");
+        let client = SecureNotifyClient::builder().api_key(token).build().unwrap();
+
+        let claims = client.api_key_claims().unwrap();
+        assert!(claims.is_expired());
+        assert_eq!(claims.scope, None);
+    }
+
+    #[test]
+    fn test_api_key_claims_non_jwt() {
+        let client = SecureNotifyClient::builder().api_key("sk_live_plain_key").build().unwrap();
+
+        let error = client.api_key_claims().unwrap_err();
+        assert!(matches!(error, SecureNotifyError::ValidationError(_)));
+        assert!(error.to_string().contains("not a JWT"));
+    }
+
+    #[test]
+    fn test_cache_snapshot_round_trip_drops_expired() {
+        use crate::utils::{CacheSnapshot, CacheSnapshotEntry, ResponseCache};
+
+        let cache: ResponseCache<String> = ResponseCache::default();
+        cache.set("GET:api/channels/a".to_string(), "a".to_string(), None);
+        cache.set("GET:api/channels/b".to_string(), "b".to_string(), Some(Duration::from_secs(300)));
+
+        let mut snapshot = cache.export();
+        assert_eq!(snapshot.entries.len(), 2);
+        snapshot.entries.push(CacheSnapshotEntry {
+            key: "GET:api/channels/stale".to_string(),
+            value: "stale".to_string(),
+            expires_at_ms: 1_000,
+        });
+
+        // Snapshots are meant to be persisted, so go through JSON
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let snapshot: CacheSnapshot<String> = serde_json::from_str(&json).unwrap();
+
+        let restored: ResponseCache<String> = ResponseCache::default();
+        assert_eq!(restored.import(snapshot), 2);
+        assert_eq!(restored.get("GET:api/channels/a").as_deref(), Some("a"));
+        assert_eq!(restored.get("GET:api/channels/b").as_deref(), Some("b"));
+        assert_eq!(restored.get("GET:api/channels/stale"), None);
+        assert!(restored.ttl("GET:api/channels/b").unwrap() > Duration::from_secs(250));
+    }
+
+    #[tokio::test]
+    async fn test_deduplicator_snapshot_round_trip_drops_expired() {
+        use crate::utils::{DeduplicatorSnapshotEntry, RequestDeduplicator};
+
+        let deduplicator = RequestDeduplicator::default();
+        deduplicator
+            .execute("api/channels", None, || async { Ok("fresh".to_string()) }, true)
+            .await
+            .unwrap();
+
+        let mut snapshot = deduplicator.export().await;
+        assert_eq!(snapshot.entries.len(), 1);
+        snapshot.entries.push(DeduplicatorSnapshotEntry {
+            key: "req_stale".to_string(),
+            value: "stale".to_string(),
+            completed_at_ms: 1_000,
+        });
+
+        let restored = RequestDeduplicator::default();
+        assert_eq!(restored.import(snapshot).await, 1);
+        let result = restored
+            .execute("api/channels", None, || async { Ok("refetched".to_string()) }, true)
+            .await;
+        assert_eq!(result.as_deref(), Ok("fresh"));
+    }
+
+    #[tokio::test]
+    async fn test_create_channel_if_absent() {
+        let (base_url, requests) = spawn_mock_server(vec![
+            MockResponse::json(201, CHANNEL_BODY),
+            MockResponse::json(412, r#"{"error":"channel exists"}"#),
+        ])
+        .await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .max_retries(0)
+            .build()
+            .unwrap();
+
+        let created = client.create_channel_if_absent("news", "public", None, None).await.unwrap();
+        assert_eq!(created.id, "ch-1");
+
+        match client.create_channel_if_absent("news", "public", None, None).await {
+            Err(SecureNotifyError::Conflict { resource, message }) => {
+                assert_eq!(resource, "channels");
+                assert!(message.contains("channel exists"));
+            }
+            other => panic!("expected Conflict, got {:?}", other.map(|r| r.id)),
+        }
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        for request in requests.iter() {
+            assert!(request.starts_with("POST /api/channels "));
+            assert!(request.to_lowercase().contains("if-none-match: *"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sse_comments_emitted_only_when_enabled() {
+        let (base_url, _requests) =
+            spawn_mock_server(vec![MockResponse::sse(": server-time=1767225600\n\n")]).await;
+
+        for emit in [true, false] {
+            let config = SseConfig::new(format!("{}/api/subscribe/ch-1", base_url), "test-key")
+                .with_emit_comments(emit);
+            let (_connection, mut receiver) = SseConnection::new(config);
+
+            let mut comments = Vec::new();
+            loop {
+                let message = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+                    .await
+                    .unwrap()
+                    .unwrap();
+                match message {
+                    SseMessage::Comment(comment) => comments.push(comment),
+                    SseMessage::Disconnected(_) => break,
+                    _ => {}
+                }
+            }
+
+            if emit {
+                assert_eq!(comments, ["server-time=1767225600"]);
+            } else {
+                assert!(comments.is_empty());
+            }
+        }
+    }
+
+    fn traceparent_header(request: &str) -> Option<String> {
+        request.lines().find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.eq_ignore_ascii_case("traceparent").then(|| value.trim().to_string())
+        })
+    }
+
+    #[tokio::test]
+    async fn test_trace_propagation_headers() {
+        let (base_url, requests) = spawn_mock_server(vec![MockResponse::json(200, CHANNEL_BODY)]).await;
+        let outcomes = Arc::new(Mutex::new(Vec::new()));
+        let recorded = outcomes.clone();
+
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url.clone())
+            .api_key("test-key")
+            .trace_propagation(true)
+            .on_request_complete(Arc::new(move |outcome: &crate::utils::RequestOutcome| {
+                recorded.lock().unwrap().push(outcome.clone());
+            }))
+            .build()
+            .unwrap();
+
+        client.get_channel("ch-1").await.unwrap();
+        let ambient = crate::utils::TraceContext::new_random().with_tracestate("vendor=abc");
+        ambient
+            .clone()
+            .scope(async { client.get_channel("ch-1").await.unwrap() })
+            .await;
+
+        let requests = requests.lock().unwrap().clone();
+        let outcomes = outcomes.lock().unwrap().clone();
+        assert_eq!(requests.len(), 2);
+
+        for (request, outcome) in requests.iter().zip(&outcomes) {
+            let header = traceparent_header(request).expect("traceparent header");
+            let parts: Vec<&str> = header.split('-').collect();
+            assert_eq!(parts.len(), 4);
+            assert_eq!(parts[0], "00");
+            assert_eq!(parts[1].len(), 32);
+            assert_eq!(parts[2].len(), 16);
+            assert_eq!(parts[3], "01");
+            assert!(parts[1..]
+                .iter()
+                .all(|part| part.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))));
+            assert!(parts[1].bytes().any(|b| b != b'0'));
+            assert!(parts[2].bytes().any(|b| b != b'0'));
+            assert_eq!(outcome.trace_id.as_deref(), Some(parts[1]));
+        }
+
+        // The second request joins the ambient trace
+        let joined = traceparent_header(&requests[1]).unwrap();
+        assert_eq!(outcomes[1].trace_id, Some(ambient.trace_id_hex()));
+        assert_ne!(joined, ambient.traceparent());
+        assert!(requests[1].to_lowercase().contains("tracestate: vendor=abc"));
+        assert_ne!(outcomes[0].trace_id, outcomes[1].trace_id);
+
+        // Disabled by default
+        let (base_url, requests) = spawn_mock_server(vec![MockResponse::json(200, CHANNEL_BODY)]).await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .build()
+            .unwrap();
+        client.get_channel("ch-1").await.unwrap();
+        assert!(traceparent_header(&requests.lock().unwrap()[0]).is_none());
+    }
+
+    #[test]
+    fn test_lenient_numeric_fields() {
+        use crate::types::api::{MessagePublishRequest, MessagePublishResponse, QueueStatus};
+
+        for priority in [r#""75""#, "75", "75.0"] {
+            let request: MessagePublishRequest = serde_json::from_str(&format!(
+                r#"{{"message":"hi","priority":{}}}"#,
+                priority
+            ))
+            .unwrap();
+            assert_eq!(request.priority, Some(75));
+        }
+        let request: MessagePublishRequest = serde_json::from_str(r#"{"message":"hi"}"#).unwrap();
+        assert_eq!(request.priority, None);
+        assert!(serde_json::from_str::<MessagePublishRequest>(r#"{"message":"hi","priority":"300"}"#).is_err());
+        assert!(serde_json::from_str::<MessagePublishRequest>(r#"{"message":"hi","priority":"high"}"#).is_err());
+
+        for (total, wait) in [("12345", "3"), (r#""12345""#, r#""3""#), ("12345.0", "3.0")] {
+            let status: QueueStatus = serde_json::from_str(&format!(
+                r#"{{"total":{},"by_priority":{{}},"estimated_wait_seconds":{}}}"#,
+                total, wait
+            ))
+            .unwrap();
+            assert_eq!(status.total, 12345);
+            assert_eq!(status.estimated_wait_seconds, 3);
+        }
+        assert!(serde_json::from_str::<QueueStatus>(
+            r#"{"total":1.5,"by_priority":{},"estimated_wait_seconds":0}"#
+        )
+        .is_err());
+
+        let response: MessagePublishResponse = serde_json::from_str(
+            r#"{"message_id":"m1","timestamp":"t","channel":"c","queue_position":"4"}"#,
+        )
+        .unwrap();
+        assert_eq!(response.queue_position, Some(4));
+        assert_eq!(response.estimated_delivery_seconds, None);
+    }
+
+    #[tokio::test]
+    async fn test_unsubscribe_all_closes_receivers() {
+        let (base_url, requests) = spawn_sse_stream_server().await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .build()
+            .unwrap();
+
+        let mut receivers = [
+            client.subscribe("ch-1").await.unwrap(),
+            client.subscribe("ch-2").await.unwrap(),
+        ];
+        for receiver in receivers.iter_mut() {
+            loop {
+                let message = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+                    .await
+                    .unwrap()
+                    .unwrap();
+                if matches!(message, SseMessage::Connected) {
+                    break;
+                }
+            }
+        }
+
+        client.unsubscribe_all().await.unwrap();
+
+        for receiver in receivers.iter_mut() {
+            let closed = tokio::time::timeout(Duration::from_secs(5), async {
+                while receiver.recv().await.is_some() {}
+            })
+            .await;
+            assert!(closed.is_ok(), "receiver still open after unsubscribe_all");
+        }
+
+        let deletes = |requests: &Mutex<Vec<String>>| {
+            let mut deletes: Vec<String> = requests
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|r| r.starts_with("DELETE "))
+                .map(|r| r.lines().next().unwrap().to_string())
+                .collect();
+            deletes.sort();
+            deletes
+        };
+        assert_eq!(
+            deletes(&requests),
+            ["DELETE /api/subscribe/ch-1 HTTP/1.1", "DELETE /api/subscribe/ch-2 HTTP/1.1"]
+        );
+
+        // Nothing is left to tear down
+        client.unsubscribe_all().await.unwrap();
+        assert_eq!(deletes(&requests).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_sdk_version_in_user_agent() {
+        let version = crate::sdk_version();
+        assert!(!version.is_empty());
+        assert_eq!(version, crate::SDK_VERSION);
+
+        let (base_url, requests) = spawn_mock_server(vec![MockResponse::json(200, CHANNEL_BODY)]).await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .build()
+            .unwrap();
+        client.get_channel("ch-1").await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        let user_agent = requests[0]
+            .lines()
+            .find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                name.eq_ignore_ascii_case("user-agent").then(|| value.trim().to_string())
+            })
+            .unwrap();
+        assert_eq!(user_agent, format!("SecureNotify-Rust/{}", version));
+    }
+
+    #[tokio::test]
+    async fn test_list_subscription_events() {
+        use crate::types::api::SubscriptionEventKind;
+
+        let (base_url, requests) = spawn_mock_server(vec![MockResponse::json(
+            200,
+            r#"[
+                {"type":"connect","timestamp":"2026-01-01T00:00:00Z"},
+                {"type":"message_count","timestamp":"2026-01-01T00:05:00Z","message_count":"42"},
+                {"type":"disconnect","timestamp":"2026-01-01T00:10:00Z","metadata":{"reason":"client_closed"}},
+                {"type":"paused","timestamp":"2026-01-01T00:11:00Z"}
+            ]"#,
+        )])
+        .await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .build()
+            .unwrap();
+
+        let events = client.list_subscription_events("sub-1", Some(10), Some(20)).await.unwrap();
+
+        let kinds: Vec<_> = events.iter().map(|e| e.event_type).collect();
+        assert_eq!(
+            kinds,
+            [
+                SubscriptionEventKind::Connect,
+                SubscriptionEventKind::MessageCount,
+                SubscriptionEventKind::Disconnect,
+                SubscriptionEventKind::Unknown,
+            ]
+        );
+        assert_eq!(events[0].timestamp, "2026-01-01T00:00:00Z");
+        assert_eq!(events[0].message_count, None);
+        assert_eq!(events[1].message_count, Some(42));
+        assert_eq!(events[2].metadata.as_ref().unwrap()["reason"], "client_closed");
+
+        let requests = requests.lock().unwrap();
+        assert!(requests[0].starts_with("GET /api/subscribe/sub-1/events?limit=10&offset=20 "));
+    }
+
+    /// Serve publishes, failing channel `bad` at once and answering others after a delay
+    async fn spawn_slow_publish_server(delay: Duration) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let captured = requests.clone();
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let captured = captured.clone();
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                    captured.lock().unwrap().push(request.clone());
+
+                    let path = request.split_whitespace().nth(1).unwrap_or_default();
+                    let channel = path.trim_start_matches("/api/publish/").to_string();
+                    let (status, body) = if channel == "bad" {
+                        ("400 Bad Request", r#"{"error":"rejected"}"#.to_string())
+                    } else {
+                        tokio::time::sleep(delay).await;
+                        (
+                            "200 OK",
+                            format!(r#"{{"message_id":"m-{0}","timestamp":"t","channel":"{0}"}}"#, channel),
+                        )
+                    };
+                    let response = format!(
+                        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+
+        (format!("http://{}", addr), requests)
+    }
+
+    #[tokio::test]
+    async fn test_publish_fanout_fail_fast_stops_early() {
+        use crate::utils::BatchPolicy;
+
+        let (base_url, requests) = spawn_slow_publish_server(Duration::from_millis(300)).await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .max_retries(0)
+            .batch_policy(BatchPolicy::FailFast)
+            .build()
+            .unwrap();
+
+        let channels: Vec<String> = std::iter::once("bad".to_string())
+            .chain((0..20).map(|i| format!("ch-{}", i)))
+            .collect();
+        let channels: Vec<&str> = channels.iter().map(String::as_str).collect();
+
+        let start = std::time::Instant::now();
+        let error = client.publish_fanout(&channels, "hello", None).await.unwrap_err();
+        assert!(matches!(error, SecureNotifyError::ApiError { status: 400, .. }), "{:?}", error);
+        assert!(start.elapsed() < Duration::from_millis(300), "did not fail fast");
+
+        // Cancelled publishes are never sent
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let sent = requests.lock().unwrap().len();
+        assert!(sent <= crate::managers::publish_manager::FANOUT_CONCURRENCY, "sent {} publishes", sent);
+
+        // Without failures the results keep channel order
+        let results = client
+            .publish_fanout(&["ch-a", "ch-b", "ch-c"], "hello", None)
+            .await
+            .unwrap();
+        let channels: Vec<String> = results.into_iter().map(|r| r.unwrap().channel).collect();
+        assert_eq!(channels, ["ch-a", "ch-b", "ch-c"]);
+    }
+
+    #[tokio::test]
+    async fn test_publish_fanout_best_effort_completes_all() {
+        let (base_url, requests) = spawn_slow_publish_server(Duration::from_millis(20)).await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .max_retries(0)
+            .build()
+            .unwrap();
+
+        let channels: Vec<String> = std::iter::once("bad".to_string())
+            .chain((0..20).map(|i| format!("ch-{}", i)))
+            .collect();
+        let channels: Vec<&str> = channels.iter().map(String::as_str).collect();
+
+        let results = client.publish_fanout(&channels, "hello", None).await.unwrap();
+        assert_eq!(results.len(), 21);
+        assert!(results[0].is_err());
+        assert!(results[1..].iter().all(Result::is_ok));
+        assert_eq!(requests.lock().unwrap().len(), 21);
+    }
+
+    #[tokio::test]
+    async fn test_quota_consumption() {
+        use crate::utils::Operation;
+
+        let (base_url, _requests) = spawn_slow_publish_server(Duration::ZERO).await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .build()
+            .unwrap();
+
+        assert_eq!(client.estimated_cost(&Operation::Publish), 1);
+        assert_eq!(client.estimated_cost(&Operation::PublishFanout(5)), 5);
+        assert_eq!(client.estimated_cost(&Operation::Subscribe), 1);
+        assert_eq!(client.quota_consumed(), 0);
+
+        let channels = ["ch-1", "ch-2", "ch-3", "ch-4", "ch-5"];
+        client.publish_fanout(&channels, "hello", None).await.unwrap();
+        assert_eq!(client.quota_consumed(), 5);
+
+        client.publish_message("ch-1", "hello", None, None, None, None, None).await.unwrap();
+        assert_eq!(client.quota_consumed(), 6);
+
+        let _receiver = client.subscribe("ch-1").await.unwrap();
+        assert_eq!(client.quota_consumed(), 7);
+    }
+
+    #[tokio::test]
+    async fn test_request_and_response_serialization_errors_differ() {
+        use crate::types::error::ErrorKind;
+
+        let (base_url, requests) = spawn_mock_server(vec![MockResponse::json(200, "not json")]).await;
+        let client = HttpClient::new(&base_url, "test-key").unwrap();
+
+        // JSON object keys must be strings, so this body cannot be serialized
+        let body: std::collections::HashMap<(u8, u8), u8> = std::collections::HashMap::from([((1, 2), 3)]);
+        let err = client
+            .post::<serde_json::Value, _>("api/publish/ch-1", &body)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SecureNotifyError::RequestSerializationError(..)), "{:?}", err);
+        assert_eq!(err.kind(), ErrorKind::RequestSerialization);
+        assert!(err.error_source().is_some());
+        assert!(requests.lock().unwrap().is_empty(), "nothing should be sent");
+
+        let err = client
+            .post::<serde_json::Value, _>("api/publish/ch-1", &serde_json::json!({"message": "hi"}))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SecureNotifyError::SerializationError(..)), "{:?}", err);
+        assert_eq!(err.kind(), ErrorKind::Serialization);
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_sse_gzip_negotiation() {
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+
+        // Compresses only when the client asks for gzip
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    let request = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+
+                    let (encoding, body) = if request.contains("accept-encoding: gzip") {
+                        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+                        encoder.write_all(b": compressed stream\n\n").unwrap();
+                        ("Content-Encoding: gzip\r\n", encoder.finish().unwrap())
+                    } else {
+                        ("", b": plain stream\n\n".to_vec())
+                    };
+                    let head = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n",
+                        encoding,
+                        body.len()
+                    );
+                    let _ = socket.write_all(head.as_bytes()).await;
+                    let _ = socket.write_all(&body).await;
+                });
+            }
+        });
+
+        for (compression, expected) in [(true, "compressed stream"), (false, "plain stream")] {
+            let config = SseConfig::new(format!("http://{}/api/subscribe/ch-1", addr), "test-key")
+                .with_emit_comments(true)
+                .with_compression(compression);
+            let (_connection, mut receiver) = SseConnection::new(config);
+
+            let mut comments = Vec::new();
+            loop {
+                let message = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+                    .await
+                    .unwrap()
+                    .unwrap();
+                match message {
+                    SseMessage::Comment(comment) => comments.push(comment),
+                    SseMessage::Disconnected(_) => break,
+                    _ => {}
+                }
+            }
+            assert_eq!(comments, [expected]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sse_fail_fast_on_status() {
+        let (base_url, requests) =
+            spawn_mock_server(vec![MockResponse::json(404, r#"{"error":"channel not found"}"#)]).await;
+        let config = SseConfig::new(format!("{}/api/subscribe/missing", base_url), "test-key")
+            .with_reconnect_delay(Duration::from_millis(10))
+            .with_max_reconnect_attempts(3)
+            .fail_fast_on_status(vec![401, 403, 404]);
+        let (connection, mut receiver) = SseConnection::new(config);
+
+        let message = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(message, SseMessage::Error(ref e, None) if e.status() == 404), "{:?}", message);
+
+        let message = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(message, SseMessage::Disconnected(DisconnectReason::Error)), "{:?}", message);
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(connection.state().await, SseState::Failed);
+        assert!(receiver.try_recv().is_err(), "no further messages expected");
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_sse_fail_fast_still_retries_other_statuses() {
+        let (base_url, requests) = spawn_mock_server(vec![MockResponse::json(503, "unavailable")]).await;
+        let config = SseConfig::new(format!("{}/api/subscribe/ch-1", base_url), "test-key")
+            .with_reconnect_delay(Duration::from_millis(10))
+            .with_max_reconnect_attempts(2)
+            .fail_fast_on_status(vec![401, 403, 404]);
+        let (connection, mut receiver) = SseConnection::new(config);
+
+        let mut errors = Vec::new();
+        while let Ok(Some(message)) = tokio::time::timeout(Duration::from_secs(5), receiver.recv()).await {
+            if let SseMessage::Error(error, _) = message {
+                let done = matches!(error, SecureNotifyError::ConnectionError(..));
+                errors.push(error);
+                if done {
+                    break;
+                }
+            }
+        }
+
+        // Three failed attempts, then the attempts-exhausted error
+        assert_eq!(errors.len(), 4);
+        assert!(errors[..3].iter().all(|e| e.status() == 503));
+        assert_eq!(requests.lock().unwrap().len(), 3);
+        assert_eq!(connection.state().await, SseState::Failed);
+    }
+
+    #[test]
+    fn test_queue_priority_distribution() {
+        use crate::types::api::QueueStatus;
+
+        let status: QueueStatus = serde_json::from_str(
+            r#"{"total":10,"by_priority":{"100":1,"high":"2","NORMAL":5,"25":2,"unknown":7},"estimated_wait_seconds":3}"#,
+        )
+        .unwrap();
+        let distribution = status.priority_distribution();
+        assert_eq!(
+            distribution,
+            [
+                (MessagePriority::Critical, 0.1),
+                (MessagePriority::High, 0.2),
+                (MessagePriority::Normal, 0.5),
+                (MessagePriority::Low, 0.2),
+                (MessagePriority::Bulk, 0.0),
+            ]
+        );
+
+        for by_priority in ["{}", "null", r#"{"50":0}"#] {
+            let status: QueueStatus = serde_json::from_str(&format!(
+                r#"{{"total":0,"by_priority":{},"estimated_wait_seconds":0}}"#,
+                by_priority
+            ))
+            .unwrap();
+            let distribution = status.priority_distribution();
+            assert_eq!(distribution.len(), 5);
+            assert!(distribution.iter().all(|(_, fraction)| *fraction == 0.0));
+        }
+    }
+
+    /// Serve responses whose body is cut off after the headers
+    async fn spawn_truncating_server() -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let captured = requests.clone();
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = [0u8; 4096];
+                let n = socket.read(&mut buf).await.unwrap_or(0);
+                captured.lock().unwrap().push(String::from_utf8_lossy(&buf[..n]).to_string());
+                let _ = socket
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 100\r\n\r\n{\"message_id\"")
+                    .await;
+            }
+        });
+
+        (format!("http://{}", addr), requests)
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn attempts_recorder() -> (Arc<Mutex<Vec<u32>>>, Arc<dyn Fn(&crate::utils::RequestOutcome) + Send + Sync>) {
+        let attempts = Arc::new(Mutex::new(Vec::new()));
+        let recorded = attempts.clone();
+        let hook = Arc::new(move |outcome: &crate::utils::RequestOutcome| {
+            recorded.lock().unwrap().push(outcome.attempts);
+        });
+        (attempts, hook)
+    }
+
+    #[tokio::test]
+    async fn test_safe_post_retries_pre_send_failure() {
+        // Nothing listens on this port, so every connection is refused
+        let addr = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap();
+        let (attempts, hook) = attempts_recorder();
+        let client = SecureNotifyClient::builder()
+            .base_url(format!("http://{}", addr))
+            .api_key("test-key")
+            .max_retries(2)
+            .initial_delay_ms(10)
+            .safe_post_retries(true)
+            .on_request_complete(hook)
+            .build()
+            .unwrap();
+
+        let err = client
+            .publish_message("ch-1", "hello", None, None, None, None, None)
+            .await
+            .unwrap_err();
+        assert!(crate::types::error::is_pre_response_error(err.root_cause()), "{:?}", err);
+        assert_eq!(*attempts.lock().unwrap(), [3]);
+    }
+
+    #[tokio::test]
+    async fn test_safe_post_retries_mid_response_failure() {
+        for (safe, expected_attempts) in [(true, 1), (false, 2)] {
+            let (base_url, requests) = spawn_truncating_server().await;
+            let (attempts, hook) = attempts_recorder();
+            let client = SecureNotifyClient::builder()
+                .base_url(base_url)
+                .api_key("test-key")
+                .max_retries(1)
+                .initial_delay_ms(10)
+                .safe_post_retries(safe)
+                .on_request_complete(hook)
+                .build()
+                .unwrap();
+
+            let err = client
+                .publish_message("ch-1", "hello", None, None, None, None, None)
+                .await
+                .unwrap_err();
+            assert!(!crate::types::error::is_pre_response_error(err.root_cause()), "{:?}", err);
+            assert_eq!(*attempts.lock().unwrap(), [expected_attempts]);
+            assert_eq!(requests.lock().unwrap().len(), expected_attempts as usize);
+        }
+
+        // Error responses are not retried either
+        let (base_url, requests) = spawn_mock_server(vec![MockResponse::json(503, "{}")]).await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .max_retries(2)
+            .initial_delay_ms(10)
+            .safe_post_retries(true)
+            .build()
+            .unwrap();
+        assert!(client.publish_message("ch-1", "hello", None, None, None, None, None).await.is_err());
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_channel_info_diff() {
+        use crate::types::api::FieldChange;
+        use serde_json::json;
+
+        let old: ChannelInfo = serde_json::from_str(CHANNEL_BODY).unwrap();
+        let mut new = old.clone();
+        assert!(old.diff(&new).is_empty());
+
+        new.name = "Renamed".to_string();
+        new.is_active = !old.is_active;
+        new.expires_at = Some("2027-01-01T00:00:00Z".to_string());
+        new.creator = Some("someone-else".to_string());
+
+        assert_eq!(
+            old.diff(&new),
+            [
+                FieldChange { field: "name", old: json!(old.name), new: json!("Renamed") },
+                FieldChange { field: "is_active", old: json!(old.is_active), new: json!(!old.is_active) },
+                FieldChange { field: "expires_at", old: json!(old.expires_at), new: json!("2027-01-01T00:00:00Z") },
+            ]
+        );
+
+        // Metadata is compared canonically
+        let mut a = old.clone();
+        let mut b = old.clone();
+        a.metadata = Some(json!({"x": 1, "y": [1.0]}));
+        b.metadata = Some(json!({"y": [1], "x": 1}));
+        assert!(a.diff(&b).is_empty());
+        b.metadata = None;
+        assert_eq!(
+            a.diff(&b),
+            [FieldChange { field: "metadata", old: a.metadata.clone().unwrap(), new: json!(null) }]
+        );
+    }
+
+    #[test]
+    fn test_api_key_info_diff() {
+        use crate::types::api::{ApiKeyInfo, FieldChange};
+        use serde_json::json;
+
+        let old: ApiKeyInfo = serde_json::from_value(json!({
+            "id": "key-1",
+            "key_prefix": "sk_live",
+            "name": "ci",
+            "permissions": ["publish"],
+            "is_active": true,
+            "created_at": "2026-01-01T00:00:00Z"
+        }))
+        .unwrap();
+        let mut new = old.clone();
+        new.permissions = Some(vec!["publish".to_string(), "subscribe".to_string()]);
+        new.is_active = false;
+        new.last_used_at = Some("2026-02-01T00:00:00Z".to_string());
+
+        assert_eq!(
+            old.diff(&new),
+            [
+                FieldChange {
+                    field: "permissions",
+                    old: json!(["publish"]),
+                    new: json!(["publish", "subscribe"]),
+                },
+                FieldChange { field: "is_active", old: json!(true), new: json!(false) },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_api_error_field_errors() {
+        use crate::types::api::ApiErrorDetails;
+
+        let body = r#"{
+            "success": false,
+            "error": {
+                "code": "VALIDATION_FAILED",
+                "message": "Invalid channel",
+                "details": {"request_id": "req-1"},
+                "field_errors": {
+                    "name": ["is required", "must be at most 64 characters"],
+                    "type": ["must be one of: public, encrypted"]
+                }
+            }
+        }"#;
+        let (base_url, _) = spawn_mock_server(vec![MockResponse::json(422, body)]).await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .max_retries(0)
+            .build()
+            .unwrap();
+
+        let error = client.get_channel("ch-1").await.unwrap_err();
+        assert_eq!(error.status(), 422);
+        let details = error.details().expect("details");
+        assert_eq!(details.code, "VALIDATION_FAILED");
+        assert_eq!(details.details, Some(serde_json::json!({"request_id": "req-1"})));
+        let field_errors = details.field_errors.as_ref().unwrap();
+        assert_eq!(field_errors["name"], ["is required", "must be at most 64 characters"]);
+        assert_eq!(field_errors["type"], ["must be one of: public, encrypted"]);
+
+        // A bare error object parses too, and the extra fields are optional
+        let details = ApiErrorDetails::from_body(r#"{"code":"NOT_FOUND","message":"gone"}"#).unwrap();
+        assert_eq!(details.field_errors, None);
+        assert_eq!(ApiErrorDetails::from_body("Internal Server Error"), None);
+    }
+
+    #[tokio::test]
+    async fn test_publish_dedup_window() {
+        let first = r#"{"message_id":"msg-1","timestamp":"2026-01-01T00:00:00Z","channel":"ch-1"}"#;
+        let second = r#"{"message_id":"msg-2","timestamp":"2026-01-01T00:00:01Z","channel":"ch-1"}"#;
+        let (base_url, requests) = spawn_mock_server(vec![
+            MockResponse::json(200, first),
+            MockResponse::json(200, second),
+        ])
+        .await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .publish_dedup_window(Duration::from_millis(300))
+            .build()
+            .unwrap();
+
+        let a = client.publish_message("ch-1", "hello", None, None, None, None, None).await.unwrap();
+        let b = client.publish_message("ch-1", "hello", None, None, None, None, None).await.unwrap();
+        assert_eq!(a.message_id, "msg-1");
+        assert_eq!(b.message_id, "msg-1");
+        assert_eq!(requests.lock().unwrap().len(), 1);
+
+        // A different message is sent, and so is a repeat once the window has passed
+        client.publish_message("ch-1", "other", None, None, None, None, None).await.unwrap();
+        assert_eq!(requests.lock().unwrap().len(), 2);
+        tokio::time::sleep(Duration::from_millis(350)).await;
+        let c = client.publish_message("ch-1", "hello", None, None, None, None, None).await.unwrap();
+        assert_eq!(c.message_id, "msg-2");
+        assert_eq!(requests.lock().unwrap().len(), 3);
+    }
+
+    /// Subscriber recording the fields of every event
+    #[cfg(feature = "tracing")]
+    #[derive(Default)]
+    struct EventRecorder {
+        events: Arc<Mutex<Vec<std::collections::HashMap<String, String>>>>,
+    }
+
+    #[cfg(feature = "tracing")]
+    impl tracing::Subscriber for EventRecorder {
+        fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            tracing::span::Id::from_u64(1)
+        }
+
+        fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+
+        fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+
+        fn event(&self, event: &tracing::Event<'_>) {
+            struct Fields(std::collections::HashMap<String, String>);
+            impl tracing::field::Visit for Fields {
+                fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+                    self.0.insert(field.name().to_string(), format!("{:?}", value));
+                }
+            }
+            let mut fields = Fields(Default::default());
+            event.record(&mut fields);
+            self.events.lock().unwrap().push(fields.0);
+        }
+
+        fn enter(&self, _: &tracing::span::Id) {}
+
+        fn exit(&self, _: &tracing::span::Id) {}
+    }
+
+    #[cfg(feature = "tracing")]
+    #[tokio::test]
+    async fn test_retry_emits_tracing_events() {
+        use crate::utils::{with_retry, RetryConfig};
+
+        let recorder = EventRecorder::default();
+        let events = recorder.events.clone();
+        let _guard = tracing::subscriber::set_default(recorder);
+
+        let statuses = [503u16, 429];
+        let config = RetryConfig::new()
+            .with_initial_delay(Duration::from_millis(5))
+            .with_jitter(false);
+        let result = with_retry(
+            |attempt| async move {
+                match statuses.get(attempt as usize) {
+                    Some(&status) => Err(SecureNotifyError::ApiError {
+                        code: status.to_string(),
+                        message: "try again".to_string(),
+                        status,
+                        details: None,
+                    }),
+                    None => Ok(attempt),
+                }
+            },
+            &config,
+        )
+        .await;
+        assert_eq!(result.unwrap(), 2);
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        for (index, (event, status)) in events.iter().zip(statuses).enumerate() {
+            assert_eq!(event["attempt"], (index + 1).to_string());
+            assert_eq!(event["status"], status.to_string());
+            assert_eq!(event["error_kind"], "\"api\"");
+        }
+        assert_eq!(events[0]["delay_ms"], "5");
+        assert_eq!(events[1]["delay_ms"], "10");
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_ready() {
+        // Delays the stream's response headers, then sends heartbeats
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let accepted = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let responded = accepted.clone();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = socket.read(&mut buf).await;
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            responded.store(true, std::sync::atomic::Ordering::SeqCst);
+            let _ = socket
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\r\n")
+                .await;
+            while socket.write_all(b":ping\n\n").await.is_ok() {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        });
+
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .build()
+            .unwrap();
+        let mut receiver = client.subscribe_ready("ch-1", Duration::from_secs(5)).await.unwrap();
+        assert!(accepted.load(std::sync::atomic::Ordering::SeqCst), "returned before the server accepted");
+
+        // `Connected` was consumed; the stream is already live
+        let message = tokio::time::timeout(Duration::from_secs(5), receiver.recv()).await.unwrap();
+        assert!(matches!(message, Some(SseMessage::Heartbeat)), "{:?}", message);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_ready_fails_when_refused() {
+        // Nothing listens on a port whose listener was dropped
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .build()
+            .unwrap();
+        let error = client.subscribe_ready("ch-1", Duration::from_secs(5)).await.unwrap_err();
+        assert!(matches!(error, SecureNotifyError::ConnectionError(..)), "{:?}", error);
+
+        // A server that accepts but never responds times out
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (_socket, _) = listener.accept().await.unwrap();
+            tokio::time::sleep(Duration::from_secs(10)).await;
+        });
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .build()
+            .unwrap();
+        let error = client.subscribe_ready("ch-1", Duration::from_millis(200)).await.unwrap_err();
+        assert!(matches!(error, SecureNotifyError::TimeoutError(..)), "{:?}", error);
+    }
+
+    #[test]
+    fn test_sharded_metrics_concurrent_recording() {
+        use crate::utils::MetricsCollector;
+
+        let collector = MetricsCollector::new_sharded(4);
+        let endpoints = ["api/publish/a", "api/publish/b", "api/channels", "api/keys"];
+        std::thread::scope(|scope| {
+            for thread in 0..8 {
+                let collector = &collector;
+                scope.spawn(move || {
+                    for i in 0..100 {
+                        let endpoint = endpoints[i % endpoints.len()];
+                        collector.record(endpoint, (thread * 100 + i) as f64, i % 10 != 0);
+                    }
+                });
+            }
+        });
+
+        let summary = collector.get_summary();
+        assert_eq!(summary.total_requests, 800);
+        assert_eq!(summary.total_failures, 80);
+        assert_eq!(summary.endpoint_count, 4);
+        for endpoint in endpoints {
+            let stats = collector.get_stats(endpoint).unwrap();
+            assert_eq!(stats.count, 200, "{}", endpoint);
+        }
+        let all = collector.get_all_stats();
+        let min = all.values().map(|s| s.min_duration_ms).fold(f64::MAX, f64::min);
+        let max = all.values().map(|s| s.max_duration_ms).fold(0.0, f64::max);
+        assert_eq!((min, max), (0.0, 799.0));
+
+        collector.record("api/keys", 1.0, true);
+        collector.reset();
+        assert_eq!(collector.get_summary().total_requests, 0);
+    }
+
+    #[test]
+    fn test_sse_config_for_channel() {
+        // Without the feature a pinned client fails to build
+        let pinned_certificate = cfg!(feature = "cert-pinning").then_some([7; 32]);
+        let http_client = HttpClient::from_config(HttpClientConfig {
+            base_url: "https://notify.example.com/v2/".to_string(),
+            api_key: "test-key".to_string(),
+            timeout: Duration::from_secs(12),
+            pinned_certificate,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let config = http_client.sse_config_for("ch-1");
+        assert_eq!(config.url, "https://notify.example.com/v2/api/subscribe/ch-1");
+        assert_eq!(config.api_key, http_client.config().api_key);
+        assert_eq!(config.connection_timeout, http_client.config().timeout);
+        assert_eq!(config.pinned_certificate, pinned_certificate);
+    }
+
+    #[tokio::test]
+    async fn test_empty_success_body() {
+        let (base_url, _) = spawn_mock_server(vec![
+            MockResponse::json(204, ""),
+            MockResponse::json(200, ""),
+        ])
+        .await;
+        let http_client = HttpClient::from_config(HttpClientConfig {
+            base_url,
+            api_key: "test-key".to_string(),
+            max_retries: 0,
+            ..Default::default()
+        })
+        .unwrap();
+
+        http_client.delete::<()>("api/subscribe/ch-1").await.unwrap();
+
+        let error = http_client.get::<ChannelInfo>("api/channels/ch-1").await.unwrap_err();
+        match error {
+            SecureNotifyError::SerializationError(message, _) => {
+                assert!(message.contains("empty 200 OK response"), "{}", message)
+            }
+            other => panic!("expected a serialization error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_retry_presets() {
+        use crate::utils::{RetryConfig, RetryPreset};
+
+        let aggressive = RetryConfig::aggressive();
+        assert_eq!(aggressive.max_retries, 5);
+        assert_eq!(aggressive.initial_delay, Duration::from_millis(200));
+        assert_eq!(aggressive.max_delay, Duration::from_secs(5));
+        assert_eq!(aggressive.backoff_multiplier, 1.5);
+
+        let conservative = RetryConfig::conservative();
+        assert_eq!(conservative.max_retries, 2);
+        assert_eq!(conservative.initial_delay, Duration::from_secs(2));
+        assert_eq!(conservative.max_delay, Duration::from_secs(60));
+        assert_eq!(conservative.backoff_multiplier, 3.0);
+
+        assert_eq!(RetryConfig::none().max_retries, 0);
+        assert_eq!(RetryPreset::Aggressive.config().max_retries, 5);
+        assert_eq!(RetryPreset::Conservative.config().max_retries, 2);
+        assert_eq!(RetryPreset::None.config().max_retries, 0);
+    }
+
+    #[tokio::test]
+    async fn test_retry_preset_none_disables_retries() {
+        use crate::utils::RetryPreset;
+
+        let (base_url, requests) = spawn_mock_server(vec![
+            MockResponse::json(500, r#"{"error":"boom"}"#),
+            MockResponse::json(200, CHANNEL_BODY),
+        ])
+        .await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .retry_preset(RetryPreset::None)
+            .build()
+            .unwrap();
+
+        let error = client.get_channel("ch-1").await.unwrap_err();
+        assert_eq!(error.status(), 500);
+        assert!(!matches!(error, SecureNotifyError::RetriesExhausted { .. }));
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_text_and_bytes() {
+        let csv = "id,name\nch-1,news\nch-2,alerts\n";
+        let (base_url, requests) = spawn_mock_server(vec![
+            MockResponse::json(503, "busy"),
+            MockResponse::json(200, csv).header("Content-Type", "text/csv"),
+        ])
+        .await;
+        let http_client = HttpClient::from_config(HttpClientConfig {
+            base_url,
+            api_key: "test-key".to_string(),
+            initial_delay_ms: 10,
+            enable_metrics: true,
+            ..Default::default()
+        })
+        .unwrap();
+
+        // Retried past the 503 and returned verbatim
+        assert_eq!(http_client.get_text("api/export.csv").await.unwrap(), csv);
+        assert_eq!(requests.lock().unwrap().len(), 2);
+        assert_eq!(http_client.get_metrics_summary().unwrap().total_requests, 1);
+
+        let binary: Vec<u8> = (0..=255).collect();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let body = binary.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = [0u8; 4096];
+                let _ = socket.read(&mut buf).await;
+                let head = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                let _ = socket.write_all(head.as_bytes()).await;
+                let _ = socket.write_all(&body).await;
+            }
+        });
+        let http_client = HttpClient::new(&base_url, "test-key").unwrap();
+        assert_eq!(http_client.get_bytes("api/blob").await.unwrap(), binary);
+
+        // Bytes that are not UTF-8 are rejected as text
+        let error = http_client.get_text("api/blob").await.unwrap_err();
+        assert!(matches!(error, SecureNotifyError::SerializationError(..)), "{:?}", error);
+    }
+
+    #[tokio::test]
+    async fn test_transfer_channel() {
+        let transferred = r#"{"id":"ch-1","name":"news","type":"public","creator":"user-2","created_at":"2026-01-01T00:00:00Z","is_active":true}"#;
+        let (base_url, requests) = spawn_mock_server(vec![
+            MockResponse::json(200, transferred),
+            MockResponse::json(403, r#"{"error":"not the owner"}"#),
+        ])
+        .await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .build()
+            .unwrap();
+
+        let channel = client.transfer_channel("ch-1", "user-2").await.unwrap();
+        assert_eq!(channel.creator.as_deref(), Some("user-2"));
+        let request = requests.lock().unwrap()[0].clone();
+        assert!(request.starts_with("POST /api/channels/ch-1/transfer "), "{}", request);
+        assert!(request.contains(r#"{"new_owner":"user-2"}"#), "{}", request);
+
+        let error = client.transfer_channel("ch-1", "user-3").await.unwrap_err();
+        match error {
+            SecureNotifyError::AuthError(message) => assert!(message.contains("not the owner"), "{}", message),
+            other => panic!("expected an auth error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_connection_quality_sampler() {
+        use crate::utils::connection::QualitySampler;
+
+        let sampler = QualitySampler::default();
+        assert_eq!(sampler.quality(), Default::default());
+
+        // Gaps of 100ms, 300ms, 100ms and 300ms
+        for gap in [0, 100, 300, 100, 300] {
+            tokio::time::advance(Duration::from_millis(gap)).await;
+            sampler.record(&SseMessage::Heartbeat);
+        }
+        tokio::time::advance(Duration::from_millis(50)).await;
+
+        let quality = sampler.quality();
+        assert_eq!(quality.avg_heartbeat_gap, Some(Duration::from_millis(200)));
+        assert_eq!(quality.jitter, Some(Duration::from_millis(100)));
+        assert_eq!(quality.last_message_age, Some(Duration::from_millis(50)));
+
+        // Other messages only refresh the message age
+        sampler.record(&SseMessage::Comment("tick".to_string()));
+        let quality = sampler.quality();
+        assert_eq!(quality.avg_heartbeat_gap, Some(Duration::from_millis(200)));
+        assert_eq!(quality.last_message_age, Some(Duration::ZERO));
+
+        sampler.reset_heartbeats();
+        assert_eq!(sampler.quality().avg_heartbeat_gap, None);
+    }
+
+    #[tokio::test]
+    async fn test_sse_connection_quality() {
+        // Heartbeats every 50ms
+        let (base_url, _) = spawn_sse_stream_server().await;
+        let config = SseConfig::new(format!("{}/api/subscribe/ch-1", base_url), "test-key");
+        let (connection, mut receiver) = SseConnection::new(config);
+
+        let mut heartbeats = 0;
+        while heartbeats < 6 {
+            match tokio::time::timeout(Duration::from_secs(5), receiver.recv()).await.unwrap() {
+                Some(SseMessage::Heartbeat) => heartbeats += 1,
+                Some(_) => {}
+                None => panic!("stream closed"),
+            }
+        }
+
+        let quality = connection.connection_quality();
+        let gap = quality.avg_heartbeat_gap.unwrap();
+        assert!(gap >= Duration::from_millis(40) && gap < Duration::from_millis(200), "{:?}", gap);
+        assert!(quality.jitter.is_some());
+        assert!(quality.last_message_age.unwrap() < Duration::from_millis(200));
+        connection.abort_handle().abort();
+    }
+
+    #[tokio::test]
+    async fn test_response_signature_verification() {
+        use base64::engine::general_purpose::STANDARD;
+        use base64::Engine;
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let signing_key = SigningKey::from_bytes(&[42; 32]);
+        let sign = |body: &str| STANDARD.encode(signing_key.sign(body.as_bytes()).to_bytes());
+        let tampered = CHANNEL_BODY.replace("\"is_active\":true", "\"is_active\":false");
+        assert_ne!(tampered, CHANNEL_BODY);
+
+        let (base_url, _) = spawn_mock_server(vec![
+            MockResponse::json(200, CHANNEL_BODY).header("X-Signature", &sign(CHANNEL_BODY)),
+            MockResponse::json(200, &tampered).header("X-Signature", &sign(CHANNEL_BODY)),
+            MockResponse::json(200, CHANNEL_BODY),
+        ])
+        .await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .response_verification_key(signing_key.verifying_key().to_bytes())
+            .build()
+            .unwrap();
+
+        let channel = client.get_channel("ch-1").await.unwrap();
+        assert_eq!(channel.id, "ch-1");
+
+        let error = client.get_channel("ch-1").await.unwrap_err();
+        assert!(matches!(error, SecureNotifyError::SignatureVerificationError(_)), "{:?}", error);
+        assert_eq!(error.kind(), crate::types::error::ErrorKind::SignatureVerification);
+
+        // Unsigned responses are rejected too
+        let error = client.get_channel("ch-1").await.unwrap_err();
+        assert!(error.to_string().contains("no X-Signature"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn test_list_all_channels_resumes_after_rate_limited_page() {
+        let channel = |id: &str| CHANNEL_BODY.replace("ch-1", id);
+        let page = |ids: &[&str]| {
+            format!("[{}]", ids.iter().map(|id| channel(id)).collect::<Vec<_>>().join(","))
+        };
+        let (base_url, requests) = spawn_mock_server(vec![
+            MockResponse::json(200, &page(&["ch-1", "ch-2"])),
+            MockResponse::json(429, r#"{"error":"slow down"}"#),
+            MockResponse::json(200, &page(&["ch-3", "ch-4"])),
+            MockResponse::json(200, &page(&["ch-5"])),
+        ])
+        .await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .initial_delay_ms(10)
+            .build()
+            .unwrap();
+
+        let channels = client.list_all_channels(None, 2).await.unwrap();
+
+        let ids: Vec<_> = channels.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, ["ch-1", "ch-2", "ch-3", "ch-4", "ch-5"]);
+        let requests = requests.lock().unwrap();
+        let paths: Vec<_> = requests
+            .iter()
+            .map(|r| r.split_whitespace().nth(1).unwrap())
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                "/api/channels?limit=2&offset=0",
+                "/api/channels?limit=2&offset=2",
+                "/api/channels?limit=2&offset=2",
+                "/api/channels?limit=2&offset=4",
+            ]
+        );
+    }
+
+    /// Collect messages until the connection reports why it stopped
+    async fn disconnect_reason(receiver: &mut tokio::sync::mpsc::Receiver<SseMessage>) -> DisconnectReason {
+        loop {
+            let message = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+                .await
+                .expect("connection did not disconnect")
+                .expect("receiver closed before disconnect");
+            if let SseMessage::Disconnected(reason) = message {
+                return reason;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sse_disconnect_reason_server_closed() {
+        let (base_url, _requests) = spawn_mock_server(vec![MockResponse::sse("data: hello\n\n")]).await;
+        let config = SseConfig::new(format!("{}/api/subscribe/ch-1", base_url), "test-key");
+        let (_connection, mut receiver) = SseConnection::new(config);
+
+        assert_eq!(disconnect_reason(&mut receiver).await, DisconnectReason::ServerClosed);
+    }
+
+    #[tokio::test]
+    async fn test_sse_disconnect_reason_max_retries_exceeded() {
+        let (base_url, requests) = spawn_mock_server(vec![MockResponse::json(503, "unavailable")]).await;
+        let config = SseConfig::new(format!("{}/api/subscribe/ch-1", base_url), "test-key")
+            .with_reconnect_delay(Duration::from_millis(10))
+            .with_max_reconnect_attempts(1);
+        let (connection, mut receiver) = SseConnection::new(config);
+
+        assert_eq!(disconnect_reason(&mut receiver).await, DisconnectReason::MaxRetriesExceeded);
+        assert_eq!(requests.lock().unwrap().len(), 2);
+        assert_eq!(connection.state().await, SseState::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_sse_disconnect_reason_client_requested() {
+        let (base_url, _requests) = spawn_sse_stream_server().await;
+        let config = SseConfig::new(format!("{}/api/subscribe/ch-1", base_url), "test-key");
+        let (connection, mut receiver) = SseConnection::new(config);
+
+        let first = tokio::time::timeout(Duration::from_secs(5), receiver.recv()).await.unwrap().unwrap();
+        assert!(matches!(first, SseMessage::Connected), "{:?}", first);
+        connection.disconnect().await;
+
+        assert_eq!(disconnect_reason(&mut receiver).await, DisconnectReason::ClientRequested);
+        assert_eq!(connection.state().await, SseState::Disconnected);
+    }
+
+    #[tokio::test]
+    async fn test_list_accessible_channels() {
+        let body = format!("[{},{}]", CHANNEL_BODY, CHANNEL_BODY.replace("ch-1", "ch-2"));
+        let (base_url, requests) = spawn_mock_server(vec![
+            MockResponse::json(200, &body),
+            MockResponse::json(404, r#"{"error":"api key not found"}"#),
+        ])
+        .await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .build()
+            .unwrap();
+
+        let channels = client.list_accessible_channels("key-1").await.unwrap();
+        let ids: Vec<_> = channels.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, ["ch-1", "ch-2"]);
+        assert!(requests.lock().unwrap()[0].starts_with("GET /api/keys/key-1/channels "));
+
+        let error = client.list_accessible_channels("missing").await.unwrap_err();
+        assert!(error.is_not_found(), "{:?}", error);
+        assert_eq!(error.status(), 404);
+        assert_eq!(error.kind(), crate::types::error::ErrorKind::NotFound);
+        assert!(error.to_string().contains("api key not found"));
+    }
+
+    #[tokio::test]
+    async fn test_field_case_request_serialization() {
+        use crate::types::api::ChannelCreateRequest;
+        use crate::utils::FieldCase;
+        use serde_json::json;
+
+        let request = ChannelCreateRequest {
+            name: "news".to_string(),
+            description: Some("Daily news".to_string()),
+            channel_type: "public".to_string(),
+            metadata: Some(json!({"owner_team": "desk"})),
+        };
+        let encode = |case: FieldCase| -> serde_json::Value {
+            serde_json::from_slice(&case.to_vec(&request).unwrap()).unwrap()
+        };
+        assert_eq!(
+            encode(FieldCase::Snake),
+            json!({"name": "news", "description": "Daily news", "type": "public", "metadata": {"owner_team": "desk"}})
+        );
+
+        let key_request = crate::types::api::ApiKeyCreateRequest {
+            name: "ci".to_string(),
+            user_id: Some("u-1".to_string()),
+            permissions: None,
+            expires_at: Some("2027-01-01T00:00:00Z".to_string()),
+        };
+        let camel: serde_json::Value =
+            serde_json::from_slice(&FieldCase::Camel.to_vec(&key_request).unwrap()).unwrap();
+        assert_eq!(camel, json!({"name": "ci", "userId": "u-1", "expiresAt": "2027-01-01T00:00:00Z"}));
+
+        let (base_url, requests) = spawn_mock_server(vec![MockResponse::json(200, CHANNEL_BODY)]).await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .field_case(FieldCase::Camel)
+            .build()
+            .unwrap();
+        client
+            .create_channel("news", "public", Some("Daily news"), Some(json!({"owner_team": "desk"})))
+            .await
+            .unwrap();
+        let raw = requests.lock().unwrap()[0].clone();
+        let body: serde_json::Value = serde_json::from_str(raw.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+        assert_eq!(body, encode(FieldCase::Camel));
+        assert_eq!(body["metadata"], json!({"owner_team": "desk"}));
+    }
+
+    #[tokio::test]
+    async fn test_enforce_local_permissions() {
+        use crate::types::api::Permission;
+
+        let (base_url, requests) = spawn_sse_stream_server().await;
+        let key = make_jwt(&serde_json::json!({"scope": "subscribe"}), b"secret");
+        let client = SecureNotifyClient::builder()
+            .base_url(&base_url)
+            .api_key(key)
+            .enforce_local_permissions(true)
+            .build()
+            .unwrap();
+        assert_eq!(client.known_permissions(), Some(vec![Permission::Subscribe]));
+
+        let error = client
+            .publish_message("ch-1", "hello", None, None, None, None, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, SecureNotifyError::AuthError(ref m) if m.contains("publish")), "{:?}", error);
+        assert!(requests.lock().unwrap().is_empty(), "publish must not be sent");
+
+        let mut receiver = client.subscribe("ch-1").await.unwrap();
+        let first = tokio::time::timeout(Duration::from_secs(5), receiver.recv()).await.unwrap().unwrap();
+        assert!(matches!(first, SseMessage::Connected), "{:?}", first);
+
+        // Permissions from a fetched key replace the JWT scopes
+        let info: crate::types::api::ApiKeyInfo = serde_json::from_str(
+            r#"{"id":"k-1","key_prefix":"sn_","name":"ci","permissions":["Publish","unknown"],"is_active":true,"created_at":"2026-01-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+        assert_eq!(info.granted_permissions(), vec![Permission::Publish]);
+        client.set_known_permissions(Some(info.granted_permissions()));
+        assert!(client.subscribe_longpoll("ch-1").await.unwrap_err().to_string().contains("subscribe"));
+
+        // Unknown permissions and disabled enforcement never block
+        client.set_known_permissions(None);
+        assert!(client.subscribe("ch-1").await.is_ok());
+        let relaxed = HttpClient::from_config(HttpClientConfig {
+            api_key: make_jwt(&serde_json::json!({"scope": "subscribe"}), b"secret"),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(relaxed.known_permissions(), Some(vec![Permission::Subscribe]));
+        assert!(relaxed.check_permission(Permission::Publish).is_ok());
+        assert!(Permission::Subscribe.is_granted_by(&[Permission::Admin]));
+    }
+
+    #[tokio::test]
+    async fn test_sse_pause_policy_buffers_or_drops_events() {
+        use crate::utils::connection::MessageSink;
+        use crate::utils::PausePolicy;
+
+        let event = |n: u32| SseMessage::Event(SseEvent::new(SseEventType::Message, n.to_string(), None, None));
+        let data = |message: SseMessage| match message {
+            SseMessage::Event(event) => event.data,
+            other => panic!("expected event, got {:?}", other),
+        };
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(100);
+        let sink = MessageSink::new(tx, PausePolicy::Buffer(2));
+        sink.pause();
+        sink.send(SseMessage::Connected).await;
+        for n in 1..=4 {
+            sink.send(event(n)).await;
+            sink.send(SseMessage::Heartbeat).await;
+        }
+        assert!(rx.try_recv().is_err(), "nothing is delivered while paused");
+
+        sink.resume().await;
+        assert!(matches!(rx.try_recv().unwrap(), SseMessage::Connected));
+        assert_eq!(data(rx.try_recv().unwrap()), "3");
+        assert_eq!(data(rx.try_recv().unwrap()), "4");
+        assert!(rx.try_recv().is_err(), "heartbeats are not held");
+        sink.send(event(5)).await;
+        assert_eq!(data(rx.try_recv().unwrap()), "5");
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(100);
+        let sink = MessageSink::new(tx, PausePolicy::Drop);
+        sink.pause();
+        sink.send(event(1)).await;
+        sink.send(SseMessage::Disconnected(DisconnectReason::ServerClosed)).await;
+        sink.resume().await;
+        assert!(matches!(rx.try_recv().unwrap(), SseMessage::Disconnected(DisconnectReason::ServerClosed)));
+        assert!(rx.try_recv().is_err(), "events are dropped while paused");
+        sink.send(event(2)).await;
+        assert_eq!(data(rx.try_recv().unwrap()), "2");
+    }
+
+    #[tokio::test]
+    async fn test_sse_pause_keeps_connection_alive() {
+        let (base_url, requests) = spawn_sse_stream_server().await;
+        let config = SseConfig::new(format!("{}/api/subscribe/ch-1", base_url), "test-key");
+        let (connection, mut receiver) = SseConnection::new(config);
+        connection.pause();
+        assert!(connection.is_paused());
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert!(receiver.try_recv().is_err(), "nothing is delivered while paused");
+        let quality = connection.connection_quality();
+        assert!(quality.avg_heartbeat_gap.is_some(), "heartbeats keep arriving: {:?}", quality);
+
+        connection.resume().await;
+        assert!(!connection.is_paused());
+        let first = tokio::time::timeout(Duration::from_secs(5), receiver.recv()).await.unwrap().unwrap();
+        assert!(matches!(first, SseMessage::Connected), "{:?}", first);
+        let next = tokio::time::timeout(Duration::from_secs(5), receiver.recv()).await.unwrap().unwrap();
+        assert!(matches!(next, SseMessage::Heartbeat), "{:?}", next);
+        assert_eq!(requests.lock().unwrap().len(), 1, "no reconnect on resume");
+    }
+
+    #[tokio::test]
+    async fn test_payload_too_large() {
+        let (base_url, requests) = spawn_mock_server(vec![
+            MockResponse::json(413, r#"{"error":{"message":"message too large","limit":65536}}"#),
+            MockResponse::json(413, "request entity too large").header("X-Max-Payload-Size", "1024"),
+            MockResponse::json(413, "request entity too large"),
+        ])
+        .await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .initial_delay_ms(10)
+            .build()
+            .unwrap();
+        let publish = || client.publish_message("ch-1", "x", None, None, None, None, None);
+
+        match publish().await {
+            Err(SecureNotifyError::PayloadTooLarge { message, limit }) => {
+                assert!(message.contains("message too large"));
+                assert_eq!(limit, Some(65536));
+            }
+            other => panic!("expected PayloadTooLarge, got {:?}", other),
+        }
+        match publish().await {
+            Err(SecureNotifyError::PayloadTooLarge { limit, .. }) => assert_eq!(limit, Some(1024)),
+            other => panic!("expected PayloadTooLarge, got {:?}", other),
+        }
+        let error = publish().await.unwrap_err();
+        assert!(error.is_payload_too_large());
+        assert!(matches!(error, SecureNotifyError::PayloadTooLarge { limit: None, .. }));
+        assert_eq!(error.status(), 413);
+        assert_eq!(error.kind(), crate::types::error::ErrorKind::PayloadTooLarge);
+
+        // Not retried: one request per publish
+        assert_eq!(requests.lock().unwrap().len(), 3);
+        assert_eq!(crate::types::error::parse_payload_limit(None, r#"{"details":{"maxSize":"2048"}}"#), Some(2048));
+    }
+
+    #[tokio::test]
+    async fn test_sse_drain_event_triggers_reconnect() {
+        let (base_url, requests) = spawn_mock_server(vec![
+            MockResponse::sse(":ping\n\nevent: drain\n\n:ping\n\n"),
+            MockResponse::sse(""),
+        ])
+        .await;
+        let config = SseConfig::new(format!("{}/api/subscribe/ch-1", base_url), "test-key")
+            .with_reconnect_delay(Duration::from_secs(30));
+        let (_connection, mut receiver) = SseConnection::new(config);
+
+        let mut kinds = Vec::new();
+        loop {
+            let message = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+                .await
+                .expect("no reconnect after drain")
+                .unwrap();
+            kinds.push(message.event_type());
+            if matches!(message, SseMessage::Disconnected(_)) {
+                break;
+            }
+        }
+
+        // Messages received before the drain are delivered, then the client reconnects without backoff
+        assert_eq!(
+            kinds,
+            [
+                SseEventType::Connected,
+                SseEventType::Heartbeat,
+                SseEventType::Draining,
+                SseEventType::Heartbeat,
+                SseEventType::Connected,
+                SseEventType::Disconnected,
+            ]
+        );
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_custom_request_id_generator() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let (base_url, requests) = spawn_mock_server(vec![
+            MockResponse::json(502, r#"{"error":"bad gateway"}"#),
+            MockResponse::json(200, CHANNEL_BODY),
+        ])
+        .await;
+        let counter = Arc::new(AtomicU32::new(0));
+        let next = counter.clone();
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .initial_delay_ms(10)
+            .request_id_generator(Arc::new(move || format!("req-{}", next.fetch_add(1, Ordering::SeqCst) + 1)))
+            .build()
+            .unwrap();
+
+        client.get_channel("ch-1").await.unwrap();
+        client.get_channel("ch-1").await.unwrap();
+
+        let ids: Vec<String> = requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|r| {
+                r.lines()
+                    .find_map(|line| line.to_lowercase().strip_prefix("x-request-id: ").map(str::to_string))
+                    .unwrap()
+            })
+            .collect();
+        // The retry of the first request keeps its ID
+        assert_eq!(ids, ["req-1", "req-1", "req-2"]);
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_timed_publish_reports_duration_and_attempts() {
+        let body = r#"{"message_id":"msg-1","timestamp":"2026-01-01T00:00:00Z","channel":"ch-1"}"#;
+        let (base_url, _requests) = spawn_mock_server(vec![
+            MockResponse::json(503, "busy"),
+            MockResponse::json(200, body),
+        ])
+        .await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .initial_delay_ms(20)
+            .build()
+            .unwrap();
+
+        let timed = client
+            .timed_publish("ch-1", "hello", None, None, None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(timed.value.message_id, "msg-1");
+        assert_eq!(timed.attempts, 2);
+        assert!(timed.duration >= Duration::from_millis(20), "{:?}", timed.duration);
+
+        let single = client
+            .timed_publish("ch-1", "hello", None, None, None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(single.attempts, 1);
+        assert!(single.duration > Duration::ZERO);
+    }
+
+    #[test]
+    fn test_sse_parser_builds_events_from_fields() {
+        let mut parser = SseParser::new();
+
+        let mut feed = |lines: &[&str]| -> Vec<SseMessage> {
+            lines.iter().filter_map(|line| parser.process_line(line)).collect()
+        };
+
+        let messages = feed(&["id: 7", "data: {\"a\":1,", "data:\"b\":2}", ""]);
+        match messages.as_slice() {
+            [SseMessage::Event(event)] => {
+                assert_eq!(event.event_type, SseEventType::Message);
+                assert_eq!(event.data, "{\"a\":1,\n\"b\":2}");
+                assert_eq!(event.id.as_deref(), Some("7"));
+                assert_eq!(event.name, None);
+            }
+            other => panic!("unexpected messages: {:?}", other),
+        }
+
+        let messages = feed(&["event: error\r", "data: boom\r", "\r"]);
+        match messages.as_slice() {
+            [SseMessage::Event(event)] => {
+                assert_eq!(event.event_type, SseEventType::Error);
+                assert_eq!(event.data, "boom");
+                // The last event ID carries over
+                assert_eq!(event.id.as_deref(), Some("7"));
+                assert_eq!(event.name.as_deref(), Some("error"));
+            }
+            other => panic!("unexpected messages: {:?}", other),
+        }
+
+        let messages = feed(&["event: presence", "data: joined", ""]);
+        assert!(matches!(
+            messages.as_slice(),
+            [SseMessage::Event(SseEvent { event_type: SseEventType::Unknown(name), .. })] if name == "presence"
+        ));
+
+        assert!(matches!(feed(&["event: heartbeat", ""]).as_slice(), [SseMessage::Heartbeat]));
+        assert!(matches!(feed(&[":ping", ""]).as_slice(), [SseMessage::Heartbeat]));
+        assert!(feed(&["event: message", ""]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_receives_server_payload() {
+        let body = "id: msg-1\nevent: message\ndata: {\"message_id\":\"msg-1\",\"channel\":\"ch-1\",\"message\":\"hi\",\"timestamp\":\"2026-01-01T00:00:00Z\"}\n\n";
+        let (base_url, _) = spawn_mock_server(vec![MockResponse::sse(body)]).await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .build()
+            .unwrap();
+
+        let messages = collect_messages(client.subscribe("ch-1").await.unwrap()).await;
+        let event = messages
+            .iter()
+            .find_map(|m| match m {
+                SseMessage::Event(event) => Some(event),
+                _ => None,
+            })
+            .expect("no event received");
+        assert_eq!(event.event_type, SseEventType::Message);
+        assert_eq!(event.id.as_deref(), Some("msg-1"));
+        let envelope: MessageEnvelope = serde_json::from_str(&event.data).unwrap();
+        assert_eq!(envelope.message, "hi");
+        assert_eq!(envelope.channel, "ch-1");
+    }
+
+    #[tokio::test]
+    async fn test_non_json_success_response_is_reported() {
+        let page = "<html>\n  <head><title>502 Bad Gateway</title></head>\n  <body>nginx</body>\n</html>";
+        let html = MockResponse {
+            status: 200,
+            headers: vec![("Content-Type".to_string(), "text/html; charset=utf-8".to_string())],
+            body: page.to_string(),
+        };
+        let (base_url, _) = spawn_mock_server(vec![html]).await;
+
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url.clone())
+            .api_key("test-key")
+            .build()
+            .unwrap();
+        let error = client.get_channel("ch-1").await.unwrap_err();
+        assert!(error.is_unexpected_content_type());
+        assert_eq!(error.kind(), crate::types::error::ErrorKind::UnexpectedContentType);
+        match &error {
+            SecureNotifyError::UnexpectedContentType { content_type, snippet } => {
+                assert_eq!(content_type, "text/html; charset=utf-8");
+                assert!(snippet.starts_with("<html> <head><title>502 Bad Gateway"), "{}", snippet);
+            }
+            other => panic!("expected UnexpectedContentType, got {:?}", other),
+        }
+        assert!(error.to_string().contains("text/html"));
+
+        // Without the check the body reaches the JSON parser
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .check_content_type(false)
+            .build()
+            .unwrap();
+        let error = client.get_channel("ch-1").await.unwrap_err();
+        assert!(matches!(error, SecureNotifyError::SerializationError(..)), "{:?}", error);
+
+        assert!(crate::types::error::is_json_content_type("application/problem+json"));
+        assert!(crate::types::error::is_json_content_type("Application/JSON; charset=utf-8"));
+        assert!(!crate::types::error::is_json_content_type("text/plain"));
+        assert!(crate::types::error::body_snippet("x".repeat(500).as_bytes()).len() < 210);
+    }
+
+    #[tokio::test]
+    async fn test_sign_batch_matches_per_item_signing() {
+        use crate::utils::MessageSigner;
+
+        let signer = MessageSigner::new("message-secret");
+        let items = [("ch-a", "hello"), ("ch-b", "hello"), ("ch-a", "world"), ("ch-a", "hello")];
+        let batch = signer.sign_batch(&items);
+        let single: Vec<String> = items.iter().map(|(c, m)| signer.sign(c, m)).collect();
+        assert_eq!(batch, single);
+        assert_eq!(batch[0], batch[3]);
+        assert_ne!(batch[0], batch[1]);
+        assert_eq!(batch[0].len(), 64);
+
+        let body = r#"{"message_id":"msg-1","timestamp":"2026-01-01T00:00:00Z","channel":"ch"}"#;
+        let (base_url, requests) = spawn_mock_server(vec![MockResponse::json(200, body)]).await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .build()
+            .unwrap();
+        let results = client.publish_batch_signed(&signer, &items[..2], None).await.unwrap();
+        assert!(results.iter().all(|r| r.is_ok()));
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        for (channel, message) in &items[..2] {
+            let request = requests
+                .iter()
+                .find(|r| r.starts_with(&format!("POST /api/publish/{} ", channel)))
+                .unwrap();
+            let json: serde_json::Value = serde_json::from_str(request.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+            assert_eq!(json["signature"], signer.sign(channel, message));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sse_multi_line_data_is_joined() {
+        // Chunks split the event mid-line to exercise buffering across reads
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = socket.read(&mut buf).await;
+            let _ = socket
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\r\n")
+                .await;
+            for chunk in ["data: {\"line\":", "1,\ndata: \"next\":2}\n", "\nevent: closing\n\n"] {
+                let _ = socket.write_all(chunk.as_bytes()).await;
+                let _ = socket.flush().await;
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+
+        let config = SseConfig::new(format!("{}/api/subscribe/ch-1", base_url), "test-key");
+        let (_connection, mut receiver) = SseConnection::new(config);
+        let mut events = Vec::new();
+        while events.len() < 2 {
+            let message = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            if let SseMessage::Event(event) = message {
+                events.push(event);
+            }
+        }
+
+        assert_eq!(events[0].event_type, SseEventType::Message);
+        assert_eq!(events[0].data, "{\"line\":1,\n\"next\":2}");
+        let value: serde_json::Value = serde_json::from_str(&events[0].data).unwrap();
+        assert_eq!(value["next"], 2);
+
+        // A named event without data lines is still dispatched
+        assert_eq!(events[1].event_type, SseEventType::Unknown("closing".to_string()));
+        assert_eq!(events[1].data, "");
+    }
+
+    #[tokio::test]
+    async fn test_subscription_health_reflects_live_connection() {
+        let (base_url, _requests) = spawn_sse_stream_server().await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .build()
+            .unwrap();
+        assert!(client.subscription_health("ch-1").await.is_none());
+
+        let mut receiver = client.subscribe("ch-1").await.unwrap();
+        for _ in 0..2 {
+            let message = tokio::time::timeout(Duration::from_secs(5), receiver.recv()).await.unwrap();
+            assert!(message.is_some());
+        }
+
+        let health = client.subscription_health("ch-1").await.unwrap();
+        assert_eq!(health.state, SseState::Connected);
+        assert!(health.last_message_age.unwrap() < Duration::from_secs(1), "{:?}", health);
+        assert_eq!(health.reconnects, 0);
+        assert!(client.subscription_health("ch-2").await.is_none());
+
+        client.unsubscribe_all().await.unwrap();
+        assert!(client.subscription_health("ch-1").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_sse_reconnect_sends_last_event_id() {
+        let (base_url, requests) = spawn_mock_server(vec![
+            MockResponse::sse("id: evt-41\ndata: one\n\nid: evt-42\ndata: two\n\nevent: drain\n\n"),
+            MockResponse::sse(":ping\n\n"),
+        ])
+        .await;
+        let config = SseConfig::new(format!("{}/api/subscribe/ch-1", base_url), "test-key");
+        let (connection, mut receiver) = SseConnection::new(config);
+        assert_eq!(connection.last_event_id().await, None);
+
+        loop {
+            let message = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            if matches!(message, SseMessage::Disconnected(_)) {
+                break;
+            }
+        }
+
+        assert_eq!(connection.last_event_id().await.as_deref(), Some("evt-42"));
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(!requests[0].to_lowercase().contains("last-event-id"));
+        assert!(requests[1].to_lowercase().contains("last-event-id: evt-42"), "{}", requests[1]);
+    }
+
+    #[tokio::test]
+    async fn test_sse_initial_connect_retries_have_own_budget() {
+        let unavailable = || MockResponse::json(503, r#"{"error":"starting"}"#);
+        // Two transient failures, then the stream opens
+        let (base_url, requests) = spawn_mock_server(vec![
+            unavailable(),
+            unavailable(),
+            MockResponse::sse(":ping\n\n"),
+        ])
+        .await;
+        let config = SseConfig::new(format!("{}/api/subscribe/ch-1", base_url), "test-key")
+            .with_reconnect_delay(Duration::from_millis(10))
+            .with_max_reconnect_attempts(0)
+            .with_initial_connect_retries(2);
+        let (_connection, mut receiver) = SseConnection::new(config);
+        loop {
+            let message = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            match message {
+                SseMessage::Connected => break,
+                SseMessage::Error(error, reconnect) => {
+                    assert_eq!(error.status(), 503);
+                    assert_eq!(reconnect.map(|r| r.max_attempts), Some(2));
+                }
+                other => panic!("unexpected message: {:?}", other),
+            }
+        }
+        assert_eq!(requests.lock().unwrap().len(), 3);
+
+        // An exhausted initial budget fails without touching the reconnect budget
+        let (base_url, requests) = spawn_mock_server(vec![unavailable()]).await;
+        let config = SseConfig::new(format!("{}/api/subscribe/ch-1", base_url), "test-key")
+            .with_reconnect_delay(Duration::from_millis(10))
+            .with_max_reconnect_attempts(10)
+            .with_initial_connect_retries(1);
+        let (_connection, mut receiver) = SseConnection::new(config);
+        let reason = disconnect_reason(&mut receiver).await;
+        assert_eq!(reason, DisconnectReason::MaxRetriesExceeded);
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_sse_disconnect_stops_connection_task() {
+        let (base_url, _requests) = spawn_sse_stream_server().await;
+        let config = SseConfig::new(format!("{}/api/subscribe/ch-1", base_url), "test-key");
+        let (connection, mut receiver) = SseConnection::new(config);
+        let first = tokio::time::timeout(Duration::from_secs(5), receiver.recv()).await.unwrap().unwrap();
+        assert!(matches!(first, SseMessage::Connected), "{:?}", first);
+
+        connection.disconnect().await;
+        assert!(connection.abort_handle().is_finished());
+        assert_eq!(disconnect_reason(&mut receiver).await, DisconnectReason::ClientRequested);
+
+        // The server keeps sending heartbeats, but nothing reads them any more
+        let later = tokio::time::timeout(Duration::from_millis(300), receiver.recv()).await;
+        assert!(later.is_err(), "{:?}", later);
+
+        // A second disconnect returns without waiting on the finished task
+        connection.clone().disconnect().await;
+    }
+
+    #[test]
+    fn test_metrics_subscribe_receives_samples() {
+        use crate::utils::MetricsCollector;
+        use tokio::sync::broadcast::error::TryRecvError;
+
+        for collector in [MetricsCollector::new(1000), MetricsCollector::new_sharded(2)] {
+            collector.record("api/channels", 1.0, true);
+            let mut receiver = collector.subscribe();
+            collector.record("api/channels", 2.0, true);
+            collector.record("api/publish/ch-1", 3.0, false);
+
+            let first = receiver.try_recv().unwrap();
+            assert_eq!((first.endpoint.as_str(), first.duration_ms, first.success), ("api/channels", 2.0, true));
+            let second = receiver.try_recv().unwrap();
+            assert_eq!((second.endpoint.as_str(), second.duration_ms, second.success), ("api/publish/ch-1", 3.0, false));
+            assert!(matches!(receiver.try_recv(), Err(TryRecvError::Empty)));
+        }
+
+        // A subscriber that falls behind loses the oldest samples instead of blocking recording
+        let collector = MetricsCollector::new(10);
+        let mut receiver = collector.subscribe();
+        for i in 0..300 {
+            collector.record("api/channels", i as f64, true);
+        }
+        assert!(matches!(receiver.try_recv(), Err(TryRecvError::Lagged(44))));
+        assert_eq!(receiver.try_recv().unwrap().duration_ms, 44.0);
+    }
+
+    #[tokio::test]
+    async fn test_deduplicator_evicts_oldest_completed() {
+        use crate::utils::{EvictionPolicy, RequestDeduplicator};
+
+        async fn fill(deduplicator: &RequestDeduplicator, endpoints: &[&str]) {
+            for endpoint in endpoints {
+                let value = endpoint.to_string();
+                deduplicator
+                    .execute(endpoint, None, || async move { Ok(value) }, true)
+                    .await
+                    .unwrap();
+            }
+        }
+        async fn cached(deduplicator: &RequestDeduplicator) -> Vec<String> {
+            deduplicator.export().await.entries.into_iter().map(|e| e.value).collect()
+        }
+
+        let deduplicator = RequestDeduplicator::new(60.0, 100, 3);
+        fill(&deduplicator, &["e1", "e2", "e3", "e4", "e5"]).await;
+        assert_eq!(cached(&deduplicator).await, ["e3", "e4", "e5"]);
+
+        // Under FIFO a reused result still ages out in completion order
+        fill(&deduplicator, &["e3", "e6"]).await;
+        assert_eq!(cached(&deduplicator).await, ["e4", "e5", "e6"]);
+
+        // Under LRU a reused result moves to the back
+        let deduplicator = RequestDeduplicator::new(60.0, 100, 3).with_eviction_policy(EvictionPolicy::Lru);
+        fill(&deduplicator, &["e1", "e2", "e3", "e1", "e4"]).await;
+        assert_eq!(cached(&deduplicator).await, ["e3", "e1", "e4"]);
+        assert_eq!(deduplicator.get_stats().await.completed_count, 3);
+    }
+
+    #[tokio::test]
+    async fn test_sse_missed_heartbeats_trigger_reconnect() {
+        // Opens the stream, then goes silent like a half-open connection
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let accepted = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = accepted.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    let _ = socket.read(&mut buf).await;
+                    let _ = socket
+                        .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\r\n")
+                        .await;
+                    tokio::time::sleep(Duration::from_secs(10)).await;
+                });
+            }
+        });
+
+        let config = SseConfig::new(format!("{}/api/subscribe/ch-1", base_url), "test-key")
+            .with_heartbeat_interval(Duration::from_millis(100))
+            .with_reconnect_delay(Duration::from_millis(10));
+        let (_connection, mut receiver) = SseConnection::new(config);
+
+        let mut messages = Vec::new();
+        while messages.len() < 3 {
+            let message = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            messages.push(message);
+        }
+        assert!(matches!(messages[0], SseMessage::Connected), "{:?}", messages);
+        assert!(
+            matches!(
+                &messages[1],
+                SseMessage::Error(SecureNotifyError::TimeoutError(..), Some(ReconnectAttempt { attempt: 1, .. }))
+            ),
+            "{:?}",
+            messages
+        );
+        assert!(matches!(messages[2], SseMessage::Connected), "{:?}", messages);
+        assert_eq!(accepted.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_create_api_key_expiring_in() {
+        use crate::managers::ApiKeyManager;
+        use time::format_description::well_known::Rfc3339;
+
+        let ttl = Duration::from_secs(90 * 24 * 60 * 60);
+        let key_body = r#"{"id":"key-1","key_prefix":"sn_","api_key":"sn_secret","name":"ci","created_at":"2026-01-01T00:00:00Z"}"#;
+        // The server clock runs an hour ahead of ours
+        let server_date = (time::OffsetDateTime::now_utc() + time::Duration::hours(1))
+            .format(&time::macros::format_description!(
+                "[weekday repr:short], [day] [month repr:short] [year] [hour]:[minute]:[second] GMT"
+            ))
+            .unwrap();
+        let (base_url, requests) = spawn_mock_server(vec![
+            MockResponse::json(201, key_body),
+            MockResponse::json(201, key_body).header("Date", &server_date),
+            MockResponse::json(201, key_body),
+        ])
+        .await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .build()
+            .unwrap();
+        let sent_expiry = |index: usize| {
+            let request = requests.lock().unwrap()[index].clone();
+            let json: serde_json::Value = serde_json::from_str(request.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+            time::OffsetDateTime::parse(json["expiresAt"].as_str().unwrap(), &Rfc3339).unwrap()
+        };
+        let assert_near = |actual: time::OffsetDateTime, expected: time::OffsetDateTime| {
+            assert!((actual - expected).abs() < time::Duration::seconds(5), "{} vs {}", actual, expected);
+        };
+
+        // Without a server date the local clock is used
+        client.create_api_key_expiring_in("ci", ttl, None, None).await.unwrap();
+        assert_near(sent_expiry(0), time::OffsetDateTime::now_utc() + ttl);
+
+        // Once a response reveals the server clock, the expiry follows it
+        client.create_api_key_expiring_in("ci", ttl, None, Some(vec!["publish"])).await.unwrap();
+        client.create_api_key_expiring_in("ci", ttl, None, None).await.unwrap();
+        assert_near(sent_expiry(2), time::OffsetDateTime::now_utc() + ttl + time::Duration::hours(1));
+    }
+
+    #[tokio::test]
+    async fn test_sse_channel_buffer_applies_backpressure() {
+        let events: String = (0..20).map(|i| format!("data: event-{}\n\n", i)).collect();
+        let (base_url, _requests) = spawn_mock_server(vec![MockResponse::sse(&events)]).await;
+        let config = SseConfig::new(format!("{}/api/subscribe/ch-1", base_url), "test-key")
+            .with_channel_buffer(2);
+        assert_eq!(SseConfig::default().channel_buffer, 100);
+        let (_connection, receiver) = SseConnection::new(config);
+
+        // The connection stops reading once the small buffer is full
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(receiver.len(), 2);
+
+        // Nothing is dropped: every event arrives as the consumer catches up
+        let messages = collect_messages(receiver).await;
+        let data: Vec<String> = messages
+            .iter()
+            .filter_map(|m| match m {
+                SseMessage::Event(event) => Some(event.data.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(data, (0..20).map(|i| format!("event-{}", i)).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_sse_overflow_policy_drops_messages() {
+        use crate::utils::OverflowPolicy;
+
+        // Sends a burst of 20 events, then holds the stream open
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    let _ = socket.read(&mut buf).await;
+                    let events: String = (0..20).map(|i| format!("data: event-{}\n\n", i)).collect();
+                    let _ = socket
+                        .write_all(format!("HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\r\n{}", events).as_bytes())
+                        .await;
+                    tokio::time::sleep(Duration::from_secs(30)).await;
+                });
+            }
+        });
+
+        let received = |policy: OverflowPolicy| async move {
+            let config = SseConfig::new(format!("http://{}/api/subscribe/ch-1", addr), "test-key")
+                .with_channel_buffer(2)
+                .with_overflow_policy(policy);
+            let (connection, mut receiver) = SseConnection::new(config);
+            // The consumer falls behind while the burst arrives
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            let mut data = Vec::new();
+            while let Ok(Some(message)) =
+                tokio::time::timeout(Duration::from_millis(200), receiver.recv()).await
+            {
+                if let SseMessage::Event(event) = message {
+                    data.push(event.data);
+                }
+            }
+            (data, connection.dropped_count())
+        };
+
+        // The most recent events survive
+        let (data, dropped) = received(OverflowPolicy::DropOldest).await;
+        assert_eq!(data.last().map(String::as_str), Some("event-19"));
+        assert!(data.len() <= 2, "at most the buffer is kept: {:?}", data);
+        assert_eq!(data.len() as u64 + dropped, 20);
+
+        // The first events to fill the buffer survive, after `Connected`
+        let (data, dropped) = received(OverflowPolicy::DropNewest).await;
+        assert_eq!(data, vec!["event-0".to_string()]);
+        assert_eq!(dropped, 19);
+
+        let (data, dropped) = received(OverflowPolicy::Block).await;
+        assert_eq!(data.len(), 20);
+        assert_eq!(dropped, 0);
+        assert_eq!(SseConfig::default().overflow_policy, OverflowPolicy::Block);
+    }
+
+    #[tokio::test]
+    async fn test_on_retries_exhausted_hook() {
+        let (base_url, _) = spawn_mock_server(vec![MockResponse::json(503, "{}")]).await;
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorded = calls.clone();
+
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .max_retries(2)
+            .initial_delay_ms(10)
+            .on_retries_exhausted(Arc::new(move |endpoint: &str, error: &SecureNotifyError, attempts: u32| {
+                recorded.lock().unwrap().push((endpoint.to_string(), error.status(), attempts));
+            }))
+            .build()
+            .unwrap();
+
+        let error = client.get_channel("ch-1").await.unwrap_err();
+        assert!(matches!(error, SecureNotifyError::RetriesExhausted { attempts: 3, .. }));
+
+        let calls = calls.lock().unwrap();
+        assert_eq!(*calls, vec![("/api/channels/ch-1".to_string(), 503, 3)]);
+    }
+
+    #[tokio::test]
+    async fn test_check_channel_access() {
+        use crate::types::api::{ChannelAccess, Permission};
+
+        let client_for = |response: MockResponse| async move {
+            let (base_url, _) = spawn_mock_server(vec![response]).await;
+            SecureNotifyClient::builder()
+                .base_url(base_url)
+                .api_key("test-key")
+                .max_retries(0)
+                .build()
+                .unwrap()
+        };
+
+        // Active channel, permissions unknown: the server decides, so both are assumed
+        let client = client_for(MockResponse::json(200, CHANNEL_BODY)).await;
+        let access = client.check_channel_access("ch-1").await.unwrap();
+        assert_eq!(
+            access,
+            ChannelAccess { exists: true, is_active: true, writable: true, readable: true }
+        );
+
+        // A subscribe-only key cannot publish
+        client.set_known_permissions(Some(vec![Permission::Subscribe]));
+        let access = client.check_channel_access("ch-1").await.unwrap();
+        assert!(access.readable && !access.writable);
+
+        // Still flagged active by the server, but past its expiry
+        let expired = r#"{"id":"ch-1","name":"news","type":"public","created_at":"2026-01-01T00:00:00Z","expiresAt":"2026-01-02T00:00:00Z","is_active":true}"#;
+        let client = client_for(MockResponse::json(200, expired)).await;
+        let access = client.check_channel_access("ch-1").await.unwrap();
+        assert_eq!(
+            access,
+            ChannelAccess { exists: true, is_active: false, writable: false, readable: false }
+        );
+
+        let client = client_for(MockResponse::json(404, r#"{"error":"not found"}"#)).await;
+        let access = client.check_channel_access("missing").await.unwrap();
+        assert_eq!(access, ChannelAccess::default());
+        assert!(!access.exists);
+
+        let client = client_for(MockResponse::json(500, r#"{"error":"boom"}"#)).await;
+        assert!(client.check_channel_access("ch-1").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sse_reconnect_attempts_reported_and_reset() {
+        // Nothing listens on this port, so every connect fails
+        let addr = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap();
+        let config = SseConfig::new(format!("http://{}/api/subscribe/ch-1", addr), "test-key")
+            .with_reconnect_delay(Duration::from_millis(10))
+            .with_max_reconnect_attempts(2);
+        let (connection, receiver) = SseConnection::new(config);
+        let progress: Vec<(u32, u32)> = collect_messages(receiver)
+            .await
+            .into_iter()
+            .filter_map(|m| match m {
+                SseMessage::Error(_, Some(reconnect)) => Some((reconnect.attempt, reconnect.max_attempts)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(progress, vec![(1, 2), (2, 2)]);
+        assert_eq!(connection.reconnect_attempts(), 2);
+
+        // A successful connect starts the count again
+        let (base_url, _requests) = spawn_mock_server(vec![
+            MockResponse::json(503, r#"{"error":"starting"}"#),
+            MockResponse::sse(":ping\n\n"),
+        ])
+        .await;
+        let config = SseConfig::new(format!("{}/api/subscribe/ch-1", base_url), "test-key")
+            .with_reconnect_delay(Duration::from_millis(10));
+        let (connection, mut receiver) = SseConnection::new(config);
+        loop {
+            let message = tokio::time::timeout(Duration::from_secs(5), receiver.recv()).await.unwrap().unwrap();
+            if matches!(message, SseMessage::Connected) {
+                break;
+            }
+        }
+        assert_eq!(connection.reconnect_attempts(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_request_options_tags_metrics_only() {
+        use crate::utils::RequestOptions;
+
+        let (base_url, requests) = spawn_mock_server(vec![MockResponse::json(200, CHANNEL_BODY)]).await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .enable_metrics(true)
+            .build()
+            .unwrap();
+        let mut samples = client.subscribe_metrics().unwrap();
+
+        let options = RequestOptions::new().with_tag("tenant", "acme-corp");
+        options.scope(client.get_channel("ch-1")).await.unwrap();
+        client.get_channel("ch-1").await.unwrap();
+
+        let tagged = samples.recv().await.unwrap();
+        assert_eq!(tagged.tags.get("tenant").map(String::as_str), Some("acme-corp"));
+        let untagged = samples.recv().await.unwrap();
+        assert!(untagged.tags.is_empty());
+
+        let by_tenant = client.metrics_by_tag("tenant").unwrap();
+        assert_eq!(by_tenant.len(), 1);
+        assert_eq!(by_tenant["acme-corp"].count, 1);
+
+        // Tags never reach the server
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests.iter().all(|r| !r.contains("acme-corp") && !r.contains("tenant")));
+    }
+
+    #[test]
+    fn test_sse_parser_handles_crlf_split_across_chunks() {
+        let stream = "id: 7\r\nevent: update\r\ndata: caf\u{e9}\r\ndata: two\r\n\r\n";
+        let mut parser = SseParser::new();
+        let mut messages = Vec::new();
+        for byte in stream.as_bytes() {
+            messages.extend(parser.feed(std::slice::from_ref(byte)));
+        }
+        assert_eq!(messages.len(), 1, "{:?}", messages);
+        let SseMessage::Event(event) = &messages[0] else {
+            panic!("expected event, got {:?}", messages[0]);
+        };
+        assert_eq!(event.data, "caf\u{e9}\ntwo");
+        assert_eq!(event.id.as_deref(), Some("7"));
+        assert_eq!(event.event_type, SseEventType::Unknown("update".to_string()));
+
+        // Bare CR and LF terminators, mixed within one stream
+        let messages = parser.feed(b"data: a\rdata: b\n\rdata: c\r\n\n");
+        let data: Vec<_> = messages
+            .iter()
+            .filter_map(|m| match m {
+                SseMessage::Event(event) => Some(event.data.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(data, vec!["a\nb", "c"]);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_all_requests() {
+        // Requests mentioning `slow` hang; every other request is answered at once
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    if String::from_utf8_lossy(&buf[..n]).contains("slow") {
+                        tokio::time::sleep(Duration::from_secs(30)).await;
+                    }
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                        CHANNEL_BODY.len(),
+                        CHANNEL_BODY
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+
+        let client = SecureNotifyClient::builder()
+            .base_url(format!("http://{}", addr))
+            .api_key("test-key")
+            .timeout(Duration::from_secs(60))
+            .build()
+            .unwrap();
+        let mut pending: Vec<_> = (0..3)
+            .map(|_| {
+                let client = client.clone();
+                tokio::spawn(async move { client.get_channel("slow").await.map(|_| ()) })
+            })
+            .collect();
+        // Requests sent outside the retry loop are cancelled as well
+        let acking = client.clone();
+        pending.push(tokio::spawn(async move { acking.ack_messages("slow", &["m-1"]).await }));
+        let streaming = client.clone();
+        pending.push(tokio::spawn(async move {
+            use futures::StreamExt;
+            let mut channels = streaming.list_channels_streaming(Some("slow"), None, None);
+            let first = channels.next().await.unwrap();
+            assert!(channels.next().await.is_none());
+            first.map(|_| ())
+        }));
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let start = std::time::Instant::now();
+        client.cancel_all_requests();
+        for request in pending {
+            let error = tokio::time::timeout(Duration::from_secs(1), request)
+                .await
+                .expect("cancelled requests resolve promptly")
+                .unwrap()
+                .unwrap_err();
+            assert!(error.is_cancelled(), "{:?}", error);
+            assert_eq!(error.kind(), crate::types::error::ErrorKind::Cancelled);
+        }
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        // Later requests are not affected by the earlier cancel
+        let channel = client.get_channel("ch-1").await.unwrap();
+        assert_eq!(channel.id, "ch-1");
+    }
+
+    #[tokio::test]
+    async fn test_cache_hit_returns_original_body() {
+        let body = r#"{"id":"ch-1","name":"news","type":"public","created_at":"2026-01-01T00:00:00Z","is_active":true,"subscriber_count":7}"#;
+        let (base_url, requests) = spawn_mock_server(vec![MockResponse::json(200, body)]).await;
+        let client = cached_http_client(&base_url);
+
+        // The first read decodes into a type that ignores `subscriber_count`
+        let channel: ChannelInfo = client.get("api/channels/ch-1").await.unwrap();
+        assert_eq!(channel.name, "news");
+
+        // The cache holds the body as sent, not a re-serialized `ChannelInfo`
+        let cached: serde_json::Value = client.get("api/channels/ch-1").await.unwrap();
+        assert_eq!(cached, serde_json::from_str::<serde_json::Value>(body).unwrap());
+        let again: ChannelInfo = client.get("api/channels/ch-1").await.unwrap();
+        assert_eq!(again.id, channel.id);
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_update_channel_sends_partial_patch() {
+        let updated = r#"{"id":"ch-1","name":"renamed","type":"public","created_at":"2026-01-01T00:00:00Z","is_active":true}"#;
+        let (base_url, requests) = spawn_mock_server(vec![MockResponse::json(200, updated)]).await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .build()
+            .unwrap();
+
+        let channel = client.update_channel("ch-1", Some("renamed"), None, None).await.unwrap();
+        assert_eq!(channel.name, "renamed");
+
+        let metadata = serde_json::json!({"team": "ops"});
+        client.update_channel("ch-1", None, Some("alerts"), Some(metadata.clone())).await.unwrap();
+
+        let error = client.update_channel("ch-1", None, None, None).await.unwrap_err();
+        assert!(matches!(error, SecureNotifyError::ValidationError(_)));
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2, "an empty update is not sent");
+        let body = |request: &str| -> serde_json::Value {
+            serde_json::from_str(request.split("\r\n\r\n").nth(1).unwrap()).unwrap()
+        };
+        assert!(requests[0].starts_with("PATCH /api/channels/ch-1 "), "{}", requests[0]);
+        assert_eq!(body(&requests[0]), serde_json::json!({"name": "renamed"}));
+        assert_eq!(body(&requests[1]), serde_json::json!({"description": "alerts", "metadata": metadata}));
+    }
+
+    #[tokio::test]
+    async fn test_non_retryable_error_after_retry_is_not_wrapped() {
+        let (base_url, requests) = spawn_mock_server(vec![
+            MockResponse::json(503, "{}"),
+            MockResponse::json(400, r#"{"error":{"code":"BAD_REQUEST","message":"bad"}}"#),
+        ])
+        .await;
+        let exhausted = Arc::new(Mutex::new(0u32));
+        let counter = exhausted.clone();
+
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .max_retries(3)
+            .initial_delay_ms(10)
+            .on_retries_exhausted(Arc::new(move |_: &str, _: &SecureNotifyError, _: u32| {
+                *counter.lock().unwrap() += 1;
+            }))
+            .build()
+            .unwrap();
+
+        let error = client.get_channel("ch-1").await.unwrap_err();
+        assert!(!matches!(error, SecureNotifyError::RetriesExhausted { .. }));
+        assert_eq!(error.status(), 400);
+        assert!(error.is_api_error());
+        assert_eq!(requests.lock().unwrap().len(), 2);
+        assert_eq!(*exhausted.lock().unwrap(), 0);
+
+        // Helpers look through the wrapper of an exhausted retry loop
+        let wrapped = SecureNotifyError::RetriesExhausted {
+            source: Box::new(SecureNotifyError::NetworkError("reset".to_string(), None)),
+            attempts: 2,
+            elapsed: Duration::from_millis(10),
+        };
+        assert!(wrapped.is_network_error());
+        assert!(!wrapped.is_api_error());
+    }
+
+    #[tokio::test]
+    async fn test_sse_400_without_filter_reconnects() {
+        let (base_url, requests) = spawn_mock_server(vec![MockResponse::json(400, r#"{"error":"bad"}"#)]).await;
+        let config = SseConfig::new(format!("{}/api/subscribe/ch-1", base_url), "test-key")
+            .with_reconnect_delay(Duration::from_millis(10))
+            .with_max_reconnect_attempts(1);
+        let (connection, receiver) = SseConnection::new(config);
+
+        let messages = collect_messages(receiver).await;
+        let statuses: Vec<u16> = messages
+            .iter()
+            .filter_map(|m| match m {
+                SseMessage::Error(e, _) => Some(e.status()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(&statuses[..2], &[400, 400]);
+        assert_eq!(requests.lock().unwrap().len(), 2);
+        assert_eq!(connection.state().await, SseState::Failed);
+
+        // Callers can still opt in to stopping on 400
+        let (base_url, requests) = spawn_mock_server(vec![MockResponse::json(400, r#"{"error":"bad"}"#)]).await;
+        let config = SseConfig::new(format!("{}/api/subscribe/ch-1", base_url), "test-key")
+            .with_reconnect_delay(Duration::from_millis(10))
+            .fail_fast_on_status(vec![400]);
+        let (_connection, receiver) = SseConnection::new(config);
+        let messages = collect_messages(receiver).await;
+        assert!(matches!(messages.last(), Some(SseMessage::Disconnected(DisconnectReason::Error))));
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_with_history_closes_subscription_on_fetch_failure() {
+        // Every GET gets an event stream, so the history fetch cannot be decoded
+        let (base_url, requests) = spawn_sse_stream_server().await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .max_retries(0)
+            .timeout(Duration::from_millis(500))
+            .build()
+            .unwrap();
+
+        assert!(client.subscribe_with_history("ch-1", 10).await.is_err());
+        assert!(client.subscription_health("ch-1").await.is_none());
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let seen = requests.lock().unwrap().len();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(requests.lock().unwrap().len(), seen, "no reconnects expected");
+    }
+
+    #[tokio::test]
+    async fn test_sse_disconnect_emits_once_without_waiting() {
+        let (base_url, _requests) = spawn_sse_stream_server().await;
+        let config = SseConfig::new(format!("{}/api/subscribe/ch-1", base_url), "test-key");
+        let (connection, mut receiver) = SseConnection::new(config);
+        let first = tokio::time::timeout(Duration::from_secs(5), receiver.recv()).await.unwrap().unwrap();
+        assert!(matches!(first, SseMessage::Connected), "{:?}", first);
+
+        // Concurrent and repeated calls while paused yield one final message
+        connection.pause();
+        let other = connection.clone();
+        tokio::join!(connection.disconnect(), other.disconnect());
+        connection.disconnect().await;
+        let mut finals = Vec::new();
+        while let Ok(message) = receiver.try_recv() {
+            if !matches!(message, SseMessage::Heartbeat) {
+                finals.push(message);
+            }
+        }
+        assert_eq!(finals.len(), 1, "{:?}", finals);
+        assert!(matches!(finals[0], SseMessage::Disconnected(DisconnectReason::ClientRequested)));
+
+        // A full channel that nobody reads does not hold up disconnect
+        let config = SseConfig::new(format!("{}/api/subscribe/ch-1", base_url), "test-key").with_channel_buffer(1);
+        let (connection, mut receiver) = SseConnection::new(config);
+        while receiver.is_empty() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        tokio::time::timeout(Duration::from_secs(2), connection.disconnect())
+            .await
+            .expect("disconnect waited for the consumer");
+        assert!(matches!(receiver.try_recv(), Ok(SseMessage::Connected)));
+        assert!(receiver.try_recv().is_err());
+        // The lost final message is not an overflow drop
+        assert_eq!(connection.dropped_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_unsubscribe_stops_the_channel_subscription() {
+        let (base_url, requests) = spawn_sse_stream_server().await;
+        let client = SecureNotifyClient::builder()
+            .base_url(base_url)
+            .api_key("test-key")
+            .build()
+            .unwrap();
+
+        let mut receivers = [
+            client.subscribe("ch-1").await.unwrap(),
+            client.subscribe("ch-2").await.unwrap(),
+        ];
+        for receiver in receivers.iter_mut() {
+            loop {
+                let message = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+                    .await
+                    .unwrap()
+                    .unwrap();
+                if matches!(message, SseMessage::Connected) {
+                    break;
+                }
+            }
+        }
+
+        client.unsubscribe("ch-1").await.unwrap();
+
+        let [unsubscribed, other] = &mut receivers;
+        let closed = tokio::time::timeout(Duration::from_secs(5), async {
+            while unsubscribed.recv().await.is_some() {}
+        })
+        .await;
+        assert!(closed.is_ok(), "receiver still open after unsubscribe");
+        assert!(client.subscription_health("ch-1").await.is_none());
+        assert!(client.subscription_health("ch-2").await.is_some());
+        assert!(!other.is_closed());
+
+        // Only the remaining channel is left for unsubscribe_all
+        client.unsubscribe_all().await.unwrap();
+        let deletes: Vec<String> = requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.starts_with("DELETE "))
+            .map(|r| r.lines().next().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            deletes,
+            ["DELETE /api/subscribe/ch-1 HTTP/1.1", "DELETE /api/subscribe/ch-2 HTTP/1.1"]
+        );
+    }
 }
@@ -3,7 +3,12 @@
 
 //! API type definitions for SecureNotify SDK
 
+use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
+use super::lenient;
+use serde_json::json;
+use crate::MessagePriority;
+use crate::utils::request_deduplicator::canonical_json;
 
 /// Request to register a public key
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -68,6 +73,20 @@ pub struct ChannelCreateRequest {
     pub metadata: Option<serde_json::Value>,
 }
 
+/// Partial update of a channel; fields left as `None` are not sent
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChannelUpdateRequest {
+    /// New channel name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// New channel description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// New metadata, replacing the existing metadata
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+}
+
 /// Response from creating a channel
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChannelCreateResponse {
@@ -115,13 +134,49 @@ pub struct ChannelInfo {
     pub metadata: Option<serde_json::Value>,
 }
 
+impl ChannelInfo {
+    /// List the fields that differ from `other`
+    ///
+    /// Compares the name, description, active flag, expiry and metadata,
+    /// with `self` as the old version and `other` as the new one.
+    pub fn diff(&self, other: &ChannelInfo) -> Vec<FieldChange> {
+        let mut changes = Vec::new();
+        FieldChange::compare(&mut changes, "name", json!(self.name), json!(other.name));
+        FieldChange::compare(&mut changes, "description", json!(self.description), json!(other.description));
+        FieldChange::compare(&mut changes, "is_active", json!(self.is_active), json!(other.is_active));
+        FieldChange::compare(&mut changes, "expires_at", json!(self.expires_at), json!(other.expires_at));
+        FieldChange::compare(&mut changes, "metadata", json!(self.metadata), json!(other.metadata));
+        changes
+    }
+}
+
+/// What the caller can do with a channel, from `check_channel_access`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelAccess {
+    /// Whether the channel was found
+    pub exists: bool,
+    /// Whether the channel is active and not past its expiry
+    pub is_active: bool,
+    /// Whether messages can be published to the channel
+    pub writable: bool,
+    /// Whether the channel can be subscribed to
+    pub readable: bool,
+}
+
+/// Request to transfer a channel to another owner
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelTransferRequest {
+    /// The user ID of the new owner
+    pub new_owner: String,
+}
+
 /// Request to publish a message
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MessagePublishRequest {
     /// The message content
     pub message: String,
     /// Message priority (default: Normal)
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, deserialize_with = "lenient::option_u8", skip_serializing_if = "Option::is_none")]
     pub priority: Option<u8>,
     /// Optional sender identifier
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -146,6 +201,19 @@ pub struct MessagePublishResponse {
     pub timestamp: String,
     /// The channel ID
     pub channel: String,
+    /// Position of the message in the channel queue, if reported
+    #[serde(default, deserialize_with = "lenient::option_u64", skip_serializing_if = "Option::is_none")]
+    pub queue_position: Option<u64>,
+    /// Estimated seconds until the message is delivered, if reported
+    #[serde(default, deserialize_with = "lenient::option_u64", skip_serializing_if = "Option::is_none")]
+    pub estimated_delivery_seconds: Option<u64>,
+}
+
+/// Request to acknowledge or reject consumed messages
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageAckRequest {
+    /// IDs of the messages being acknowledged or rejected
+    pub message_ids: Vec<String>,
 }
 
 /// Information about a message
@@ -165,10 +233,47 @@ pub struct MessageInfo {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sender: Option<String>,
     /// Message priority
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, deserialize_with = "lenient::option_u8", skip_serializing_if = "Option::is_none")]
     pub priority: Option<u8>,
 }
 
+/// An operation scope that can be granted to an API key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Permission {
+    /// Publish messages
+    Publish,
+    /// Subscribe to channels
+    Subscribe,
+    /// Every operation
+    Admin,
+}
+
+impl Permission {
+    /// Get the name used for this permission in scopes and key info
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Publish => "publish",
+            Self::Subscribe => "subscribe",
+            Self::Admin => "admin",
+        }
+    }
+
+    /// Parse a permission name, ignoring case; unknown names give `None`
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "publish" => Some(Self::Publish),
+            "subscribe" => Some(Self::Subscribe),
+            "admin" => Some(Self::Admin),
+            _ => None,
+        }
+    }
+
+    /// Check whether a set of granted permissions allows this one
+    pub fn is_granted_by(&self, granted: &[Permission]) -> bool {
+        granted.iter().any(|p| p == self || *p == Self::Admin)
+    }
+}
+
 /// Request to create an API key
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiKeyCreateRequest {
@@ -230,6 +335,57 @@ pub struct ApiKeyInfo {
     pub expires_at: Option<String>,
 }
 
+impl ApiKeyInfo {
+    /// Get the recognized permissions granted to the key
+    pub fn granted_permissions(&self) -> Vec<Permission> {
+        self.permissions
+            .iter()
+            .flatten()
+            .filter_map(|name| Permission::parse(name))
+            .collect()
+    }
+
+    /// List the fields that differ from `other`
+    ///
+    /// Compares the name, permissions, active flag and expiry, with `self`
+    /// as the old version and `other` as the new one.
+    pub fn diff(&self, other: &ApiKeyInfo) -> Vec<FieldChange> {
+        let mut changes = Vec::new();
+        FieldChange::compare(&mut changes, "name", json!(self.name), json!(other.name));
+        FieldChange::compare(&mut changes, "permissions", json!(self.permissions), json!(other.permissions));
+        FieldChange::compare(&mut changes, "is_active", json!(self.is_active), json!(other.is_active));
+        FieldChange::compare(&mut changes, "expires_at", json!(self.expires_at), json!(other.expires_at));
+        changes
+    }
+}
+
+/// A field whose value differs between two fetches of a resource
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    /// The field name
+    pub field: &'static str,
+    /// Value in the older version (`null` if unset)
+    pub old: serde_json::Value,
+    /// Value in the newer version (`null` if unset)
+    pub new: serde_json::Value,
+}
+
+impl FieldChange {
+    /// Record a change if the values differ once canonicalized
+    ///
+    /// Canonical comparison ignores object key order and `1` vs `1.0`.
+    fn compare(
+        changes: &mut Vec<FieldChange>,
+        field: &'static str,
+        old: serde_json::Value,
+        new: serde_json::Value,
+    ) {
+        if canonical_json(&old) != canonical_json(&new) {
+            changes.push(FieldChange { field, old, new });
+        }
+    }
+}
+
 /// Subscription information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubscriptionInfo {
@@ -243,17 +399,146 @@ pub struct SubscriptionInfo {
     pub is_active: bool,
 }
 
+/// Kind of a subscription history event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubscriptionEventKind {
+    /// The subscriber connected
+    Connect,
+    /// The subscriber disconnected
+    Disconnect,
+    /// Periodic count of messages delivered to the subscriber
+    MessageCount,
+    /// An event kind this SDK version does not know
+    #[serde(other)]
+    Unknown,
+}
+
+/// Entry in a subscription's event history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriptionEvent {
+    /// The event kind
+    #[serde(rename = "type")]
+    pub event_type: SubscriptionEventKind,
+    /// When the event occurred
+    pub timestamp: String,
+    /// Messages delivered, for `message_count` events
+    #[serde(default, deserialize_with = "lenient::option_u64", skip_serializing_if = "Option::is_none")]
+    pub message_count: Option<u64>,
+    /// Optional metadata, such as a disconnect reason
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+}
+
 /// Message queue status
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueueStatus {
     /// Total messages in the queue
+    #[serde(deserialize_with = "lenient::u64")]
     pub total: u64,
     /// Messages by priority
     pub by_priority: serde_json::Value,
     /// Queue wait time estimate (seconds)
+    #[serde(deserialize_with = "lenient::u64")]
     pub estimated_wait_seconds: u64,
 }
 
+impl QueueStatus {
+    /// Get the fraction of queued messages at each priority
+    ///
+    /// `by_priority` may be keyed by priority value (`"75"`) or name
+    /// (`"high"`), with counts as numbers or numeric strings; other keys are
+    /// ignored. Every priority is listed, from `Critical` down to `Bulk`. The
+    /// fractions sum to 1, or are all 0 for an empty queue.
+    pub fn priority_distribution(&self) -> Vec<(MessagePriority, f64)> {
+        const PRIORITIES: [MessagePriority; 5] = [
+            MessagePriority::Critical,
+            MessagePriority::High,
+            MessagePriority::Normal,
+            MessagePriority::Low,
+            MessagePriority::Bulk,
+        ];
+
+        let mut counts = [0u64; PRIORITIES.len()];
+        if let Some(by_priority) = self.by_priority.as_object() {
+            for (key, count) in by_priority {
+                let key = key.trim().to_ascii_lowercase();
+                let index = PRIORITIES
+                    .iter()
+                    .position(|priority| key == priority.as_str() || key == priority.value().to_string());
+                if let (Some(index), Ok(count)) = (index, lenient::u64(count)) {
+                    counts[index] += count;
+                }
+            }
+        }
+
+        let queued: u64 = counts.iter().sum();
+        PRIORITIES
+            .iter()
+            .zip(counts)
+            .map(|(priority, count)| {
+                let fraction = if queued == 0 { 0.0 } else { count as f64 / queued as f64 };
+                (*priority, fraction)
+            })
+            .collect()
+    }
+}
+
+/// Validators identifying the version of a resource a client already has
+///
+/// Taken from the `ETag` and `Last-Modified` headers of a previous response
+/// and sent back as `If-None-Match` and `If-Modified-Since`. Weak ETags
+/// (`W/"..."`) are sent unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Validators {
+    /// Entity tag of the cached version
+    pub etag: Option<String>,
+    /// `Last-Modified` date of the cached version
+    pub last_modified: Option<String>,
+}
+
+impl Validators {
+    /// Read the validators from response headers
+    pub fn from_headers(headers: &http::HeaderMap) -> Self {
+        let header = |name: http::header::HeaderName| {
+            headers.get(name).and_then(|v| v.to_str().ok()).map(String::from)
+        };
+        Self {
+            etag: header(http::header::ETAG),
+            last_modified: header(http::header::LAST_MODIFIED),
+        }
+    }
+
+    /// Check whether no validator is set
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
+/// Result of a conditional request
+#[derive(Debug, Clone, PartialEq)]
+pub enum Conditional<T> {
+    /// The resource changed; the new version and its validators
+    Modified { value: T, validators: Validators },
+    /// The server answered 304; the cached version is still current
+    NotModified,
+}
+
+impl<T> Conditional<T> {
+    /// Check whether the server reported the resource as unchanged
+    pub fn is_not_modified(&self) -> bool {
+        matches!(self, Conditional::NotModified)
+    }
+
+    /// Get the new value, if the resource changed
+    pub fn into_value(self) -> Option<T> {
+        match self {
+            Conditional::Modified { value, .. } => Some(value),
+            Conditional::NotModified => None,
+        }
+    }
+}
+
 /// Generic API response wrapper
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiResponse<T> {
@@ -267,10 +552,30 @@ pub struct ApiResponse<T> {
     pub error: Option<ApiErrorDetails>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Error information returned by the API
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ApiErrorDetails {
     pub code: String,
     pub message: String,
+    /// Additional error context, in a server-defined shape
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+    /// Validation messages keyed by the request field they refer to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field_errors: Option<HashMap<String, Vec<String>>>,
+}
+
+impl ApiErrorDetails {
+    /// Parse the error information from a response body
+    ///
+    /// Accepts both the `ApiResponse` envelope (`{"success": false, "error": {...}}`)
+    /// and a bare error object.
+    pub fn from_body(body: &str) -> Option<Self> {
+        if let Ok(ApiResponse::<serde_json::Value> { error: Some(error), .. }) = serde_json::from_str(body) {
+            return Some(error);
+        }
+        serde_json::from_str(body).ok()
+    }
 }
 
 /// SSE event types
@@ -281,9 +586,24 @@ pub enum SseEventType {
     Error,
     Connected,
     Disconnected,
+    Draining,
+    Comment,
     Unknown(String),
 }
 
+impl SseEventType {
+    /// Map an SSE `event:` name to its type, keeping unrecognized names
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "message" => Self::Message,
+            "heartbeat" => Self::Heartbeat,
+            "error" => Self::Error,
+            "connected" => Self::Connected,
+            other => Self::Unknown(other.to_string()),
+        }
+    }
+}
+
 impl std::fmt::Display for SseEventType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -292,6 +612,8 @@ impl std::fmt::Display for SseEventType {
             Self::Error => write!(f, "error"),
             Self::Connected => write!(f, "connected"),
             Self::Disconnected => write!(f, "disconnected"),
+            Self::Draining => write!(f, "draining"),
+            Self::Comment => write!(f, "comment"),
             Self::Unknown(s) => write!(f, "unknown: {}", s),
         }
     }
@@ -326,6 +648,65 @@ impl SseEvent {
     }
 }
 
+/// Message delivered to subscribers
+///
+/// This is the parsed form of a message event's JSON data and the
+/// recommended way to consume subscription messages.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageEnvelope {
+    /// The unique message ID
+    pub message_id: String,
+    /// The channel ID
+    pub channel: String,
+    /// The message content
+    pub message: String,
+    /// Whether the message is encrypted
+    #[serde(default)]
+    pub encrypted: bool,
+    /// Optional sender identifier
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sender: Option<String>,
+    /// Message priority
+    #[serde(default, deserialize_with = "lenient::option_u8", skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u8>,
+    /// When the message was created
+    pub timestamp: String,
+}
+
+impl MessageEnvelope {
+    /// Build a message event carrying this envelope as its JSON data
+    pub fn to_sse_event(&self) -> crate::Result<SseEvent> {
+        Ok(SseEvent::new(
+            SseEventType::Message,
+            serde_json::to_string(self)?,
+            Some(self.message_id.clone()),
+            None,
+        ))
+    }
+}
+
+impl From<MessageInfo> for MessageEnvelope {
+    fn from(info: MessageInfo) -> Self {
+        Self {
+            message_id: info.id,
+            channel: info.channel,
+            message: info.message,
+            encrypted: info.encrypted,
+            sender: info.sender,
+            priority: info.priority,
+            timestamp: info.created_at,
+        }
+    }
+}
+
+impl TryFrom<&SseEvent> for MessageEnvelope {
+    type Error = crate::SecureNotifyError;
+
+    fn try_from(event: &SseEvent) -> std::result::Result<Self, Self::Error> {
+        Ok(serde_json::from_str(&event.data)?)
+    }
+}
+
 /// Stream event for real-time message delivery
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamEvent {
@@ -3,16 +3,54 @@
 
 //! Error types for SecureNotify SDK
 
+use std::sync::Arc;
 use thiserror::Error;
 use crate::SecureNotifyError;
 
+/// Shared handle to the underlying error that caused a SecureNotifyError
+///
+/// Held in an `Arc` so that `SecureNotifyError` stays `Clone` while keeping
+/// the original error, and its own source chain, reachable via `source()`.
+#[derive(Clone)]
+pub struct ErrorSource(Arc<dyn std::error::Error + Send + Sync + 'static>);
+
+impl ErrorSource {
+    /// Wrap an underlying error
+    pub fn new(error: impl std::error::Error + Send + Sync + 'static) -> Self {
+        Self(Arc::new(error))
+    }
+
+    /// Get the wrapped error
+    pub fn inner(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+        self.0.as_ref()
+    }
+}
+
+impl std::fmt::Debug for ErrorSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl std::fmt::Display for ErrorSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl std::error::Error for ErrorSource {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.0.source()
+    }
+}
+
 /// Convert from reqwest errors
 impl From<reqwest::Error> for SecureNotifyError {
     fn from(e: reqwest::Error) -> Self {
         if e.is_timeout() {
-            Self::TimeoutError(e.to_string())
+            Self::TimeoutError(e.to_string(), Some(ErrorSource::new(e)))
         } else if e.is_connect() {
-            Self::ConnectionError(e.to_string())
+            Self::ConnectionError(e.to_string(), Some(ErrorSource::new(e)))
         } else if e.is_status() {
             let status = e.status().unwrap_or(reqwest::StatusCode::INTERNAL_SERVER_ERROR);
             let code = status.as_u16().to_string();
@@ -21,9 +59,10 @@ impl From<reqwest::Error> for SecureNotifyError {
                 code,
                 message,
                 status: status.as_u16(),
+                details: None,
             }
         } else {
-            Self::NetworkError(e.to_string())
+            Self::NetworkError(e.to_string(), Some(ErrorSource::new(e)))
         }
     }
 }
@@ -31,28 +70,97 @@ impl From<reqwest::Error> for SecureNotifyError {
 /// Convert from serde_json errors
 impl From<serde_json::Error> for SecureNotifyError {
     fn from(e: serde_json::Error) -> Self {
-        Self::SerializationError(e.to_string())
+        Self::SerializationError(e.to_string(), Some(ErrorSource::new(e)))
     }
 }
 
 /// Convert from url parsing errors
 impl From<url::ParseError> for SecureNotifyError {
     fn from(e: url::ParseError) -> Self {
-        Self::ConnectionError(format!("URL parsing error: {}", e))
+        Self::ConnectionError(format!("URL parsing error: {}", e), Some(ErrorSource::new(e)))
     }
 }
 
 /// Convert from std::io errors
 impl From<std::io::Error> for SecureNotifyError {
     fn from(e: std::io::Error) -> Self {
-        Self::ConnectionError(e.to_string())
+        Self::ConnectionError(e.to_string(), Some(ErrorSource::new(e)))
     }
 }
 
 /// Convert from tokio::time::Elapsed error
 impl From<tokio::time::error::Elapsed> for SecureNotifyError {
-    fn from(_: tokio::time::error::Elapsed) -> Self {
-        Self::TimeoutError("Request timed out".to_string())
+    fn from(e: tokio::time::error::Elapsed) -> Self {
+        Self::TimeoutError("Request timed out".to_string(), Some(ErrorSource::new(e)))
+    }
+}
+
+/// Broad category of a SecureNotifyError
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    Api,
+    Network,
+    Connection,
+    Timeout,
+    Serialization,
+    RequestSerialization,
+    Auth,
+    SignatureVerification,
+    Conflict,
+    NotFound,
+    PayloadTooLarge,
+    UnexpectedContentType,
+    ServiceUnavailable,
+    Validation,
+    Cancelled,
+    Unknown,
+}
+
+impl ErrorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Api => "api",
+            Self::Network => "network",
+            Self::Connection => "connection",
+            Self::Timeout => "timeout",
+            Self::Serialization => "serialization",
+            Self::RequestSerialization => "request_serialization",
+            Self::Auth => "auth",
+            Self::SignatureVerification => "signature_verification",
+            Self::Conflict => "conflict",
+            Self::NotFound => "not_found",
+            Self::PayloadTooLarge => "payload_too_large",
+            Self::UnexpectedContentType => "unexpected_content_type",
+            Self::ServiceUnavailable => "service_unavailable",
+            Self::Validation => "validation",
+            Self::Cancelled => "cancelled",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+impl SecureNotifyError {
+    /// Get the broad category of this error
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::ApiError { .. } => ErrorKind::Api,
+            Self::NetworkError(..) => ErrorKind::Network,
+            Self::ConnectionError(..) => ErrorKind::Connection,
+            Self::TimeoutError(..) => ErrorKind::Timeout,
+            Self::SerializationError(..) => ErrorKind::Serialization,
+            Self::RequestSerializationError(..) => ErrorKind::RequestSerialization,
+            Self::AuthError(_) => ErrorKind::Auth,
+            Self::SignatureVerificationError(_) => ErrorKind::SignatureVerification,
+            Self::Conflict { .. } => ErrorKind::Conflict,
+            Self::NotFound { .. } => ErrorKind::NotFound,
+            Self::PayloadTooLarge { .. } => ErrorKind::PayloadTooLarge,
+            Self::UnexpectedContentType { .. } => ErrorKind::UnexpectedContentType,
+            Self::ServiceUnavailable { .. } => ErrorKind::ServiceUnavailable,
+            Self::ValidationError(_) => ErrorKind::Validation,
+            Self::Cancelled => ErrorKind::Cancelled,
+            Self::RetriesExhausted { source, .. } => source.kind(),
+            Self::Unknown(_) => ErrorKind::Unknown,
+        }
     }
 }
 
@@ -86,6 +194,7 @@ impl From<ManagerError> for SecureNotifyError {
                     code: "KEY_MANAGER_ERROR".to_string(),
                     message: msg,
                     status: 500,
+                    details: None,
                 }
             }
             ManagerError::ChannelManager(msg) => {
@@ -93,6 +202,7 @@ impl From<ManagerError> for SecureNotifyError {
                     code: "CHANNEL_MANAGER_ERROR".to_string(),
                     message: msg,
                     status: 500,
+                    details: None,
                 }
             }
             ManagerError::PublishManager(msg) => {
@@ -100,6 +210,7 @@ impl From<ManagerError> for SecureNotifyError {
                     code: "PUBLISH_MANAGER_ERROR".to_string(),
                     message: msg,
                     status: 500,
+                    details: None,
                 }
             }
             ManagerError::SubscribeManager(msg) => {
@@ -107,6 +218,7 @@ impl From<ManagerError> for SecureNotifyError {
                     code: "SUBSCRIBE_MANAGER_ERROR".to_string(),
                     message: msg,
                     status: 500,
+                    details: None,
                 }
             }
             ManagerError::ApiKeyManager(msg) => {
@@ -114,6 +226,7 @@ impl From<ManagerError> for SecureNotifyError {
                     code: "API_KEY_MANAGER_ERROR".to_string(),
                     message: msg,
                     status: 500,
+                    details: None,
                 }
             }
         }
@@ -131,10 +244,98 @@ pub fn is_retryable_status(status: u16) -> bool {
 /// Check if an error is retryable
 pub fn is_retryable_error(error: &SecureNotifyError) -> bool {
     match error {
-        SecureNotifyError::NetworkError(_) => true,
-        SecureNotifyError::ConnectionError(_) => true,
-        SecureNotifyError::TimeoutError(_) => true,
+        SecureNotifyError::NetworkError(..) => true,
+        SecureNotifyError::ConnectionError(..) => true,
+        SecureNotifyError::TimeoutError(..) => true,
         SecureNotifyError::ApiError { status, .. } => is_retryable_status(*status),
+        SecureNotifyError::ServiceUnavailable { .. } => true,
+        _ => false,
+    }
+}
+
+/// Check if a request failed before the server could have received it
+///
+/// True only when the connection could not be established (refused, DNS
+/// failure, TLS handshake failure or connect timeout). Failures after the
+/// request was sent, including partially received responses, return false.
+pub fn is_pre_response_error(error: &SecureNotifyError) -> bool {
+    match error {
+        SecureNotifyError::NetworkError(_, Some(source))
+        | SecureNotifyError::ConnectionError(_, Some(source))
+        | SecureNotifyError::TimeoutError(_, Some(source)) => source
+            .inner()
+            .downcast_ref::<reqwest::Error>()
+            .is_some_and(reqwest::Error::is_connect),
         _ => false,
     }
 }
+
+/// Header a server may use to state the largest accepted payload in bytes
+pub const MAX_PAYLOAD_SIZE_HEADER: &str = "x-max-payload-size";
+
+/// Find the payload size limit in a 413 response
+///
+/// Checks the `X-Max-Payload-Size` header, then a `limit`, `max_size` or
+/// `maxSize` field at the top level of a JSON body or under its `error` or
+/// `details` object. Numbers may be sent as JSON numbers or strings.
+pub fn parse_payload_limit(header: Option<&str>, body: &str) -> Option<u64> {
+    if let Some(limit) = header.and_then(|v| v.trim().parse().ok()) {
+        return Some(limit);
+    }
+
+    let body: serde_json::Value = serde_json::from_str(body).ok()?;
+    let objects = [Some(&body), body.get("error"), body.get("details")];
+    let limit = objects.into_iter().flatten().find_map(|object| {
+        ["limit", "max_size", "maxSize"].iter().find_map(|key| match object.get(key)? {
+            serde_json::Value::Number(n) => n.as_u64(),
+            serde_json::Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        })
+    });
+    limit
+}
+
+/// Characters of a response body kept in `SecureNotifyError::UnexpectedContentType`
+const BODY_SNIPPET_CHARS: usize = 200;
+
+/// Check whether a `Content-Type` value names a JSON media type
+///
+/// Accepts `application/json`, `text/json` and structured `+json` types
+/// such as `application/problem+json`, ignoring parameters and case.
+pub fn is_json_content_type(value: &str) -> bool {
+    let essence = value.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    match essence.split_once('/') {
+        Some((_, subtype)) => subtype == "json" || subtype.ends_with("+json"),
+        None => false,
+    }
+}
+
+/// The start of a response body for error messages, with whitespace collapsed
+pub fn body_snippet(body: &[u8]) -> String {
+    let text = String::from_utf8_lossy(body);
+    let mut snippet: String = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if let Some((index, _)) = snippet.char_indices().nth(BODY_SNIPPET_CHARS) {
+        snippet.truncate(index);
+        snippet.push_str("...");
+    }
+    snippet
+}
+
+/// Parse a `Retry-After` header value (delay in seconds or an HTTP date)
+pub fn parse_retry_after(value: &str) -> Option<std::time::Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(std::time::Duration::from_secs(seconds));
+    }
+
+    let delay = parse_http_date(value)? - time::OffsetDateTime::now_utc();
+    Some(std::time::Duration::try_from(delay).unwrap_or_default())
+}
+
+/// Parse an HTTP date such as `Wed, 21 Oct 2026 07:28:00 GMT`
+pub fn parse_http_date(value: &str) -> Option<time::OffsetDateTime> {
+    let format = time::macros::format_description!(
+        "[weekday repr:short], [day] [month repr:short] [year] [hour]:[minute]:[second] GMT"
+    );
+    Some(time::PrimitiveDateTime::parse(value.trim(), &format).ok()?.assume_utc())
+}
@@ -0,0 +1,74 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (c) 2026 KirkyX. All rights reserved.
+
+//! Tolerant deserializers for numeric fields
+//!
+//! Some servers send counters and priorities as strings (`"75"`) or floats
+//! (`75.0`). These helpers accept any of those forms for unsigned integer
+//! fields, and are used through `#[serde(deserialize_with = "...")]`.
+
+use serde::de::{Deserializer, Error};
+use serde::Deserialize;
+
+/// Any JSON representation of an unsigned integer
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Number {
+    Unsigned(u64),
+    Float(f64),
+    Text(String),
+}
+
+impl Number {
+    fn to_u64(&self) -> Option<u64> {
+        match self {
+            Number::Unsigned(n) => Some(*n),
+            Number::Float(f) => float_to_u64(*f),
+            Number::Text(s) => {
+                let s = s.trim();
+                s.parse::<u64>()
+                    .ok()
+                    .or_else(|| s.parse::<f64>().ok().and_then(float_to_u64))
+            }
+        }
+    }
+}
+
+/// Convert an integral, non-negative float that fits in a u64
+fn float_to_u64(f: f64) -> Option<u64> {
+    (f.is_finite() && f.fract() == 0.0 && f >= 0.0 && f < u64::MAX as f64).then_some(f as u64)
+}
+
+fn convert<T: TryFrom<u64>, E: Error>(number: Number) -> Result<T, E> {
+    number
+        .to_u64()
+        .and_then(|n| T::try_from(n).ok())
+        .ok_or_else(|| E::custom(format!("invalid {}", describe(&number))))
+}
+
+fn describe(number: &Number) -> String {
+    match number {
+        Number::Unsigned(n) => format!("unsigned integer {} (out of range)", n),
+        Number::Float(f) => format!("number {}", f),
+        Number::Text(s) => format!("numeric string {:?}", s),
+    }
+}
+
+/// Deserialize a `u64` from a number or numeric string
+pub fn u64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    convert(Number::deserialize(deserializer)?)
+}
+
+/// Deserialize an optional `u64` from a number, numeric string or null
+pub fn option_u64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    Option::<Number>::deserialize(deserializer)?
+        .map(convert)
+        .transpose()
+}
+
+/// Deserialize an optional `u8` from a number, numeric string or null
+pub fn option_u8<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u8>, D::Error> {
+    Option::<Number>::deserialize(deserializer)?
+        .map(convert)
+        .transpose()
+}
@@ -5,6 +5,7 @@
 
 pub mod api;
 pub mod error;
+pub mod lenient;
 
 pub use api::*;
 pub use error::*;
@@ -5,7 +5,8 @@
 
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use serde::{Serialize, Deserialize};
 
 /// Cache entry with expiration
 #[derive(Debug, Clone)]
@@ -15,7 +16,7 @@ struct CacheEntry<T> {
 }
 
 /// Cache metrics
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CacheMetrics {
     pub hits: u64,
     pub misses: u64,
@@ -23,6 +24,65 @@ pub struct CacheMetrics {
     pub cleanup_count: u64,
 }
 
+/// Serializable copy of a cache's live entries, for persisting across restarts
+///
+/// Expiry is stored as wall-clock time because `Instant` does not survive a
+/// process restart.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheSnapshot<T> {
+    pub entries: Vec<CacheSnapshotEntry<T>>,
+}
+
+/// A single entry of a [`CacheSnapshot`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheSnapshotEntry<T> {
+    pub key: String,
+    pub value: T,
+    /// Expiry in milliseconds since the Unix epoch
+    pub expires_at_ms: u64,
+}
+
+/// Convert a wall-clock time to milliseconds since the Unix epoch
+pub(crate) fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+/// Caching policy derived from a `Cache-Control` response header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CachePolicy {
+    /// The response must not be cached (`no-store`, `private` or `max-age=0`)
+    NoStore,
+    /// The response may be cached for the given duration (`max-age`)
+    MaxAge(Duration),
+    /// No caching directive; the cache default TTL applies
+    Default,
+}
+
+impl CachePolicy {
+    /// Parse a `Cache-Control` header value
+    pub fn from_cache_control(value: Option<&str>) -> Self {
+        let mut policy = Self::Default;
+
+        for directive in value.unwrap_or_default().split(',') {
+            let directive = directive.trim().to_ascii_lowercase();
+            if directive == "no-store" || directive == "private" {
+                return Self::NoStore;
+            }
+            if let Some(seconds) = directive.strip_prefix("max-age=") {
+                if let Ok(seconds) = seconds.trim_matches('"').parse::<u64>() {
+                    policy = if seconds == 0 {
+                        Self::NoStore
+                    } else {
+                        Self::MaxAge(Duration::from_secs(seconds))
+                    };
+                }
+            }
+        }
+
+        policy
+    }
+}
+
 /// Response cache with TTL support
 pub struct ResponseCache<T> {
     cache: Arc<RwLock<HashMap<String, CacheEntry<T>>>>,
@@ -42,11 +102,6 @@ impl<T: Clone> ResponseCache<T> {
         }
     }
 
-    /// Create a response cache with default settings (60s TTL, 1000 max entries)
-    pub fn default() -> Self {
-        Self::new(Duration::from_secs(60), 1000)
-    }
-
     /// Get a value from the cache
     /// Returns cloned value for safety (avoids lifetime issues with locked data)
     pub fn get(&self, key: &str) -> Option<T> {
@@ -94,6 +149,14 @@ impl<T: Clone> ResponseCache<T> {
         metrics.entries = cache.len() as u64;
     }
 
+    /// Get the remaining time to live of an entry
+    pub fn ttl(&self, key: &str) -> Option<Duration> {
+        let cache = self.cache.read().unwrap();
+        cache
+            .get(key)
+            .and_then(|entry| entry.expires_at.checked_duration_since(Instant::now()))
+    }
+
     /// Delete a value from the cache
     pub fn delete(&self, key: &str) {
         let mut cache = self.cache.write().unwrap();
@@ -132,6 +195,47 @@ impl<T: Clone> ResponseCache<T> {
         removed
     }
 
+    /// Export all unexpired entries
+    pub fn export(&self) -> CacheSnapshot<T> {
+        let cache = self.cache.read().unwrap();
+        let now = Instant::now();
+        let now_ms = unix_millis(SystemTime::now());
+
+        let entries = cache
+            .iter()
+            .filter_map(|(key, entry)| {
+                let remaining = entry.expires_at.checked_duration_since(now)?;
+                Some(CacheSnapshotEntry {
+                    key: key.clone(),
+                    value: entry.value.clone(),
+                    expires_at_ms: now_ms + remaining.as_millis() as u64,
+                })
+            })
+            .collect();
+        CacheSnapshot { entries }
+    }
+
+    /// Load entries from a snapshot, skipping those that have since expired
+    ///
+    /// Imported entries keep their original expiry and replace existing
+    /// entries with the same key.
+    ///
+    /// # Returns
+    /// Number of entries imported
+    pub fn import(&self, snapshot: CacheSnapshot<T>) -> usize {
+        let now_ms = unix_millis(SystemTime::now());
+        let mut imported = 0;
+
+        for entry in snapshot.entries {
+            if entry.expires_at_ms > now_ms {
+                let ttl = Duration::from_millis(entry.expires_at_ms - now_ms);
+                self.set(entry.key, entry.value, Some(ttl));
+                imported += 1;
+            }
+        }
+        imported
+    }
+
     /// Get the current cache size
     pub fn size(&self) -> usize {
         let cache = self.cache.read().unwrap();
@@ -160,4 +264,11 @@ impl<T: Clone> ResponseCache<T> {
             metrics.hits as f64 / total as f64
         }
     }
-}
\ No newline at end of file
+}
+
+impl<T: Clone> Default for ResponseCache<T> {
+    /// Create a response cache with default settings (60s TTL, 1000 max entries)
+    fn default() -> Self {
+        Self::new(Duration::from_secs(60), 1000)
+    }
+}
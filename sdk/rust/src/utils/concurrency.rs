@@ -0,0 +1,126 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (c) 2026 KirkyX. All rights reserved.
+
+//! Adaptive concurrency limiting for SDK operations
+
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Latency above this multiple of the running average counts as a spike
+const LATENCY_SPIKE_FACTOR: f64 = 2.0;
+
+/// Weight of the newest sample in the running latency average
+const LATENCY_EWMA_WEIGHT: f64 = 0.1;
+
+/// Factor applied to the limit on errors and latency spikes
+const DECREASE_FACTOR: f64 = 0.5;
+
+/// Mutable state of the limiter
+#[derive(Debug)]
+struct LimiterState {
+    limit: f64,
+    in_flight: usize,
+    avg_latency_ms: Option<f64>,
+}
+
+/// AIMD (additive increase, multiplicative decrease) concurrency limiter
+///
+/// Each healthy response grows the limit by `1 / limit`, i.e. by about one
+/// per round of requests. An error or a latency spike halves it. The limit
+/// always stays within `[min, max]` and starts at `max`.
+#[derive(Debug)]
+pub struct AdaptiveConcurrencyLimiter {
+    min: usize,
+    max: usize,
+    state: Mutex<LimiterState>,
+    released: Notify,
+}
+
+/// Slot for one in-flight request, released on drop
+#[derive(Debug)]
+pub struct ConcurrencyPermit<'a> {
+    limiter: &'a AdaptiveConcurrencyLimiter,
+}
+
+impl Drop for ConcurrencyPermit<'_> {
+    fn drop(&mut self) {
+        self.limiter.state.lock().unwrap().in_flight -= 1;
+        self.limiter.released.notify_one();
+    }
+}
+
+impl AdaptiveConcurrencyLimiter {
+    /// Create a new limiter
+    ///
+    /// # Arguments
+    /// * `min` - Lowest allowed concurrency (at least 1)
+    /// * `max` - Highest allowed concurrency
+    pub fn new(min: usize, max: usize) -> Self {
+        let min = min.max(1);
+        let max = max.max(min);
+        Self {
+            min,
+            max,
+            state: Mutex::new(LimiterState {
+                limit: max as f64,
+                in_flight: 0,
+                avg_latency_ms: None,
+            }),
+            released: Notify::new(),
+        }
+    }
+
+    /// Get the current concurrency limit
+    pub fn limit(&self) -> usize {
+        self.state.lock().unwrap().limit.floor() as usize
+    }
+
+    /// Get the number of requests currently in flight
+    pub fn in_flight(&self) -> usize {
+        self.state.lock().unwrap().in_flight
+    }
+
+    /// Wait for a free slot under the current limit
+    pub async fn acquire(&self) -> ConcurrencyPermit<'_> {
+        loop {
+            let released = self.released.notified();
+            {
+                let mut state = self.state.lock().unwrap();
+                if state.in_flight < state.limit.floor() as usize {
+                    state.in_flight += 1;
+                    return ConcurrencyPermit { limiter: self };
+                }
+            }
+            released.await;
+        }
+    }
+
+    /// Feed the outcome of a request into the limit
+    ///
+    /// `success` should be false only for errors that indicate overload,
+    /// such as timeouts and 5xx responses.
+    pub fn record(&self, success: bool, latency: Duration) {
+        let latency_ms = latency.as_secs_f64() * 1000.0;
+        let mut state = self.state.lock().unwrap();
+
+        let spike = state
+            .avg_latency_ms
+            .is_some_and(|avg| latency_ms > avg * LATENCY_SPIKE_FACTOR);
+        state.avg_latency_ms = Some(match state.avg_latency_ms {
+            Some(avg) => avg + (latency_ms - avg) * LATENCY_EWMA_WEIGHT,
+            None => latency_ms,
+        });
+
+        let limit = if success && !spike {
+            state.limit + 1.0 / state.limit
+        } else {
+            state.limit * DECREASE_FACTOR
+        };
+        state.limit = limit.clamp(self.min as f64, self.max as f64);
+        drop(state);
+
+        // A higher limit may let waiting requests proceed
+        self.released.notify_waiters();
+    }
+}
@@ -3,11 +3,38 @@
 
 //! SSE (Server-Sent Events) connection manager for SecureNotify SDK
 
-use std::sync::Arc;
-use tokio::sync::mpsc;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, watch, Notify};
 use tokio::time::Duration;
 use futures::StreamExt;
-use crate::{SecureNotifyError, Result, SseEvent};
+use futures::future::BoxFuture;
+use rand::Rng;
+use rand::rngs::OsRng;
+use crate::{SecureNotifyError, Result, SseEvent, SseEventType};
+use crate::types::error::ErrorSource;
+use crate::utils::tls;
+
+/// Token provider callback, resolving to the credential for the next connect attempt
+pub type TokenProviderFn = dyn Fn() -> BoxFuture<'static, Result<String>> + Send + Sync;
+
+/// Source of short-lived credentials consulted on every SSE connect attempt
+#[derive(Clone)]
+pub struct TokenProvider(pub Arc<TokenProviderFn>);
+
+impl std::fmt::Debug for TokenProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TokenProvider")
+    }
+}
+
+impl TokenProvider {
+    /// Fetch a fresh token
+    pub async fn token(&self) -> Result<String> {
+        (self.0)().await
+    }
+}
 
 /// Configuration for SSE connection
 #[derive(Debug, Clone)]
@@ -16,14 +43,46 @@ pub struct SseConfig {
     pub url: String,
     /// API key for authentication
     pub api_key: String,
-    /// Heartbeat interval (default: 30 seconds)
+    /// Expected server heartbeat interval; a stream silent for twice this
+    /// long is treated as dead and reconnected (default: 30 seconds, zero disables)
     pub heartbeat_interval: Duration,
     /// Reconnect delay on disconnect (default: 1 second)
     pub reconnect_delay: Duration,
     /// Maximum reconnect attempts (default: 10)
     pub max_reconnect_attempts: u32,
+    /// Retries of the first connect, before the stream has ever been established
+    /// (default: none, counted against `max_reconnect_attempts`)
+    pub initial_connect_retries: Option<u32>,
     /// Connection timeout (default: 30 seconds)
     pub connection_timeout: Duration,
+    /// Event types forwarded to the receiver (default: all)
+    pub event_filter: Option<Vec<SseEventType>>,
+    /// Whether to add random jitter to reconnect delays (default: false)
+    pub reconnect_jitter: bool,
+    /// Server-side filter expression, e.g. `priority>=75` (default: none)
+    pub filter: Option<String>,
+    /// SHA-256 fingerprint the server's leaf certificate must match (default: none)
+    pub pinned_certificate: Option<[u8; 32]>,
+    /// Extra DER-encoded root certificates to trust (default: none)
+    pub root_certificates: Vec<Vec<u8>>,
+    /// Unsubscribe after this long without a message (default: never)
+    pub idle_unsubscribe_after: Option<Duration>,
+    /// Whether heartbeats count as activity for the idle timer (default: false)
+    pub heartbeat_resets_idle: bool,
+    /// Provider of a fresh credential per connect attempt, replacing `api_key` (default: none)
+    pub token_provider: Option<TokenProvider>,
+    /// Whether `:` comment lines are delivered as `SseMessage::Comment` (default: false)
+    pub emit_comments: bool,
+    /// Whether to request a gzip-compressed stream (default: true)
+    pub compression: bool,
+    /// Response statuses that end the connection instead of reconnecting (default: none)
+    pub fail_fast_statuses: Vec<u16>,
+    /// What happens to events that arrive while paused (default: buffer 100)
+    pub pause_policy: PausePolicy,
+    /// Messages the receiver channel holds before the stream waits for the consumer (default: 100)
+    pub channel_buffer: usize,
+    /// What happens to messages when the receiver channel is full (default: block)
+    pub overflow_policy: OverflowPolicy,
 }
 
 impl Default for SseConfig {
@@ -34,7 +93,22 @@ impl Default for SseConfig {
             heartbeat_interval: Duration::from_secs(30),
             reconnect_delay: Duration::from_secs(1),
             max_reconnect_attempts: 10,
+            initial_connect_retries: None,
             connection_timeout: Duration::from_secs(30),
+            event_filter: None,
+            reconnect_jitter: false,
+            filter: None,
+            pinned_certificate: None,
+            root_certificates: Vec::new(),
+            idle_unsubscribe_after: None,
+            heartbeat_resets_idle: false,
+            token_provider: None,
+            emit_comments: false,
+            compression: true,
+            fail_fast_statuses: Vec::new(),
+            pause_policy: PausePolicy::default(),
+            channel_buffer: 100,
+            overflow_policy: OverflowPolicy::default(),
         }
     }
 }
@@ -49,7 +123,11 @@ impl SseConfig {
         }
     }
 
-    /// Set the heartbeat interval
+    /// Set the expected server heartbeat interval
+    ///
+    /// If no data arrives for twice this interval, the connection is
+    /// assumed half-open and is dropped and reconnected. Zero disables the
+    /// check.
     pub fn with_heartbeat_interval(mut self, interval: Duration) -> Self {
         self.heartbeat_interval = interval;
         self
@@ -67,13 +145,174 @@ impl SseConfig {
         self
     }
 
+    /// Retry the first connect up to `retries` times, with its own budget
+    ///
+    /// Until the stream has been established once, failed connects count
+    /// against this budget instead of `max_reconnect_attempts`, which then
+    /// applies in full to later drops.
+    pub fn with_initial_connect_retries(mut self, retries: u32) -> Self {
+        self.initial_connect_retries = Some(retries);
+        self
+    }
+
+    /// Set the connection timeout
+    pub fn with_connection_timeout(mut self, timeout: Duration) -> Self {
+        self.connection_timeout = timeout;
+        self
+    }
+
+    /// Enable or disable random jitter on reconnect delays
+    pub fn with_reconnect_jitter(mut self, jitter: bool) -> Self {
+        self.reconnect_jitter = jitter;
+        self
+    }
+
+    /// Only forward messages whose event type is in the given set
+    pub fn with_event_filter(mut self, event_types: &[SseEventType]) -> Self {
+        self.event_filter = Some(event_types.to_vec());
+        self
+    }
+
+    /// Ask the server to only send messages matching a filter expression
+    pub fn with_filter(mut self, filter: impl Into<String>) -> Self {
+        self.filter = Some(filter.into());
+        self
+    }
+
+    /// Only accept a server whose leaf certificate has this SHA-256 fingerprint
+    #[cfg(feature = "cert-pinning")]
+    pub fn with_pinned_certificate(mut self, sha256: [u8; 32]) -> Self {
+        self.pinned_certificate = Some(sha256);
+        self
+    }
+
+    /// Trust a DER-encoded root certificate in addition to the default roots
+    pub fn with_root_certificate(mut self, der: Vec<u8>) -> Self {
+        self.root_certificates.push(der);
+        self
+    }
+
+    /// Unsubscribe automatically when no message arrives within `idle`
+    ///
+    /// When the window elapses the client sends `DELETE` to the subscription
+    /// URL, emits a final `SseMessage::Disconnected(DisconnectReason::IdleTimeout)`
+    /// and does not reconnect.
+    pub fn with_idle_unsubscribe_after(mut self, idle: Option<Duration>) -> Self {
+        self.idle_unsubscribe_after = idle;
+        self
+    }
+
+    /// Choose whether heartbeats reset the idle timer
+    pub fn with_heartbeat_resets_idle(mut self, resets: bool) -> Self {
+        self.heartbeat_resets_idle = resets;
+        self
+    }
+
+    /// Fetch the credential from `provider` on every connect attempt
+    ///
+    /// Use this when the server authenticates the stream with a short-lived
+    /// token: each reconnect asks the provider again instead of reusing the
+    /// static `api_key`. A provider error counts as a failed connect attempt.
+    pub fn with_token_provider(mut self, provider: Arc<TokenProviderFn>) -> Self {
+        self.token_provider = Some(TokenProvider(provider));
+        self
+    }
+
+    /// Deliver `:` comment lines, such as `: server-time=...`, to the receiver
+    pub fn with_emit_comments(mut self, emit: bool) -> Self {
+        self.emit_comments = emit;
+        self
+    }
+
+    /// Request a gzip-compressed stream
+    ///
+    /// When enabled, connections send `Accept-Encoding: gzip` and a stream
+    /// the server sends with `Content-Encoding: gzip` is decompressed before
+    /// parsing. Servers that do not compress are unaffected.
+    pub fn with_compression(mut self, enabled: bool) -> Self {
+        self.compression = enabled;
+        self
+    }
+
+    /// Stop instead of reconnecting when the server answers with one of these statuses
+    ///
+    /// Useful for statuses that will not change on retry, such as 401, 403
+    /// or 404. The error is delivered to the receiver, the state becomes
+    /// `Failed` and no reconnect is attempted. 400 also behaves this way
+    /// when a server-side filter is set, as it means the filter was rejected.
+    pub fn fail_fast_on_status(mut self, statuses: Vec<u16>) -> Self {
+        self.fail_fast_statuses = statuses;
+        self
+    }
+
+    /// Choose what happens to events that arrive while the connection is paused
+    pub fn with_pause_policy(mut self, policy: PausePolicy) -> Self {
+        self.pause_policy = policy;
+        self
+    }
+
+    /// Set how many messages the receiver channel holds
+    ///
+    /// When the buffer is full, the connection waits for the consumer
+    /// before reading more from the network, so messages are never dropped;
+    /// the server sees the backpressure through TCP flow control. Time
+    /// spent waiting does not count towards the heartbeat watchdog. Values
+    /// below 1 are treated as 1.
+    pub fn with_channel_buffer(mut self, size: usize) -> Self {
+        self.channel_buffer = size;
+        self
+    }
+
+    /// Choose what happens to messages when the receiver channel is full
+    ///
+    /// Dropping keeps the stream read at network speed, so a slow consumer
+    /// sees recent events instead of stale ones. Only events, heartbeats and
+    /// comments are dropped; connection lifecycle messages always arrive.
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow_policy = policy;
+        self
+    }
+
+    /// Get the credential for the next connect attempt
+    pub async fn current_api_key(&self) -> Result<String> {
+        match &self.token_provider {
+            Some(provider) => provider.token().await,
+            None => Ok(self.api_key.clone()),
+        }
+    }
+
+    /// Check whether a message counts as activity for the idle timer
+    fn resets_idle(&self, message: &SseMessage) -> bool {
+        match message {
+            SseMessage::Heartbeat | SseMessage::Comment(_) => self.heartbeat_resets_idle,
+            _ => true,
+        }
+    }
+
+    /// Check whether a message passes the event filter
+    pub fn accepts(&self, message: &SseMessage) -> bool {
+        match &self.event_filter {
+            Some(allowed) => allowed.contains(&message.event_type()),
+            None => true,
+        }
+    }
+
     /// Build the URL with query parameters
     pub fn build_url(&self) -> Result<String> {
+        self.build_url_with_key(&self.api_key)
+    }
+
+    /// Build the URL with query parameters, authenticating with `api_key`
+    pub fn build_url_with_key(&self, api_key: &str) -> Result<String> {
         let mut url = url::Url::parse(&self.url)
-            .map_err(|e| SecureNotifyError::ConnectionError(format!("Invalid SSE URL: {}", e)))?;
-        if !self.api_key.is_empty() {
+            .map_err(|e| SecureNotifyError::ConnectionError(format!("Invalid SSE URL: {}", e), Some(ErrorSource::new(e))))?;
+        if !api_key.is_empty() {
             url.query_pairs_mut()
-                .append_pair("api_key", &self.api_key);
+                .append_pair("api_key", api_key);
+        }
+        if let Some(filter) = &self.filter {
+            url.query_pairs_mut()
+                .append_pair("filter", filter);
         }
         Ok(url.to_string())
     }
@@ -88,10 +327,308 @@ pub enum SseMessage {
     Heartbeat,
     /// Connection opened
     Connected,
-    /// Connection closed
-    Disconnected,
-    /// Error occurred
-    Error(SecureNotifyError),
+    /// Connection closed for good; no further messages follow
+    Disconnected(DisconnectReason),
+    /// The server is draining this connection; a reconnect to another backend follows
+    Draining,
+    /// Comment line, without the leading `:` (only with `emit_comments`)
+    Comment(String),
+    /// Error occurred, with the reconnect that follows it, if any
+    Error(SecureNotifyError, Option<ReconnectAttempt>),
+}
+
+/// Progress of the reconnect that follows an SSE error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconnectAttempt {
+    /// Attempt number, counting from 1 since the last successful connect
+    pub attempt: u32,
+    /// Attempts allowed before the connection gives up
+    pub max_attempts: u32,
+}
+
+impl SseMessage {
+    /// Get the event type of this message
+    pub fn event_type(&self) -> SseEventType {
+        match self {
+            Self::Event(event) => event.event_type.clone(),
+            Self::Heartbeat => SseEventType::Heartbeat,
+            Self::Connected => SseEventType::Connected,
+            Self::Disconnected(_) => SseEventType::Disconnected,
+            Self::Draining => SseEventType::Draining,
+            Self::Comment(_) => SseEventType::Comment,
+            Self::Error(..) => SseEventType::Error,
+        }
+    }
+}
+
+/// Why an SSE connection stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisconnectReason {
+    /// `SseConnection::disconnect` was called
+    ClientRequested,
+    /// The server ended the stream cleanly
+    ServerClosed,
+    /// Reconnecting failed `max_reconnect_attempts` times in a row
+    MaxRetriesExceeded,
+    /// No message arrived within the idle unsubscribe window
+    IdleTimeout,
+    /// An error that retrying cannot fix, such as an invalid URL or a fail-fast status
+    Error,
+}
+
+/// What a paused connection does with events that arrive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PausePolicy {
+    /// Hold up to this many events for delivery on resume, dropping the oldest beyond it
+    Buffer(usize),
+    /// Discard events until resumed
+    Drop,
+}
+
+impl Default for PausePolicy {
+    fn default() -> Self {
+        Self::Buffer(100)
+    }
+}
+
+/// What a connection does with messages the receiver has no room for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Wait for the consumer, pausing reads from the network
+    #[default]
+    Block,
+    /// Discard the oldest undelivered message to make room
+    DropOldest,
+    /// Discard the incoming message
+    DropNewest,
+}
+
+impl SseMessage {
+    /// Check whether an overflow policy may discard the message
+    fn is_droppable(&self) -> bool {
+        matches!(self, Self::Event(_) | Self::Heartbeat | Self::Comment(_))
+    }
+}
+
+/// Undelivered messages under `OverflowPolicy::DropOldest`
+///
+/// A forwarder task moves them into the channel as the consumer makes
+/// room, so the oldest can still be discarded while they wait.
+#[derive(Debug)]
+struct Backlog {
+    queue: Mutex<BacklogQueue>,
+    capacity: usize,
+    ready: Notify,
+}
+
+#[derive(Debug, Default)]
+struct BacklogQueue {
+    messages: VecDeque<SseMessage>,
+    /// Set when the sink is dropped; the forwarder exits once the queue is empty
+    closed: bool,
+}
+
+impl Backlog {
+    /// Queue a message, returning whether an older one was discarded for it
+    fn push(&self, message: SseMessage) -> bool {
+        let mut queue = self.queue.lock().unwrap();
+        let mut dropped = false;
+        if queue.messages.len() >= self.capacity {
+            if let Some(oldest) = queue.messages.iter().position(SseMessage::is_droppable) {
+                queue.messages.remove(oldest);
+                dropped = true;
+            }
+        }
+        queue.messages.push_back(message);
+        drop(queue);
+        self.ready.notify_one();
+        dropped
+    }
+
+    fn close(&self) {
+        self.queue.lock().unwrap().closed = true;
+        self.ready.notify_one();
+    }
+
+    /// Move queued messages into the channel until the sink is dropped
+    async fn forward(self: Arc<Self>, tx: mpsc::Sender<SseMessage>) {
+        // Reserving first means the message sent is the oldest once there is room
+        while let Ok(permit) = tx.reserve().await {
+            let message = loop {
+                {
+                    let mut queue = self.queue.lock().unwrap();
+                    if let Some(message) = queue.messages.pop_front() {
+                        break message;
+                    }
+                    if queue.closed {
+                        return;
+                    }
+                }
+                self.ready.notified().await;
+            };
+            permit.send(message);
+        }
+    }
+}
+
+/// Messages held back while a connection is paused
+#[derive(Debug, Default)]
+struct Paused {
+    held: VecDeque<SseMessage>,
+    events: usize,
+}
+
+/// Delivers messages to the receiver, holding them back while paused
+///
+/// Events are kept or dropped per the `PausePolicy`. Heartbeats and
+/// comments are discarded, while connection lifecycle messages are always
+/// kept so they are delivered in order on resume.
+#[derive(Debug)]
+pub(crate) struct MessageSink {
+    tx: mpsc::Sender<SseMessage>,
+    policy: PausePolicy,
+    paused: Mutex<Option<Paused>>,
+    overflow: OverflowPolicy,
+    backlog: Option<Arc<Backlog>>,
+    dropped: AtomicU64,
+}
+
+impl MessageSink {
+    pub(crate) fn new(tx: mpsc::Sender<SseMessage>, policy: PausePolicy) -> Self {
+        Self {
+            tx,
+            policy,
+            paused: Mutex::new(None),
+            overflow: OverflowPolicy::Block,
+            backlog: None,
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Apply an overflow policy, keeping up to `capacity` undelivered messages
+    ///
+    /// With `DropOldest` the messages wait in a backlog of that size, so the
+    /// channel itself should hold a single message.
+    pub(crate) fn with_overflow(mut self, overflow: OverflowPolicy, capacity: usize) -> Self {
+        if overflow == OverflowPolicy::DropOldest {
+            let backlog = Arc::new(Backlog {
+                queue: Mutex::new(BacklogQueue::default()),
+                capacity: capacity.max(1),
+                ready: Notify::new(),
+            });
+            tokio::spawn(backlog.clone().forward(self.tx.clone()));
+            self.backlog = Some(backlog);
+        }
+        self.overflow = overflow;
+        self
+    }
+
+    pub(crate) async fn send(&self, message: SseMessage) {
+        if let Some(paused) = self.paused.lock().unwrap().as_mut() {
+            self.hold(paused, message);
+            return;
+        }
+        self.deliver(message).await;
+    }
+
+    /// Hand a message to the receiver according to the overflow policy
+    async fn deliver(&self, message: SseMessage) {
+        match (self.overflow, &self.backlog) {
+            (OverflowPolicy::DropOldest, Some(backlog)) => {
+                if backlog.push(message) {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            (OverflowPolicy::DropNewest, _) => match self.tx.try_send(message) {
+                Err(mpsc::error::TrySendError::Full(message)) if message.is_droppable() => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+                Err(mpsc::error::TrySendError::Full(message)) => {
+                    let _ = self.tx.send(message).await;
+                }
+                _ => {}
+            },
+            _ => {
+                let _ = self.tx.send(message).await;
+            }
+        }
+    }
+
+    /// Hand a message to the receiver without waiting, even while paused
+    ///
+    /// For a final message that must neither block on a full channel nor
+    /// sit in the pause buffer. A message that finds no room is dropped
+    /// without counting towards `dropped_count`, which only tracks the
+    /// overflow policy.
+    pub(crate) fn try_deliver(&self, message: SseMessage) {
+        match &self.backlog {
+            Some(backlog) => {
+                if backlog.push(message) {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            None => {
+                let _ = self.tx.try_send(message);
+            }
+        }
+    }
+
+    /// Number of messages discarded by the overflow policy
+    pub(crate) fn dropped_count(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    fn hold(&self, paused: &mut Paused, message: SseMessage) {
+        match (&message, self.policy) {
+            (SseMessage::Heartbeat | SseMessage::Comment(_), _) => {}
+            (SseMessage::Event(_), PausePolicy::Drop) => {}
+            (SseMessage::Event(_), PausePolicy::Buffer(limit)) => {
+                if paused.events >= limit {
+                    let Some(oldest) = paused.held.iter().position(|m| matches!(m, SseMessage::Event(_))) else {
+                        return;
+                    };
+                    paused.held.remove(oldest);
+                    paused.events -= 1;
+                }
+                paused.held.push_back(message);
+                paused.events += 1;
+            }
+            _ => paused.held.push_back(message),
+        }
+    }
+
+    pub(crate) fn pause(&self) {
+        self.paused.lock().unwrap().get_or_insert_with(Paused::default);
+    }
+
+    pub(crate) async fn resume(&self) {
+        loop {
+            // Messages arriving mid-flush are still held, keeping the order intact
+            let next = {
+                let mut paused = self.paused.lock().unwrap();
+                match paused.as_mut().and_then(|p| p.held.pop_front()) {
+                    Some(message) => message,
+                    None => {
+                        *paused = None;
+                        return;
+                    }
+                }
+            };
+            self.deliver(next).await;
+        }
+    }
+
+    pub(crate) fn is_paused(&self) -> bool {
+        self.paused.lock().unwrap().is_some()
+    }
+}
+
+impl Drop for MessageSink {
+    fn drop(&mut self) {
+        if let Some(backlog) = &self.backlog {
+            backlog.close();
+        }
+    }
 }
 
 /// SSE connection state
@@ -104,57 +641,369 @@ pub enum SseState {
     Failed,
 }
 
+/// Line-oriented parser for an SSE stream
+///
+/// Parser state that must survive reconnects (such as the server-sent
+/// `retry:` interval) is kept here so one parser can be reused per subscription.
+#[derive(Debug)]
+pub(crate) struct SseParser {
+    event_name: Option<String>,
+    data: Vec<String>,
+    id: Option<String>,
+    saw_comment: bool,
+    retry: Option<Duration>,
+    emit_comments: bool,
+    /// Bytes of the line not yet terminated
+    partial: Vec<u8>,
+    /// The last line ended in `\r`, so a following `\n` completes that CRLF
+    skip_lf: bool,
+}
+
+impl SseParser {
+    /// Create a new parser
+    pub(crate) fn new() -> Self {
+        Self {
+            event_name: None,
+            data: Vec::new(),
+            id: None,
+            saw_comment: false,
+            retry: None,
+            emit_comments: false,
+            partial: Vec::new(),
+            skip_lf: false,
+        }
+    }
+
+    /// Report comment lines as `SseMessage::Comment` instead of ignoring them
+    pub(crate) fn with_comments(mut self, emit: bool) -> Self {
+        self.emit_comments = emit;
+        self
+    }
+
+    /// Reset per-connection state before processing a new stream
+    pub(crate) fn reset(&mut self) {
+        self.event_name = None;
+        self.data.clear();
+        self.id = None;
+        self.saw_comment = false;
+        self.partial.clear();
+        self.skip_lf = false;
+    }
+
+    /// Reconnect delay requested by the server via the `retry:` field
+    pub(crate) fn retry(&self) -> Option<Duration> {
+        self.retry
+    }
+
+    /// Process a chunk of the stream, returning the messages it completes
+    ///
+    /// Lines may end in `\r\n`, `\r` or `\n`, and chunks may split a line
+    /// anywhere, including between the `\r` and `\n` of a CRLF or inside a
+    /// multi-byte character. Lines are decoded as UTF-8 once complete.
+    pub(crate) fn feed(&mut self, chunk: &[u8]) -> Vec<SseMessage> {
+        let mut messages = Vec::new();
+        for &byte in chunk {
+            if std::mem::take(&mut self.skip_lf) && byte == b'\n' {
+                continue;
+            }
+            match byte {
+                b'\r' | b'\n' => {
+                    self.skip_lf = byte == b'\r';
+                    let line = std::mem::take(&mut self.partial);
+                    if let Some(message) = self.process_line(&String::from_utf8_lossy(&line)) {
+                        messages.push(message);
+                    }
+                }
+                _ => self.partial.push(byte),
+            }
+        }
+        messages
+    }
+
+    /// Process a single line, returning a message to dispatch if any
+    pub(crate) fn process_line(&mut self, line: &str) -> Option<SseMessage> {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        if let Some(comment) = line.strip_prefix(':') {
+            self.saw_comment = true;
+            if self.emit_comments {
+                return Some(SseMessage::Comment(comment.trim_start().to_string()));
+            }
+            return None;
+        }
+
+        // Per the SSE spec, a single space after the colon is not part of the value
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "event" => self.event_name = Some(value.to_string()),
+            "data" => self.data.push(value.to_string()),
+            "id" if !value.contains('\0') => self.id = Some(value.to_string()),
+            // Values that are not plain digits are ignored
+            "retry" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+                if let Ok(ms) = value.parse::<u64>() {
+                    self.retry = Some(Duration::from_millis(ms));
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Turn the fields collected since the last blank line into a message
+    ///
+    /// Data lines are joined with `\n`, without a trailing newline. An event
+    /// with a name other than `message` is dispatched even without data.
+    fn dispatch(&mut self) -> Option<SseMessage> {
+        let name = self.event_name.take().filter(|name| !name.is_empty());
+        let data = std::mem::take(&mut self.data);
+        let saw_comment = std::mem::take(&mut self.saw_comment);
+        // The last event ID persists until the server sends a new one
+        let id = self.id.clone();
+
+        match name.as_deref() {
+            Some("drain") => return Some(SseMessage::Draining),
+            Some("heartbeat") => return Some(SseMessage::Heartbeat),
+            _ => {}
+        }
+        let named = name.as_deref().is_some_and(|name| name != "message");
+        if !data.is_empty() || named {
+            let event_type = SseEventType::from_name(name.as_deref().unwrap_or("message"));
+            return Some(SseMessage::Event(SseEvent::new(event_type, data.join("\n"), id, name)));
+        }
+        // A comment-only block such as `:ping` is a keep-alive
+        saw_comment.then_some(SseMessage::Heartbeat)
+    }
+}
+
+/// Heartbeats kept for computing connection quality
+const QUALITY_WINDOW: usize = 20;
+
+/// Liveliness of an SSE connection, for display such as a signal indicator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ConnectionQuality {
+    /// Mean time between recent heartbeats (`None` until two have arrived)
+    pub avg_heartbeat_gap: Option<Duration>,
+    /// Mean deviation of recent heartbeat gaps from their average
+    pub jitter: Option<Duration>,
+    /// Time since the last message of any kind (`None` if none has arrived)
+    pub last_message_age: Option<Duration>,
+}
+
+/// Records message arrival times for [`ConnectionQuality`]
+#[derive(Debug, Default)]
+pub(crate) struct QualitySampler {
+    heartbeats: std::sync::Mutex<std::collections::VecDeque<tokio::time::Instant>>,
+    last_message: std::sync::Mutex<Option<tokio::time::Instant>>,
+    reconnects: std::sync::atomic::AtomicU32,
+}
+
+impl QualitySampler {
+    /// Note the arrival of a message
+    pub(crate) fn record(&self, message: &SseMessage) {
+        let now = tokio::time::Instant::now();
+        *self.last_message.lock().unwrap() = Some(now);
+        if matches!(message, SseMessage::Heartbeat) {
+            let mut heartbeats = self.heartbeats.lock().unwrap();
+            if heartbeats.len() == QUALITY_WINDOW {
+                heartbeats.pop_front();
+            }
+            heartbeats.push_back(now);
+        }
+    }
+
+    /// Note that the connection is being re-established
+    pub(crate) fn record_reconnect(&self) {
+        self.reconnects.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Forget heartbeats from a previous connection, so gaps never span a reconnect
+    pub(crate) fn reset_heartbeats(&self) {
+        self.heartbeats.lock().unwrap().clear();
+    }
+
+    /// Compute the current quality figures
+    pub(crate) fn quality(&self) -> ConnectionQuality {
+        let heartbeats = self.heartbeats.lock().unwrap();
+        let gaps: Vec<Duration> = heartbeats
+            .iter()
+            .zip(heartbeats.iter().skip(1))
+            .map(|(earlier, later)| *later - *earlier)
+            .collect();
+        let (avg_heartbeat_gap, jitter) = if gaps.is_empty() {
+            (None, None)
+        } else {
+            let avg = gaps.iter().sum::<Duration>() / gaps.len() as u32;
+            let deviation: Duration = gaps
+                .iter()
+                .map(|gap| gap.abs_diff(avg))
+                .sum();
+            (Some(avg), Some(deviation / gaps.len() as u32))
+        };
+
+        ConnectionQuality {
+            avg_heartbeat_gap,
+            jitter,
+            last_message_age: self.last_message.lock().unwrap().map(|at| at.elapsed()),
+        }
+    }
+}
+
+/// Local view of a subscription's connection health
+///
+/// Unlike `SubscriptionInfo` from the server, this reflects what the
+/// client itself observes on the stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriptionHealth {
+    /// Current state of the connection
+    pub state: SseState,
+    /// Time since the last message of any kind arrived (`None` if none has)
+    pub last_message_age: Option<Duration>,
+    /// Reconnects since the subscription was opened, including after drains
+    pub reconnects: u32,
+}
+
+/// Shared view of a connection's state and activity, for health queries
+#[derive(Debug, Clone)]
+pub(crate) struct HealthProbe {
+    state: Arc<tokio::sync::RwLock<SseState>>,
+    quality: Arc<QualitySampler>,
+}
+
+impl HealthProbe {
+    /// Read the current health of the connection
+    pub(crate) async fn health(&self) -> SubscriptionHealth {
+        SubscriptionHealth {
+            state: self.state.read().await.clone(),
+            last_message_age: self
+                .quality
+                .last_message
+                .lock()
+                .unwrap()
+                .map(|at| at.elapsed()),
+            reconnects: self.quality.reconnects.load(std::sync::atomic::Ordering::Relaxed),
+        }
+    }
+}
+
+/// How a stream that ended without an error was closed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StreamEnd {
+    /// The server closed the stream
+    Closed,
+    /// No message arrived within the idle window
+    Idle,
+    /// The server sent `event: drain` ahead of shutting the backend down
+    Drained,
+}
+
+/// Marks the connection task as ended when dropped
+struct StopSignal(watch::Sender<bool>);
+
+impl Drop for StopSignal {
+    fn drop(&mut self) {
+        self.0.send_replace(true);
+    }
+}
+
 /// SSE connection manager
 #[derive(Clone)]
 pub struct SseConnection {
     _config: SseConfig,
     state: Arc<tokio::sync::RwLock<SseState>>,
-    _message_tx: mpsc::Sender<SseMessage>,
-    _handle: Arc<tokio::task::JoinHandle<()>>,
+    quality: Arc<QualitySampler>,
+    sink: Arc<MessageSink>,
+    last_event_id: Arc<tokio::sync::RwLock<Option<String>>>,
+    reconnect_attempts: Arc<AtomicU32>,
+    abort: tokio::task::AbortHandle,
+    /// Becomes `true` when the connection task ends, however it ends
+    stopped: watch::Receiver<bool>,
+    /// Set by the `disconnect` call that emits the final message
+    disconnected: Arc<AtomicBool>,
 }
 
 impl SseConnection {
     /// Create a new SSE connection
     pub fn new(config: SseConfig) -> (Self, mpsc::Receiver<SseMessage>) {
-        let (message_tx, message_rx) = mpsc::channel(100);
+        let capacity = config.channel_buffer.max(1);
+        let (message_tx, message_rx) = match config.overflow_policy {
+            // The sink's backlog holds the messages; the channel only hands them over
+            OverflowPolicy::DropOldest => mpsc::channel(1),
+            _ => mpsc::channel(capacity),
+        };
         let state = Arc::new(tokio::sync::RwLock::new(SseState::Disconnected));
         let config_clone = config.clone();
         let state_clone = state.clone();
-        let message_tx_clone = message_tx.clone();
+        let sink = Arc::new(
+            MessageSink::new(message_tx, config.pause_policy).with_overflow(config.overflow_policy, capacity),
+        );
+        let sink_clone = sink.clone();
+        let quality = Arc::new(QualitySampler::default());
+        let quality_clone = quality.clone();
+        let last_event_id = Arc::new(tokio::sync::RwLock::new(None));
+        let last_event_id_clone = last_event_id.clone();
+        let reconnect_attempts = Arc::new(AtomicU32::new(0));
+        let reconnect_attempts_clone = reconnect_attempts.clone();
+        let (stopped_tx, stopped) = watch::channel(false);
 
         let handle = tokio::spawn(async move {
-            Self::run_connection(&config_clone, &message_tx_clone, &state_clone).await;
+            // Signals the end on drop, so an aborted task reports it too
+            let _stopped = StopSignal(stopped_tx);
+            Self::run_connection(
+                &config_clone,
+                &sink_clone,
+                &state_clone,
+                &quality_clone,
+                &last_event_id_clone,
+                &reconnect_attempts_clone,
+            )
+            .await;
         });
 
         (
             Self {
                 _config: config,
                 state,
-                _message_tx: message_tx,
-                _handle: Arc::new(handle),
+                quality,
+                sink,
+                last_event_id,
+                reconnect_attempts,
+                abort: handle.abort_handle(),
+                stopped,
+                disconnected: Arc::new(AtomicBool::new(false)),
             },
             message_rx,
         )
     }
 
     /// Run the connection loop
+    ///
+    /// Each reconnect sends the ID of the last event received as
+    /// `Last-Event-ID`, so the server can resume the stream after it.
     async fn run_connection(
         config: &SseConfig,
-        message_tx: &mpsc::Sender<SseMessage>,
+        message_tx: &MessageSink,
         state: &tokio::sync::RwLock<SseState>,
+        quality: &QualitySampler,
+        last_event_id: &tokio::sync::RwLock<Option<String>>,
+        reconnect_attempts: &AtomicU32,
     ) {
-        let mut reconnect_attempts = 0u32;
-        let url = match config.build_url() {
-            Ok(url) => url,
-            Err(e) => {
-                let _ = message_tx.send(SseMessage::Error(e)).await;
-                {
-                    let mut state_guard = state.write().await;
-                    *state_guard = SseState::Failed;
-                }
-                return;
+        let mut established = false;
+        let mut parser = SseParser::new().with_comments(config.emit_comments);
+        // Reject an invalid URL up front rather than retrying it
+        if let Err(e) = config.build_url() {
+            Self::dispatch(config, message_tx, SseMessage::Error(e, None)).await;
+            {
+                let mut state_guard = state.write().await;
+                *state_guard = SseState::Failed;
             }
-        };
+            Self::dispatch(config, message_tx, SseMessage::Disconnected(DisconnectReason::Error)).await;
+            return;
+        }
 
         loop {
             {
@@ -162,115 +1011,282 @@ impl SseConnection {
                 *state_guard = SseState::Connecting;
             }
 
-            let result = Self::connect_and_process(config, &url, message_tx).await;
+            // Resolve the credential per attempt so a token provider can refresh it
+            let result = match config.current_api_key().await.and_then(|key| config.build_url_with_key(&key)) {
+                Ok(url) => {
+                    Self::connect_and_process(
+                        config,
+                        &url,
+                        message_tx,
+                        &mut parser,
+                        state,
+                        quality,
+                        last_event_id,
+                        reconnect_attempts,
+                    )
+                    .await
+                }
+                Err(e) => Err(e),
+            };
+
+            // The first establishment ends the initial-connect budget
+            if !established && *state.read().await == SseState::Connected {
+                established = true;
+            }
+            let max_attempts = match config.initial_connect_retries {
+                Some(retries) if !established => retries,
+                _ => config.max_reconnect_attempts,
+            };
 
             match result {
-                Ok(()) => {
+                Ok(StreamEnd::Closed) => {
                     // Normal disconnect
-                    let _ = message_tx.send(SseMessage::Disconnected).await;
+                    Self::dispatch(config, message_tx, SseMessage::Disconnected(DisconnectReason::ServerClosed)).await;
+                    break;
+                }
+                Ok(StreamEnd::Drained) => {
+                    // A planned handover, not a failure: reconnect straight away
+                    reconnect_attempts.store(0, Ordering::Relaxed);
+                    quality.record_reconnect();
+                    let mut state_guard = state.write().await;
+                    *state_guard = SseState::Reconnecting;
+                }
+                Ok(StreamEnd::Idle) => {
+                    if let Err(e) = Self::unsubscribe(config).await {
+                        Self::dispatch(config, message_tx, SseMessage::Error(e, None)).await;
+                    }
+                    {
+                        let mut state_guard = state.write().await;
+                        *state_guard = SseState::Disconnected;
+                    }
+                    Self::dispatch(config, message_tx, SseMessage::Disconnected(DisconnectReason::IdleTimeout)).await;
                     break;
                 }
                 Err(error) => {
-                    let _ = message_tx.send(SseMessage::Error(error.clone())).await;
+                    // A rejected filter fails the same way on retry
+                    let rejected_filter = error.status() == 400 && config.filter.is_some();
+                    let fail_fast = rejected_filter || config.fail_fast_statuses.contains(&error.status());
 
-                    if reconnect_attempts >= config.max_reconnect_attempts {
-                        let _ = message_tx.send(SseMessage::Error(
+                    let reconnect = if fail_fast || reconnect_attempts.load(Ordering::Relaxed) >= max_attempts {
+                        None
+                    } else {
+                        {
+                            let mut state_guard = state.write().await;
+                            *state_guard = SseState::Reconnecting;
+                        }
+                        quality.record_reconnect();
+                        let attempt = reconnect_attempts.fetch_add(1, Ordering::Relaxed) + 1;
+                        Some(ReconnectAttempt { attempt, max_attempts })
+                    };
+                    Self::dispatch(config, message_tx, SseMessage::Error(error, reconnect)).await;
+
+                    if fail_fast {
+                        {
+                            let mut state_guard = state.write().await;
+                            *state_guard = SseState::Failed;
+                        }
+                        Self::dispatch(config, message_tx, SseMessage::Disconnected(DisconnectReason::Error)).await;
+                        break;
+                    }
+
+                    let Some(reconnect) = reconnect else {
+                        Self::dispatch(config, message_tx, SseMessage::Error(
                             SecureNotifyError::ConnectionError(
                                 "Max reconnect attempts reached".to_string(),
+                                None,
                             ),
+                            None,
                         ))
                         .await;
                         {
                             let mut state_guard = state.write().await;
                             *state_guard = SseState::Failed;
                         }
+                        Self::dispatch(
+                            config,
+                            message_tx,
+                            SseMessage::Disconnected(DisconnectReason::MaxRetriesExceeded),
+                        )
+                        .await;
                         break;
-                    }
+                    };
 
-                    {
-                        let mut state_guard = state.write().await;
-                        *state_guard = SseState::Reconnecting;
+                    // Backoff before reconnecting. Uses tokio time only, so tests
+                    // can drive it with `tokio::time::pause()` and `advance()`.
+                    let mut delay = Self::reconnect_delay(config, reconnect.attempt, parser.retry());
+                    if config.reconnect_jitter {
+                        let jitter_range = delay.as_millis() as f64 * 0.1;
+                        let jitter = OsRng.gen_range(0.0..=jitter_range);
+                        delay += Duration::from_millis(jitter as u64);
                     }
-                    reconnect_attempts += 1;
-
-                    // Backoff before reconnecting
-                    let delay = config.reconnect_delay.as_secs_f64()
-                        * 2.0f64.powf(reconnect_attempts as f64);
-                    let delay = Duration::from_secs_f64(delay).min(Duration::from_secs(60));
-
                     tokio::time::sleep(delay).await;
                 }
             }
         }
     }
 
+    /// Send a message to the receiver if it passes the event filter
+    async fn dispatch(
+        config: &SseConfig,
+        message_tx: &MessageSink,
+        message: SseMessage,
+    ) {
+        if config.accepts(&message) {
+            message_tx.send(message).await;
+        }
+    }
+
+    /// Delay before the given reconnect attempt, counting from 1
+    ///
+    /// The first reconnect waits the base delay, and each further attempt
+    /// doubles it. A server-sent `retry:` interval replaces
+    /// `config.reconnect_delay` as the base. It is capped at the connection
+    /// timeout so a misbehaving server cannot stall reconnects.
+    pub(crate) fn reconnect_delay(
+        config: &SseConfig,
+        attempt: u32,
+        server_retry: Option<Duration>,
+    ) -> Duration {
+        let base = match server_retry {
+            Some(retry) => retry.min(config.connection_timeout),
+            None => config.reconnect_delay,
+        };
+        let delay = base.as_secs_f64()
+            * 2.0f64.powf(attempt.saturating_sub(1) as f64);
+        Duration::from_secs_f64(delay).min(Duration::from_secs(60))
+    }
+
+    /// Build the HTTP client used for the stream
+    fn client(config: &SseConfig) -> Result<reqwest::Client> {
+        // With gzip enabled reqwest sends `Accept-Encoding: gzip` and decodes the body
+        let builder = reqwest::Client::builder()
+            .timeout(config.connection_timeout)
+            .gzip(config.compression);
+        Ok(tls::configure(builder, config.pinned_certificate, &config.root_certificates, false)?.build()?)
+    }
+
+    /// Cancel the subscription on the server
+    async fn unsubscribe(config: &SseConfig) -> Result<()> {
+        let api_key = config.current_api_key().await?;
+        let response = Self::client(config)?
+            .delete(&config.url)
+            .header("x-api-key", api_key)
+            .send()
+            .await
+            .map_err(tls::send_error)?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            return Err(SecureNotifyError::ApiError {
+                code: status.as_u16().to_string(),
+                message: format!("Idle unsubscribe failed with status: {}", status),
+                status: status.as_u16(),
+                details: None,
+            });
+        }
+        Ok(())
+    }
+
     /// Connect to SSE and process events
+    #[allow(clippy::too_many_arguments)]
     async fn connect_and_process(
         config: &SseConfig,
         url: &str,
-        message_tx: &mpsc::Sender<SseMessage>,
-    ) -> Result<()> {
-        let client = reqwest::Client::builder()
-            .timeout(config.connection_timeout)
-            .build()?;
+        message_tx: &MessageSink,
+        parser: &mut SseParser,
+        state: &tokio::sync::RwLock<SseState>,
+        quality: &QualitySampler,
+        last_event_id: &tokio::sync::RwLock<Option<String>>,
+        reconnect_attempts: &AtomicU32,
+    ) -> Result<StreamEnd> {
+        let client = Self::client(config)?;
     
-        let response = client
+        let mut request = client
             .get(url)
             .header("Accept", "text/event-stream")
-            .header("Cache-Control", "no-cache")
-            .send()
-            .await?;
+            .header("Cache-Control", "no-cache");
+        if let Some(id) = last_event_id.read().await.as_deref() {
+            request = request.header("Last-Event-ID", id);
+        }
+        let response = request.send().await.map_err(tls::send_error)?;
     
         if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            let message = if body.trim().is_empty() {
+                format!("SSE connection failed with status: {}", status)
+            } else {
+                format!("SSE connection failed with status: {}: {}", status, body.trim())
+            };
             return Err(SecureNotifyError::ApiError {
-                code: response.status().as_u16().to_string(),
-                message: format!("SSE connection failed with status: {}", response.status()),
-                status: response.status().as_u16(),
+                code: status.as_u16().to_string(),
+                message,
+                status: status.as_u16(),
+                details: None,
             });
         }
     
         // Send connected message
-        let _ = message_tx.send(SseMessage::Connected).await;
+        *state.write().await = SseState::Connected;
+        reconnect_attempts.store(0, Ordering::Relaxed);
+        quality.reset_heartbeats();
+        Self::dispatch(config, message_tx, SseMessage::Connected).await;
     
         // Process SSE stream
         let mut stream = response.bytes_stream();
-        let mut buffer = String::new();
-        let mut event_type = String::from("message");
-    
-        while let Some(chunk_result) = stream.next().await {
-            let chunk = chunk_result?;
-            let chunk_str = String::from_utf8_lossy(&chunk);
-            buffer.push_str(&chunk_str);
+        parser.reset();
+        let idle_deadline = |idle: Duration| tokio::time::Instant::now() + idle;
+        let mut deadline = config.idle_unsubscribe_after.map(idle_deadline);
+        let mut draining = false;
     
-            // Process complete lines
-            while let Some(pos) = buffer.find('\n') {
-                let line = buffer[..pos].to_string();
-                buffer = buffer[pos + 1..].to_string();
-    
-                let line = line.trim();
-                if line.is_empty() {
-                    // Empty line - dispatch event
-                    if !event_type.is_empty() {
-                        // Send event (simplified implementation)
-                        let _ = message_tx.send(SseMessage::Heartbeat).await;
-                    }
-                    event_type = String::from("message");
-                } else if line.starts_with("event:") {
-                    event_type = line[6..].trim().to_string();
-                } else if line.starts_with("data:") {
-                    // Parse data (simplified)
-                    let data = line[5..].trim();
-                    if !data.is_empty() {
-                        // Send message
-                        let _ = message_tx.send(SseMessage::Heartbeat).await;
+        loop {
+            // Any data, heartbeats included, proves the connection is alive
+            let watchdog = (!config.heartbeat_interval.is_zero())
+                .then(|| tokio::time::Instant::now() + config.heartbeat_interval * 2);
+            let wait_until = match (deadline, watchdog) {
+                (Some(idle), Some(watchdog)) => Some(idle.min(watchdog)),
+                (idle, watchdog) => idle.or(watchdog),
+            };
+            let next = match wait_until {
+                Some(until) => match tokio::time::timeout_at(until, stream.next()).await {
+                    Ok(next) => next,
+                    Err(_) if deadline == Some(until) => return Ok(StreamEnd::Idle),
+                    Err(_) => {
+                        return Err(SecureNotifyError::TimeoutError(
+                            format!(
+                                "No data received from the SSE stream within {:?}",
+                                config.heartbeat_interval * 2
+                            ),
+                            None,
+                        ))
                     }
-                } else if line.starts_with(':') {
-                    // Comment - ignore
+                },
+                None => stream.next().await,
+            };
+            let Some(chunk_result) = next else {
+                break;
+            };
+            let chunk = chunk_result?;
+
+            for message in parser.feed(&chunk) {
+                quality.record(&message);
+                if let SseMessage::Event(SseEvent { id: Some(id), .. }) = &message {
+                    *last_event_id.write().await = Some(id.clone());
+                }
+                if config.resets_idle(&message) {
+                    deadline = config.idle_unsubscribe_after.map(idle_deadline);
                 }
+                draining |= matches!(message, SseMessage::Draining);
+                Self::dispatch(config, message_tx, message).await;
+            }
+
+            // Lines already received are delivered; nothing more is read
+            if draining {
+                return Ok(StreamEnd::Drained);
             }
         }
     
-        Ok(())
+        Ok(StreamEnd::Closed)
     }
 
     /// Get the current connection state
@@ -285,9 +1301,94 @@ impl SseConnection {
         *state_guard == SseState::Connected
     }
 
+    /// Get the heartbeat regularity and freshness of the stream
+    ///
+    /// Gaps are measured over the last 20 heartbeats of the current
+    /// connection. Messages are counted before the event filter is applied.
+    pub fn connection_quality(&self) -> ConnectionQuality {
+        self.quality.quality()
+    }
+
+    /// Get the ID of the last event received, sent as `Last-Event-ID` on reconnect
+    pub async fn last_event_id(&self) -> Option<String> {
+        self.last_event_id.read().await.clone()
+    }
+
+    /// Get the number of reconnects attempted since the stream was last connected
+    ///
+    /// Zero while connected. Each attempt is also reported on the
+    /// `SseMessage::Error` that causes it, for showing progress such as
+    /// "reconnecting (attempt 3/10)".
+    pub fn reconnect_attempts(&self) -> u32 {
+        self.reconnect_attempts.load(Ordering::Relaxed)
+    }
+
+    /// Get the state, recent activity and reconnect count of the connection
+    pub async fn health(&self) -> SubscriptionHealth {
+        self.health_probe().health().await
+    }
+
+    /// Get the number of messages discarded because the receiver was full
+    ///
+    /// Always zero under `OverflowPolicy::Block`.
+    pub fn dropped_count(&self) -> u64 {
+        self.sink.dropped_count()
+    }
+
+    /// Get a shared handle for reading the connection's health later
+    pub(crate) fn health_probe(&self) -> HealthProbe {
+        HealthProbe {
+            state: self.state.clone(),
+            quality: self.quality.clone(),
+        }
+    }
+
+    /// Stop delivering messages without closing the connection
+    ///
+    /// The stream keeps running, so heartbeats keep it alive and no
+    /// reconnect is needed on resume. Events arriving meanwhile are held or
+    /// dropped according to `SseConfig::pause_policy`.
+    pub fn pause(&self) {
+        self.sink.pause();
+    }
+
+    /// Deliver the messages held while paused, then resume live delivery
+    pub async fn resume(&self) {
+        self.sink.resume().await;
+    }
+
+    /// Check whether delivery is paused
+    pub fn is_paused(&self) -> bool {
+        self.sink.is_paused()
+    }
+
+    /// Get a handle that stops the connection task when aborted
+    pub(crate) fn abort_handle(&self) -> tokio::task::AbortHandle {
+        self.abort.clone()
+    }
+
     /// Disconnect from the SSE stream
+    ///
+    /// Stops the connection task and waits for it to end, closing the
+    /// stream, then emits a final
+    /// `SseMessage::Disconnected(DisconnectReason::ClientRequested)`.
+    ///
+    /// The final message is emitted once, however many times this is
+    /// called. It skips the pause buffer and never waits for room in the
+    /// receiver's channel; if the channel is full, it is dropped.
     pub async fn disconnect(&self) {
-        let mut state_guard = self.state.write().await;
-        *state_guard = SseState::Disconnected;
+        self.abort.abort();
+        let _ = self.stopped.clone().wait_for(|stopped| *stopped).await;
+        {
+            let mut state_guard = self.state.write().await;
+            *state_guard = SseState::Disconnected;
+        }
+        if self.disconnected.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let message = SseMessage::Disconnected(DisconnectReason::ClientRequested);
+        if self._config.accepts(&message) {
+            self.sink.try_deliver(message);
+        }
     }
 }
@@ -4,13 +4,100 @@
 //! HTTP client utilities for SecureNotify SDK
 
 use reqwest::{Client, RequestBuilder, Response, redirect::Policy};
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU16, AtomicU32, AtomicU64, Ordering};
 use std::time::Duration;
-use crate::{SecureNotifyError, Result};
+use crate::{SecureNotifyError, Result, MessagePriority};
+use crate::types::api::{ApiErrorDetails, Conditional, MessagePublishRequest, MessagePublishResponse, Permission, Validators};
 use crate::utils::retry::{with_retry, RetryConfig};
+use crate::types::error::{is_retryable_error, ErrorSource};
 use super::metrics::{MetricsCollector, MetricsContext};
-use super::cache::ResponseCache;
+use super::cache::{CachePolicy, CacheSnapshot, ResponseCache};
 use super::request_deduplicator::RequestDeduplicator;
+use super::rate_limiter::PriorityRateLimiter;
+use super::interceptor::{InterceptorChain, MetadataValidator, RequestCompleteHook, RequestIdGenerator, RequestOutcome, RetriesExhaustedHook};
+use super::signing::{RequestSigner, ResponseVerifier};
+use super::json_stream::json_array_stream;
+use super::concurrency::AdaptiveConcurrencyLimiter;
+use super::sequencer::ChannelSequencer;
+use super::connection::SseConfig;
+use super::publish_throttle::PublishThrottle;
+use super::subscriptions::SubscriptionRegistry;
+use super::quota::Operation;
+use super::trace::TraceContext;
+use super::request_options::RequestOptions;
+use super::jwt::ApiKeyClaims;
+use super::tls;
+use futures::future;
+use futures::stream::{self, BoxStream, StreamExt};
+
+/// HTTP protocol version selection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HttpVersion {
+    /// Negotiate the version (HTTP/2 via ALPN when available)
+    #[default]
+    Auto,
+    /// Only use HTTP/1.1
+    Http1Only,
+    /// Use HTTP/2 without negotiation (required for h2c backends)
+    Http2PriorKnowledge,
+}
+
+/// How batch operations handle failures of individual items
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BatchPolicy {
+    /// Run every item and report each result separately
+    #[default]
+    BestEffort,
+    /// Stop at the first failure, cancelling requests still in flight
+    FailFast,
+}
+
+/// Casing of request body field names
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FieldCase {
+    /// Field names as the request types declare them (snake_case)
+    #[default]
+    Snake,
+    /// camelCase field names, e.g. `channel_type` becomes `channelType`
+    Camel,
+}
+
+impl FieldCase {
+    /// Serialize a request body with its field names in this casing
+    ///
+    /// Only the body's own fields are renamed; nested values such as
+    /// `metadata` are sent as given.
+    pub fn to_vec<T: serde::Serialize>(&self, body: &T) -> serde_json::Result<Vec<u8>> {
+        match self {
+            Self::Snake => serde_json::to_vec(body),
+            Self::Camel => match serde_json::to_value(body)? {
+                serde_json::Value::Object(fields) => serde_json::to_vec(
+                    &fields
+                        .into_iter()
+                        .map(|(name, value)| (snake_to_camel(&name), value))
+                        .collect::<serde_json::Map<_, _>>(),
+                ),
+                other => serde_json::to_vec(&other),
+            },
+        }
+    }
+}
+
+/// Convert a snake_case name to camelCase, leaving other names unchanged
+fn snake_to_camel(name: &str) -> String {
+    let mut parts = name.split('_');
+    let mut camel = parts.next().unwrap_or_default().to_string();
+    for part in parts {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            camel.extend(first.to_uppercase());
+            camel.push_str(chars.as_str());
+        }
+    }
+    camel
+}
 
 /// HTTP client configuration
 #[derive(Debug, Clone)]
@@ -22,6 +109,61 @@ pub struct HttpClientConfig {
     pub initial_delay_ms: u64,
     pub max_delay_ms: u64,
     pub backoff_multiplier: f64,
+    pub enable_metrics: bool,
+    pub enable_cache: bool,
+    pub enable_deduplication: bool,
+    /// Publish rate limits as `(rate per second, burst)` per message priority
+    pub priority_rate_limits: HashMap<MessagePriority, (f64, u32)>,
+    /// Priority used when a message is published without one
+    pub default_priority: Option<MessagePriority>,
+    /// Extra headers sent with every request
+    pub default_headers: http::HeaderMap,
+    /// Interceptors run on every outgoing request
+    pub interceptors: InterceptorChain,
+    /// Callback invoked once per completed request
+    pub on_request_complete: Option<RequestCompleteHook>,
+    /// Callback invoked when a request fails after retrying
+    pub on_retries_exhausted: Option<RetriesExhaustedHook>,
+    /// HTTP protocol version to use
+    pub http_version: HttpVersion,
+    /// Signer applied to every request before it is sent
+    pub signer: Option<Arc<dyn RequestSigner>>,
+    /// Retry when a 2xx response body fails to deserialize
+    pub retry_serialization_errors: bool,
+    /// Path probed by `warm_up` (default: the base URL)
+    pub warm_up_path: String,
+    /// Whether `warm_up` fails on non-success responses
+    pub strict_warm_up: bool,
+    /// Validator applied to metadata before it is sent
+    pub metadata_validator: Option<MetadataValidator>,
+    /// Failed SSE connection attempts before falling back to long-polling
+    pub longpoll_fallback_after: Option<u32>,
+    /// Bounds `(min, max)` for adaptive request concurrency
+    pub adaptive_concurrency: Option<(usize, usize)>,
+    /// SHA-256 fingerprint the server's leaf certificate must match
+    pub pinned_certificate: Option<[u8; 32]>,
+    /// Extra DER-encoded root certificates to trust
+    pub root_certificates: Vec<Vec<u8>>,
+    /// Serialize publishes per channel so they reach the server in call order
+    pub ordered_publish: bool,
+    /// Send W3C `traceparent`/`tracestate` headers with each request
+    pub trace_propagation: bool,
+    /// Failure handling for batch operations such as `publish_fanout`
+    pub batch_policy: BatchPolicy,
+    /// Retry POST requests only when they failed before being sent
+    pub safe_post_retries: bool,
+    /// Window within which an identical publish returns the earlier response
+    pub publish_dedup_window: Option<std::time::Duration>,
+    /// Ed25519 public key that response bodies must be signed with
+    pub response_verification_key: Option<[u8; 32]>,
+    /// Casing of request body field names
+    pub field_case: FieldCase,
+    /// Reject operations the key's known permissions do not allow before sending
+    pub enforce_local_permissions: bool,
+    /// Generator of `X-Request-Id` values (default: random UUID v4)
+    pub request_id_generator: Option<RequestIdGenerator>,
+    /// Reject JSON responses whose `Content-Type` is not a JSON type
+    pub check_content_type: bool,
 }
 
 impl Default for HttpClientConfig {
@@ -34,6 +176,35 @@ impl Default for HttpClientConfig {
             initial_delay_ms: 1000,
             max_delay_ms: 30000,
             backoff_multiplier: 2.0,
+            enable_metrics: false,
+            enable_cache: false,
+            enable_deduplication: false,
+            priority_rate_limits: HashMap::new(),
+            default_priority: None,
+            default_headers: http::HeaderMap::new(),
+            interceptors: InterceptorChain::new(),
+            on_request_complete: None,
+            on_retries_exhausted: None,
+            http_version: HttpVersion::Auto,
+            signer: None,
+            retry_serialization_errors: false,
+            warm_up_path: String::new(),
+            strict_warm_up: false,
+            metadata_validator: None,
+            longpoll_fallback_after: None,
+            adaptive_concurrency: None,
+            pinned_certificate: None,
+            root_certificates: Vec::new(),
+            ordered_publish: false,
+            trace_propagation: false,
+            batch_policy: BatchPolicy::BestEffort,
+            safe_post_retries: false,
+            publish_dedup_window: None,
+            response_verification_key: None,
+            field_case: FieldCase::Snake,
+            enforce_local_permissions: false,
+            request_id_generator: None,
+            check_content_type: true,
         }
     }
 }
@@ -48,6 +219,18 @@ pub struct HttpClient {
     metrics_collector: Option<Arc<MetricsCollector>>,
     cache: Option<Arc<ResponseCache<String>>>,
     request_deduplicator: Option<Arc<RequestDeduplicator>>,
+    rate_limiter: Option<Arc<PriorityRateLimiter>>,
+    concurrency_limiter: Option<Arc<AdaptiveConcurrencyLimiter>>,
+    publish_sequencer: Option<Arc<ChannelSequencer>>,
+    publish_throttle: Option<Arc<PublishThrottle>>,
+    response_verifier: Option<ResponseVerifier>,
+    subscriptions: Arc<SubscriptionRegistry>,
+    quota_consumed: Arc<AtomicU64>,
+    known_permissions: Arc<std::sync::RwLock<Option<Vec<Permission>>>>,
+    /// Server clock minus local clock, from the latest response `Date` header
+    clock_offset: Arc<std::sync::RwLock<Option<time::Duration>>>,
+    /// Bumped by `cancel_all_requests`; requests in flight watch for a change
+    cancellation: Arc<tokio::sync::watch::Sender<u64>>,
 }
 
 impl HttpClient {
@@ -68,6 +251,7 @@ impl HttpClient {
     }
 
     /// Create an HTTP client with custom configuration
+    #[allow(clippy::too_many_arguments)]
     pub fn with_config(
         base_url: &str,
         api_key: &str,
@@ -80,50 +264,112 @@ impl HttpClient {
         enable_cache: bool,
         enable_deduplication: bool,
     ) -> Result<Self> {
+        Self::from_config(HttpClientConfig {
+            base_url: base_url.to_string(),
+            api_key: api_key.to_string(),
+            timeout,
+            max_retries,
+            initial_delay_ms,
+            max_delay_ms,
+            backoff_multiplier,
+            enable_metrics,
+            enable_cache,
+            enable_deduplication,
+            ..Default::default()
+        })
+    }
+
+    /// Create an HTTP client from a full configuration
+    pub fn from_config(config: HttpClientConfig) -> Result<Self> {
         // Configure SSL/TLS with TLS 1.2 enforcement and redirect limits (SECURITY FIX)
         // Minimum TLS 1.2 provides strong security while maintaining broad compatibility
-        let client = Client::builder()
-            .timeout(timeout)
+        let builder = Client::builder()
+            .timeout(config.timeout)
             .redirect(Policy::limited(5)) // Limit redirects to prevent SSRF
             .use_native_tls()
-            .min_tls_version(reqwest::tls::Version::TLS_1_2)
+            .min_tls_version(reqwest::tls::Version::TLS_1_2);
+        let builder = tls::configure(
+            builder,
+            config.pinned_certificate,
+            &config.root_certificates,
+            config.http_version == HttpVersion::Http1Only,
+        )?;
+
+        let builder = match config.http_version {
+            HttpVersion::Auto => builder,
+            HttpVersion::Http1Only => builder.http1_only(),
+            HttpVersion::Http2PriorKnowledge => builder.http2_prior_knowledge(),
+        };
+
+        let client = builder
             .build()
-            .map_err(|e| SecureNotifyError::ConnectionError(format!("Failed to build HTTP client: {}", e)))?;
+            .map_err(|e| SecureNotifyError::ConnectionError(format!("Failed to build HTTP client: {}", e), None))?;
 
-        let metrics_collector = if enable_metrics {
+        let metrics_collector = if config.enable_metrics {
             Some(Arc::new(MetricsCollector::default()))
         } else {
             None
         };
 
-        let cache = if enable_cache {
+        let cache = if config.enable_cache {
             Some(Arc::new(ResponseCache::default()))
         } else {
             None
         };
 
-        let request_deduplicator = if enable_deduplication {
+        let request_deduplicator = if config.enable_deduplication {
             Some(Arc::new(RequestDeduplicator::default()))
         } else {
             None
         };
 
+        let rate_limiter = if config.priority_rate_limits.is_empty() {
+            None
+        } else {
+            Some(Arc::new(PriorityRateLimiter::new(&config.priority_rate_limits)))
+        };
+
+        let concurrency_limiter = config
+            .adaptive_concurrency
+            .map(|(min, max)| Arc::new(AdaptiveConcurrencyLimiter::new(min, max)));
+
+        let publish_sequencer = config
+            .ordered_publish
+            .then(|| Arc::new(ChannelSequencer::new()));
+
+        let publish_throttle = config
+            .publish_dedup_window
+            .map(|window| Arc::new(PublishThrottle::new(window)));
+
+        let response_verifier = config
+            .response_verification_key
+            .as_ref()
+            .map(ResponseVerifier::new)
+            .transpose()?;
+
+        // A JWT key's scopes are the best local guess until told otherwise
+        let known_permissions = ApiKeyClaims::decode(&config.api_key)
+            .ok()
+            .and_then(|claims| claims.permissions());
+
         Ok(Self {
             client,
-            base_url: base_url.to_string(),
-            api_key: api_key.to_string(),
-            config: HttpClientConfig {
-                base_url: base_url.to_string(),
-                api_key: api_key.to_string(),
-                timeout,
-                max_retries,
-                initial_delay_ms,
-                max_delay_ms,
-                backoff_multiplier,
-            },
+            base_url: config.base_url.clone(),
+            api_key: config.api_key.clone(),
+            config,
             metrics_collector,
             cache,
             request_deduplicator,
+            rate_limiter,
+            concurrency_limiter,
+            publish_sequencer,
+            publish_throttle,
+            response_verifier,
+            subscriptions: Arc::new(SubscriptionRegistry::new()),
+            quota_consumed: Arc::new(AtomicU64::new(0)),
+            known_permissions: Arc::new(std::sync::RwLock::new(known_permissions)),
+            clock_offset: Arc::new(std::sync::RwLock::new(None)),
+            cancellation: Arc::new(tokio::sync::watch::channel(0).0),
         })
     }
 
@@ -132,6 +378,14 @@ impl HttpClient {
         &self.config
     }
 
+    /// Check metadata against the configured validator, if any
+    pub fn validate_metadata(&self, metadata: Option<&serde_json::Value>) -> Result<()> {
+        match (&self.config.metadata_validator, metadata) {
+            (Some(validator), Some(metadata)) => validator.validate(metadata),
+            _ => Ok(()),
+        }
+    }
+
     /// Build the base URL for an endpoint
     fn build_url(&self, endpoint: &str) -> String {
         let base = self.base_url.trim_end_matches('/');
@@ -139,22 +393,105 @@ impl HttpClient {
         format!("{}/{}", base, endpoint)
     }
 
+    /// Build the SSE configuration for subscribing to a channel
+    ///
+    /// The subscribe URL is derived from the base URL like any other endpoint,
+    /// and the API key, request timeout, extra root certificates and
+    /// certificate pin are carried over.
+    pub fn sse_config_for(&self, channel_id: &str) -> SseConfig {
+        let url = self.build_url(&format!("api/subscribe/{}", channel_id));
+        let mut config = self.config.root_certificates.iter().fold(
+            SseConfig::new(url, self.api_key.clone()).with_connection_timeout(self.config.timeout),
+            |config, der| config.with_root_certificate(der.clone()),
+        );
+        config.pinned_certificate = self.config.pinned_certificate;
+        config
+    }
+
     /// Create a request builder with authentication
     fn request(&self, method: reqwest::Method, endpoint: &str) -> RequestBuilder {
-        let url = self.build_url(endpoint);
-        let mut builder = self.client.request(method, url);
+        self.consume_quota(Operation::Request.cost());
+
+        let mut url = self.build_url(endpoint);
+        let mut method = method;
+        let mut headers = http::HeaderMap::new();
 
-        builder = builder.header("User-Agent", "SecureNotify-Rust/0.1.0");  // Add User-Agent header
+        if let Ok(value) = http::HeaderValue::from_str(&format!("SecureNotify-Rust/{}", crate::SDK_VERSION)) {
+            headers.insert(http::header::USER_AGENT, value);
+        }
 
-        // Add request ID for tracing
-        let request_id = uuid::Uuid::new_v4().to_string();
-        builder = builder.header("X-Request-ID", request_id);
+        // Add request ID for tracing; retries resend it unchanged
+        let request_id = match &self.config.request_id_generator {
+            Some(generator) => generator.generate(),
+            None => uuid::Uuid::new_v4().to_string(),
+        };
+        if let Ok(value) = http::HeaderValue::from_str(&request_id) {
+            headers.insert("x-request-id", value);
+        }
+
+        if self.config.trace_propagation {
+            let trace = TraceContext::current()
+                .map(|ctx| ctx.child())
+                .unwrap_or_else(TraceContext::new_random);
+            if let Ok(value) = http::HeaderValue::from_str(&trace.traceparent()) {
+                headers.insert("traceparent", value);
+            }
+            if let Some(value) = trace
+                .tracestate
+                .as_deref()
+                .and_then(|state| http::HeaderValue::from_str(state).ok())
+            {
+                headers.insert("tracestate", value);
+            }
+        }
 
         if !self.api_key.is_empty() {
-            builder = builder.header("X-API-Key", &self.api_key);
+            if let Ok(value) = http::HeaderValue::from_str(&self.api_key) {
+                headers.insert("x-api-key", value);
+            }
+        }
+
+        for (name, value) in &self.config.default_headers {
+            headers.insert(name.clone(), value.clone());
+        }
+
+        if !self.config.interceptors.is_empty() {
+            if let Ok(request) = http::Request::builder()
+                .method(method.as_str())
+                .uri(url.as_str())
+                .body(())
+            {
+                let (mut parts, _) = request.into_parts();
+                parts.headers = headers;
+                self.config.interceptors.apply(&mut parts);
+
+                method = reqwest::Method::from_bytes(parts.method.as_str().as_bytes()).unwrap_or(method);
+                url = parts.uri.to_string();
+                headers = parts.headers;
+            }
         }
 
-        builder
+        self.client.request(method, url).headers(to_reqwest_headers(&headers))
+    }
+
+    /// Create a request builder with a JSON body
+    ///
+    /// The body is serialized up front so a value that cannot be serialized
+    /// fails with `RequestSerializationError` before anything is sent,
+    /// rather than being confused with an unparseable response.
+    fn json_request<B: serde::Serialize>(
+        &self,
+        method: reqwest::Method,
+        endpoint: &str,
+        body: &B,
+    ) -> Result<RequestBuilder> {
+        let body = self.config.field_case.to_vec(body).map_err(|e| {
+            SecureNotifyError::RequestSerializationError(e.to_string(), Some(ErrorSource::new(e)))
+        })?;
+        Ok(self
+            .request(method, endpoint)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body))
     }
 
     /// Execute a request with retry logic
@@ -162,39 +499,107 @@ impl HttpClient {
         &self,
         request: RequestBuilder,
     ) -> Result<T> {
+        self.execute_with_retry_headers(request)
+            .await
+            .map(|(value, _)| value)
+    }
+
+    /// Execute a request with retry logic, also returning the response headers
+    async fn execute_with_retry_headers<T: serde::de::DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<(T, reqwest::header::HeaderMap)> {
+        self.execute_with_retry_decoded(request, self.json_decoder()).await
+    }
+
+    /// Execute a request with retry logic, decoding the body with `decode`
+    ///
+    /// Decoding runs inside the retry loop, so decode failures are retried
+    /// when serialization errors are.
+    async fn execute_with_retry_decoded<T, D>(
+        &self,
+        request: RequestBuilder,
+        decode: D,
+    ) -> Result<(T, reqwest::header::HeaderMap)>
+    where
+        D: Fn(reqwest::StatusCode, &reqwest::header::HeaderMap, &[u8]) -> Result<T>,
+    {
+        let request = request.try_clone()
+            .ok_or_else(|| SecureNotifyError::ConnectionError("Failed to clone request for retry".to_string(), None))?;
+
+        // Create metrics context if metrics are enabled
+        let (method, endpoint, trace_id) = request.try_clone()
+            .and_then(|r| r.build().ok())
+            .map(|r| {
+                let trace_id = r.headers()
+                    .get("traceparent")
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(TraceContext::parse)
+                    .map(|ctx| ctx.trace_id_hex());
+                (r.method().clone(), r.url().path().to_string(), trace_id)
+            })
+            .unwrap_or_else(|| (reqwest::Method::GET, "unknown".to_string(), None));
+
         let retry_config = RetryConfig::new()
             .with_max_retries(self.config.max_retries)
             .with_initial_delay(Duration::from_millis(self.config.initial_delay_ms))
             .with_max_delay(Duration::from_millis(self.config.max_delay_ms))
             .with_backoff_multiplier(self.config.backoff_multiplier)
-            .with_jitter(true);
-
-        let request = request.try_clone()
-            .ok_or_else(|| SecureNotifyError::ConnectionError("Failed to clone request for retry".to_string()))?;
+            .with_jitter(true)
+            .with_retry_serialization_errors(self.config.retry_serialization_errors)
+            .with_pre_response_only(self.config.safe_post_retries && method == reqwest::Method::POST);
 
-        // Create metrics context if metrics are enabled
-        let endpoint = request.try_clone()
-            .and_then(|r| r.build().ok())
-            .map(|r| r.url().path().to_string())
-            .unwrap_or_else(|| "unknown".to_string());
+        // Tags label the metrics and span only; they are never sent
+        let tags = RequestOptions::current().map(|options| options.tags).unwrap_or_default();
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!("request", method = %method, endpoint = %endpoint, tags = ?tags);
 
         let metrics_context = self.metrics_collector.as_ref().map(|mc| {
-            MetricsContext::new(mc.as_ref(), &endpoint)
+            MetricsContext::new(mc.as_ref(), &endpoint).with_tags(tags)
         });
 
-        let result = with_retry(
-            |_attempt| {
+        let start_time = std::time::Instant::now();
+        let attempts = AtomicU32::new(0);
+        let last_status = AtomicU16::new(0);
+
+        let retried = with_retry(
+            |attempt| {
+                attempts.store(attempt + 1, Ordering::Relaxed);
                 let request = request.try_clone()
-                    .ok_or_else(|| SecureNotifyError::ConnectionError("Failed to clone request in retry loop".to_string()));
+                    .ok_or_else(|| SecureNotifyError::ConnectionError("Failed to clone request in retry loop".to_string(), None));
+                let last_status = &last_status;
+                let decode = &decode;
                 async move {
                     let request = request?;
-                    let response = request.send().await?;
-                    self.handle_response(response).await
+                    let _permit = match &self.concurrency_limiter {
+                        Some(limiter) => Some(limiter.acquire().await),
+                        None => None,
+                    };
+                    let attempt_start = std::time::Instant::now();
+
+                    let result = match self.send(request).await {
+                        Ok(response) => {
+                            last_status.store(response.status().as_u16(), Ordering::Relaxed);
+                            self.handle_response(response, decode).await
+                        }
+                        Err(e) => Err(e),
+                    };
+
+                    // Only failures that signal overload shrink the limit
+                    if let Some(limiter) = &self.concurrency_limiter {
+                        let overloaded = result.as_ref().is_err_and(is_retryable_error);
+                        limiter.record(!overloaded, attempt_start.elapsed());
+                    }
+                    result
                 }
             },
             &retry_config,
-        )
-        .await;
+        );
+        #[cfg(feature = "tracing")]
+        let retried = tracing::Instrument::instrument(retried, span);
+        let result = self.cancellable(retried).await;
+
+        super::timing::record_attempts(attempts.load(Ordering::Relaxed));
 
         // Mark success or failure for metrics
         if let Some(mut ctx) = metrics_context {
@@ -204,64 +609,290 @@ impl HttpClient {
             ctx.record();
         }
 
+        if let (Some(hook), Err(SecureNotifyError::RetriesExhausted { source, attempts, .. })) =
+            (&self.config.on_retries_exhausted, &result)
+        {
+            hook.call(&endpoint, source, *attempts);
+        }
+
+        if let Some(hook) = &self.config.on_request_complete {
+            let status = last_status.load(Ordering::Relaxed);
+            hook.call(&RequestOutcome {
+                method: http::Method::from_bytes(method.as_str().as_bytes())
+                    .unwrap_or(http::Method::GET),
+                endpoint,
+                status: (status != 0).then_some(status),
+                duration: start_time.elapsed(),
+                attempts: attempts.load(Ordering::Relaxed),
+                error_kind: result.as_ref().err().map(|e| e.kind()),
+                trace_id,
+            });
+        }
+
         result
     }
 
+    /// Send a request, signing it first when a signer is configured
+    async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        match &self.config.signer {
+            Some(signer) => self.client.execute(Self::sign(request, signer.as_ref())?).await,
+            None => request.send().await,
+        }
+        .map_err(tls::send_error)
+    }
+
+    /// Build a request and apply the signer to it
+    fn sign(request: RequestBuilder, signer: &dyn RequestSigner) -> Result<reqwest::Request> {
+        let mut request = request.build()?;
+        let (mut parts, _) = http::Request::builder()
+            .method(request.method().as_str())
+            .uri(request.url().as_str())
+            .body(())
+            .map_err(|e| SecureNotifyError::ConnectionError(format!("Failed to sign request: {}", e), None))?
+            .into_parts();
+        parts.headers = from_reqwest_headers(request.headers());
+
+        let body = request.body().and_then(|b| b.as_bytes()).unwrap_or_default();
+        signer.sign(&mut parts, body);
+
+        *request.headers_mut() = to_reqwest_headers(&parts.headers);
+        Ok(request)
+    }
+
     /// Handle the HTTP response
-    async fn handle_response<T: serde::de::DeserializeOwned>(
+    ///
+    /// Success and 304 bodies are passed to `decode`; other statuses become
+    /// errors. With a response verification key configured, a success body
+    /// is only decoded once its `X-Signature` has been verified.
+    async fn handle_response<T>(
         &self,
         response: Response,
-    ) -> Result<T> {
+        decode: impl Fn(reqwest::StatusCode, &reqwest::header::HeaderMap, &[u8]) -> Result<T>,
+    ) -> Result<(T, reqwest::header::HeaderMap)> {
         let status = response.status();
 
-        if status.is_success() {
-            response.json().await.map_err(|e| e.into())
+        if status.is_success() || status == reqwest::StatusCode::NOT_MODIFIED {
+            let headers = response.headers().clone();
+            self.record_server_date(&headers);
+            let body = response.bytes().await?;
+            if let (true, Some(verifier)) = (status.is_success(), &self.response_verifier) {
+                verifier.verify(&headers, &body)?;
+            }
+            Ok((decode(status, &headers, &body)?, headers))
         } else {
-            // Try to parse error response
-            let error_text = response.text().await.unwrap_or_default();
-            let code = status.as_u16().to_string();
+            Err(Self::error_from_response(response).await)
+        }
+    }
 
-            Err(SecureNotifyError::ApiError {
-                code,
-                message: error_text,
-                status: status.as_u16(),
+    /// Decoder for JSON responses, checking the content type when configured
+    fn json_decoder<T: serde::de::DeserializeOwned>(
+        &self,
+    ) -> impl Fn(reqwest::StatusCode, &reqwest::header::HeaderMap, &[u8]) -> Result<T> {
+        let check_content_type = self.config.check_content_type;
+        move |status: reqwest::StatusCode, headers: &reqwest::header::HeaderMap, body: &[u8]| {
+            if check_content_type {
+                ensure_json_content_type(headers, body)?;
+            }
+            decode_json(status, body)
+        }
+    }
+
+    /// Build an error from a non-success response
+    ///
+    /// 409 and 412 responses become `SecureNotifyError::Conflict`, naming the
+    /// resource collection from the request path (e.g. `channels`), 413
+    /// responses become `SecureNotifyError::PayloadTooLarge` with any size
+    /// limit the server gave, and 503 responses become
+    /// `SecureNotifyError::ServiceUnavailable` carrying the `Retry-After`
+    /// hint. Other errors carry any structured error details found in the
+    /// body.
+    async fn error_from_response(response: Response) -> SecureNotifyError {
+        let status = response.status();
+        let resource = response
+            .url()
+            .path_segments()
+            .and_then(|mut segments| {
+                segments.find(|segment| !segment.is_empty() && *segment != "api")
             })
+            .unwrap_or("unknown")
+            .to_string();
+
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(crate::types::error::parse_retry_after);
+
+        let payload_limit_header = response
+            .headers()
+            .get(crate::types::error::MAX_PAYLOAD_SIZE_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+
+        // Try to parse error response
+        let error_text = response.text().await.unwrap_or_default();
+
+        if status == reqwest::StatusCode::SERVICE_UNAVAILABLE {
+            return SecureNotifyError::ServiceUnavailable {
+                message: error_text,
+                retry_after,
+            };
+        }
+
+        if status == reqwest::StatusCode::PAYLOAD_TOO_LARGE {
+            let limit = crate::types::error::parse_payload_limit(payload_limit_header.as_deref(), &error_text);
+            return SecureNotifyError::PayloadTooLarge {
+                message: error_text,
+                limit,
+            };
+        }
+
+        // A failed precondition (e.g. `If-None-Match: *` on create) means the resource exists
+        if status == reqwest::StatusCode::CONFLICT || status == reqwest::StatusCode::PRECONDITION_FAILED {
+            return SecureNotifyError::Conflict {
+                resource,
+                message: error_text,
+            };
+        }
+
+        let details = ApiErrorDetails::from_body(&error_text).map(Box::new);
+        SecureNotifyError::ApiError {
+            code: status.as_u16().to_string(),
+            message: error_text,
+            status: status.as_u16(),
+            details,
         }
     }
 
     /// Execute a GET request
-    pub async fn get<T: serde::de::DeserializeOwned + serde::Serialize>(&self, endpoint: &str) -> Result<T> {
+    ///
+    /// When the cache is enabled, the response `Cache-Control` header is
+    /// honored: `no-store` and `private` responses are not cached, and
+    /// `max-age` overrides the default TTL. The response body is cached as
+    /// received, so a hit decodes exactly what the network path decoded.
+    pub async fn get<T: serde::de::DeserializeOwned>(&self, endpoint: &str) -> Result<T> {
         // Check cache first if enabled
         if let Some(cache) = &self.cache {
-            let cache_key = format!("GET:{}", endpoint);
-            if let Some(cached) = cache.get(&cache_key) {
-                return serde_json::from_str(&cached).map_err(|e| {
-                    SecureNotifyError::SerializationError(format!("Failed to parse cached response: {}", e))
-                });
+            if let Some(cached) = cache.get(&Self::cache_key(endpoint)) {
+                return decode_json(reqwest::StatusCode::OK, cached.as_bytes());
             }
         }
 
         let request = self.request(reqwest::Method::GET, endpoint);
-        let result = self.execute_with_retry(request).await?;
+        let decode = self.json_decoder::<T>();
+        let keep_body = self.cache.is_some();
+        let ((result, body), headers) = self
+            .execute_with_retry_decoded(request, |status, headers, body| {
+                let value = decode(status, headers, body)?;
+                Ok((value, keep_body.then(|| String::from_utf8_lossy(body).into_owned())))
+            })
+            .await?;
 
         // Cache successful responses
-        if let Some(cache) = &self.cache {
-            if let Ok(json) = serde_json::to_string(&result) {
-                let cache_key = format!("GET:{}", endpoint);
-                cache.set(cache_key, json, None);
+        if let (Some(cache), Some(body)) = (&self.cache, body) {
+            let cache_control = headers
+                .get(reqwest::header::CACHE_CONTROL)
+                .and_then(|v| v.to_str().ok());
+            let ttl = match CachePolicy::from_cache_control(cache_control) {
+                CachePolicy::NoStore => None,
+                CachePolicy::MaxAge(ttl) => Some(Some(ttl)),
+                CachePolicy::Default => Some(None),
+            };
+
+            if let Some(ttl) = ttl {
+                cache.set(Self::cache_key(endpoint), body, ttl);
             }
         }
 
         Ok(result)
     }
 
+    /// Fetch every page of an offset-paginated listing
+    ///
+    /// `endpoint` builds the URL for a page of `page_size` items starting at
+    /// the given offset. Pages are requested until one comes back short. Each
+    /// page goes through the normal retry logic, so a rate-limited page is
+    /// retried with backoff and the listing resumes from that page's offset
+    /// instead of starting over.
+    pub async fn get_all_pages<T, F>(&self, page_size: u32, endpoint: F) -> Result<Vec<T>>
+    where
+        T: serde::de::DeserializeOwned,
+        F: Fn(u32, u32) -> String,
+    {
+        let page_size = page_size.max(1);
+        let mut items = Vec::new();
+        let mut offset = 0;
+        loop {
+            let page: Vec<T> = self.get(&endpoint(page_size, offset)).await?;
+            let count = page.len() as u32;
+            items.extend(page);
+            if count < page_size {
+                return Ok(items);
+            }
+            offset += count;
+        }
+    }
+
+    /// Execute a conditional GET request
+    ///
+    /// Sends `If-None-Match` and `If-Modified-Since` from `validators` and
+    /// returns `Conditional::NotModified` when the server answers 304. Any
+    /// other success must carry a body. The response cache is bypassed.
+    pub async fn get_conditional<T: serde::de::DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        validators: &Validators,
+    ) -> Result<Conditional<T>> {
+        let mut request = self.request(reqwest::Method::GET, endpoint);
+        if let Some(etag) = &validators.etag {
+            request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &validators.last_modified {
+            request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+        }
+
+        let json = self.json_decoder::<T>();
+        let decode = move |status: reqwest::StatusCode, headers: &reqwest::header::HeaderMap, body: &[u8]| {
+            if status == reqwest::StatusCode::NOT_MODIFIED {
+                return Ok(None);
+            }
+            json(status, headers, body).map(Some)
+        };
+        let (value, headers) = self.execute_with_retry_decoded(request, decode).await?;
+        Ok(match value {
+            Some(value) => Conditional::Modified {
+                value,
+                validators: Validators::from_headers(&from_reqwest_headers(&headers)),
+            },
+            None => Conditional::NotModified,
+        })
+    }
+
+    /// Build the cache key for a GET endpoint
+    fn cache_key(endpoint: &str) -> String {
+        format!("GET:{}", endpoint)
+    }
+
     /// Execute a POST request with a body
     pub async fn post<T: serde::de::DeserializeOwned, B: serde::Serialize + Sync>(
         &self,
         endpoint: &str,
         body: &B,
     ) -> Result<T> {
-        let request = self.request(reqwest::Method::POST, endpoint).json(&body);
+        let request = self.json_request(reqwest::Method::POST, endpoint, body)?;
+        self.execute_with_retry(request).await
+    }
+
+    /// Execute a POST request with a body and extra headers
+    pub async fn post_with_headers<T: serde::de::DeserializeOwned, B: serde::Serialize + Sync>(
+        &self,
+        endpoint: &str,
+        body: &B,
+        headers: http::HeaderMap,
+    ) -> Result<T> {
+        let request = self
+            .json_request(reqwest::Method::POST, endpoint, body)?
+            .headers(to_reqwest_headers(&headers));
         self.execute_with_retry(request).await
     }
 
@@ -271,7 +902,17 @@ impl HttpClient {
         endpoint: &str,
         body: &B,
     ) -> Result<T> {
-        let request = self.request(reqwest::Method::PUT, endpoint).json(&body);
+        let request = self.json_request(reqwest::Method::PUT, endpoint, body)?;
+        self.execute_with_retry(request).await
+    }
+
+    /// Execute a PATCH request with a body
+    pub async fn patch<T: serde::de::DeserializeOwned, B: serde::Serialize + Sync>(
+        &self,
+        endpoint: &str,
+        body: &B,
+    ) -> Result<T> {
+        let request = self.json_request(reqwest::Method::PATCH, endpoint, body)?;
         self.execute_with_retry(request).await
     }
 
@@ -281,25 +922,117 @@ impl HttpClient {
         self.execute_with_retry(request).await
     }
 
+    /// Execute a GET request and return the body as text, without JSON parsing
+    ///
+    /// For endpoints returning plain text or CSV. Retries and metrics apply
+    /// as for other requests; responses are not cached.
+    pub async fn get_text(&self, endpoint: &str) -> Result<String> {
+        let request = self.request(reqwest::Method::GET, endpoint);
+        self.execute_with_retry_decoded(request, |status, _, body| decode_text(status, body))
+            .await
+            .map(|(text, _)| text)
+    }
+
+    /// Execute a GET request and return the raw body bytes
+    ///
+    /// Retries and metrics apply as for other requests; responses are not
+    /// cached.
+    pub async fn get_bytes(&self, endpoint: &str) -> Result<Vec<u8>> {
+        let request = self.request(reqwest::Method::GET, endpoint);
+        self.execute_with_retry_decoded(request, |_, _, body| Ok(body.to_vec()))
+            .await
+            .map(|(bytes, _)| bytes)
+    }
+
+    /// Execute a GET request and deserialize a JSON array response element by element
+    ///
+    /// Items are yielded as soon as they are received instead of after the
+    /// whole body has been buffered. The request is sent when the stream is
+    /// first polled and is not retried.
+    pub fn get_stream<T>(self: Arc<Self>, endpoint: &str) -> BoxStream<'static, Result<T>>
+    where
+        T: serde::de::DeserializeOwned + Send + 'static,
+    {
+        let request = self.request(reqwest::Method::GET, endpoint);
+        let cancelled = self.cancellation.subscribe();
+        let items = stream::once(async move {
+            let response = self.send(request).await?;
+            if !response.status().is_success() {
+                return Err(Self::error_from_response(response).await);
+            }
+            Ok(json_array_stream(response.bytes_stream()))
+        })
+        .flat_map(|result| match result {
+            Ok(items) => items,
+            Err(e) => stream::once(future::ready(Err(e))).boxed(),
+        })
+        .boxed();
+
+        // A cancel ends the stream with a final `Cancelled` item
+        stream::unfold(Some((items, cancelled)), |state| async move {
+            let (mut items, mut cancelled) = state?;
+            tokio::select! {
+                item = items.next() => item.map(|item| (item, Some((items, cancelled)))),
+                _ = cancelled.changed() => Some((Err(SecureNotifyError::Cancelled), None)),
+            }
+        })
+        .boxed()
+    }
+
+    /// Execute a long-poll GET request that the server may hold open for `wait`
+    ///
+    /// The request timeout is extended by `wait`. Responses are neither
+    /// cached nor retried. Like SSE streams, long polls carry subscriptions
+    /// and are not aborted by `cancel_all_requests`.
+    pub async fn long_poll<T: serde::de::DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        wait: Duration,
+    ) -> Result<T> {
+        let request = self
+            .request(reqwest::Method::GET, endpoint)
+            .timeout(self.config.timeout + wait);
+        let response = self.send(request).await?;
+        self.handle_response(response, self.json_decoder()).await.map(|(value, _)| value)
+    }
+
+    /// Open a pooled connection to the server ahead of real traffic
+    ///
+    /// Sends a single HEAD request without retries. Any response means the
+    /// connection (and TLS session) is established, so the status is ignored
+    /// unless `strict_warm_up` is set.
+    pub async fn warm_up(&self) -> Result<()> {
+        let request = self.request(reqwest::Method::HEAD, &self.config.warm_up_path);
+        self.cancellable(async {
+            let response = self.send(request).await?;
+
+            if self.config.strict_warm_up && !response.status().is_success() {
+                return Err(Self::error_from_response(response).await);
+            }
+            Ok(())
+        })
+        .await
+    }
+
     /// Execute a POST request that returns no body
     pub async fn post_empty(&self, endpoint: &str) -> Result<()> {
         let request = self.request(reqwest::Method::POST, endpoint);
+        self.cancellable(self.send_no_content(request)).await
+    }
 
-        match request.send().await {
-            Ok(response) => {
-                if response.status().is_success() {
-                    Ok(())
-                } else {
-                    let status = response.status();
-                    let error_text = response.text().await.unwrap_or_default();
-                    Err(SecureNotifyError::ApiError {
-                        code: status.as_u16().to_string(),
-                        message: error_text,
-                        status: status.as_u16(),
-                    })
-                }
-            }
-            Err(e) => Err(e.into()),
+    /// Execute a POST request with a body, ignoring the response body
+    pub async fn post_no_content<B: serde::Serialize + Sync>(&self, endpoint: &str, body: &B) -> Result<()> {
+        let request = self.json_request(reqwest::Method::POST, endpoint, body)?;
+        self.cancellable(self.send_no_content(request)).await
+    }
+
+    /// Send a request once, discarding the body of a successful response
+    async fn send_no_content(&self, request: reqwest::RequestBuilder) -> Result<()> {
+        let response = self.send(request).await?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(Self::error_from_response(response).await)
         }
     }
 
@@ -332,6 +1065,15 @@ impl HttpClient {
         self.metrics_collector.as_ref().and_then(|mc| mc.get_stats(endpoint))
     }
 
+    /// Get metrics across all endpoints, grouped by the value of a request tag
+    ///
+    /// # Returns
+    /// * `Some(stats)` - HashMap of tag value -> MetricStats
+    /// * `None` - Metrics are not enabled
+    pub fn get_metrics_by_tag(&self, key: &str) -> Option<std::collections::HashMap<String, super::metrics::MetricStats>> {
+        self.metrics_collector.as_ref().map(|mc| mc.get_stats_by_tag(key))
+    }
+
     /// Reset all metrics
     ///
     /// This clears all collected metric data. Useful for testing or periodic resets.
@@ -341,6 +1083,24 @@ impl HttpClient {
         }
     }
 
+    /// Render metrics in Prometheus text exposition format
+    ///
+    /// # Returns
+    /// * `Some(text)` - Metrics ready to be served to a Prometheus scraper
+    /// * `None` - Metrics are not enabled
+    pub fn export_prometheus(&self) -> Option<String> {
+        self.metrics_collector.as_ref().map(|mc| mc.export_prometheus())
+    }
+
+    /// Subscribe to request metric samples as they are recorded
+    ///
+    /// # Returns
+    /// * `Some(receiver)` - Lossy feed of new samples
+    /// * `None` - Metrics are not enabled
+    pub fn subscribe_metrics(&self) -> Option<tokio::sync::broadcast::Receiver<super::metrics::MetricSample>> {
+        self.metrics_collector.as_ref().map(|mc| mc.subscribe())
+    }
+
     /// Check if metrics collection is enabled
     pub fn metrics_enabled(&self) -> bool {
         self.metrics_collector.is_some()
@@ -355,6 +1115,21 @@ impl HttpClient {
         }
     }
 
+    /// Export the cached responses for persisting across restarts
+    ///
+    /// Returns `None` if the cache is disabled.
+    pub fn export_cache(&self) -> Option<CacheSnapshot<String>> {
+        self.cache.as_ref().map(|c| c.export())
+    }
+
+    /// Load cached responses from a snapshot, skipping expired entries
+    ///
+    /// # Returns
+    /// Number of entries imported (0 if the cache is disabled)
+    pub fn import_cache(&self, snapshot: CacheSnapshot<String>) -> usize {
+        self.cache.as_ref().map(|c| c.import(snapshot)).unwrap_or(0)
+    }
+
     /// Remove expired cache entries
     ///
     /// # Returns
@@ -371,6 +1146,14 @@ impl HttpClient {
         self.cache.as_ref().map(|c| c.size()).unwrap_or(0)
     }
 
+    /// Get the remaining time to live of a cached GET response
+    ///
+    /// # Returns
+    /// Remaining TTL, or None if the endpoint is not cached or cache is disabled
+    pub fn get_cache_ttl(&self, endpoint: &str) -> Option<Duration> {
+        self.cache.as_ref().and_then(|c| c.ttl(&Self::cache_key(endpoint)))
+    }
+
     /// Get cache metrics
     ///
     /// # Returns
@@ -476,6 +1259,150 @@ impl HttpClient {
         }
     }
 
+    // Rate limiting methods
+
+    /// Wait for a publish permit for the given message priority
+    ///
+    /// Returns immediately when no limit is configured for the priority.
+    pub async fn acquire_publish_permit(&self, priority: MessagePriority) {
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire(priority).await;
+        }
+    }
+
+    /// Wait for the channel's turn to publish when ordered publishing is enabled
+    ///
+    /// Hold the returned guard until the publish, including retries, has
+    /// finished. Returns `None` immediately when ordering is disabled.
+    pub async fn acquire_publish_order(&self, channel: &str) -> Option<tokio::sync::OwnedMutexGuard<()>> {
+        match &self.publish_sequencer {
+            Some(sequencer) => Some(sequencer.lock(channel).await),
+            None => None,
+        }
+    }
+
+    /// Send a publish unless an identical one succeeded within the dedup window
+    ///
+    /// Returns the earlier response for a repeat. Runs `send` directly when
+    /// no dedup window is configured.
+    pub async fn throttle_publish<F>(
+        &self,
+        channel: &str,
+        request: &MessagePublishRequest,
+        send: F,
+    ) -> Result<MessagePublishResponse>
+    where
+        F: std::future::Future<Output = Result<MessagePublishResponse>>,
+    {
+        match &self.publish_throttle {
+            Some(throttle) => throttle.publish(channel, request, send).await,
+            None => send.await,
+        }
+    }
+
+    /// Add quota units for an operation that bypasses the request helpers
+    ///
+    /// Every request sent by this client is counted automatically; this is
+    /// for operations such as opening an SSE subscription.
+    pub fn consume_quota(&self, units: u32) {
+        self.quota_consumed.fetch_add(u64::from(units), Ordering::Relaxed);
+    }
+
+    /// Get the quota units consumed since the client was created
+    pub fn quota_consumed(&self) -> u64 {
+        self.quota_consumed.load(Ordering::Relaxed)
+    }
+
+    /// Get the permissions the API key is known to have, if any
+    ///
+    /// Taken from the `scope` claim of a JWT key, or set with
+    /// `set_known_permissions`.
+    pub fn known_permissions(&self) -> Option<Vec<Permission>> {
+        self.known_permissions.read().unwrap().clone()
+    }
+
+    /// Replace the permissions the API key is known to have
+    ///
+    /// Use `None` when they are unknown; every operation is then sent.
+    pub fn set_known_permissions(&self, permissions: Option<Vec<Permission>>) {
+        *self.known_permissions.write().unwrap() = permissions;
+    }
+
+    /// Abort every request in flight, including any retry backoff
+    ///
+    /// Each resolves with `SecureNotifyError::Cancelled`, and streams from
+    /// `get_stream` end with it. Requests started afterwards are unaffected.
+    /// Subscriptions, over SSE or long polling, are not touched.
+    pub fn cancel_all_requests(&self) {
+        self.cancellation.send_modify(|generation| *generation += 1);
+    }
+
+    /// Run a request, resolving with `Cancelled` if `cancel_all_requests` is called first
+    async fn cancellable<T>(&self, request: impl std::future::Future<Output = Result<T>>) -> Result<T> {
+        // Subscribing marks the current generation as seen, so only later cancels apply
+        let mut cancelled = self.cancellation.subscribe();
+        tokio::select! {
+            result = request => result,
+            _ = cancelled.changed() => Err(SecureNotifyError::Cancelled),
+        }
+    }
+
+    /// Server clock minus local clock, once a response has carried a `Date` header
+    ///
+    /// The `Date` header has one-second resolution, so the offset is only
+    /// accurate to about a second.
+    pub fn clock_offset(&self) -> Option<time::Duration> {
+        *self.clock_offset.read().unwrap()
+    }
+
+    /// Current time on the server's clock, or the local clock if not yet known
+    pub fn server_now(&self) -> time::OffsetDateTime {
+        time::OffsetDateTime::now_utc() + self.clock_offset().unwrap_or(time::Duration::ZERO)
+    }
+
+    /// Update the clock offset from a response's `Date` header
+    fn record_server_date(&self, headers: &reqwest::header::HeaderMap) {
+        let server_date = headers
+            .get(reqwest::header::DATE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(crate::types::error::parse_http_date);
+        if let Some(server_date) = server_date {
+            *self.clock_offset.write().unwrap() = Some(server_date - time::OffsetDateTime::now_utc());
+        }
+    }
+
+    /// Fail fast when the key is known to lack `required`
+    ///
+    /// Only applies with `enforce_local_permissions`. When the permissions
+    /// are unknown the check passes and the server decides.
+    pub fn check_permission(&self, required: Permission) -> Result<()> {
+        if !self.config.enforce_local_permissions {
+            return Ok(());
+        }
+        match &*self.known_permissions.read().unwrap() {
+            Some(granted) if !required.is_granted_by(granted) => Err(SecureNotifyError::AuthError(format!(
+                "API key lacks the `{}` permission",
+                required.as_str()
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Get the registry of subscriptions opened through this client
+    pub fn subscriptions(&self) -> &SubscriptionRegistry {
+        &self.subscriptions
+    }
+
+    /// Check if a publish rate limit is configured for the given priority
+    pub fn is_priority_rate_limited(&self, priority: MessagePriority) -> bool {
+        self.rate_limiter.as_ref().map(|l| l.is_limited(priority)).unwrap_or(false)
+    }
+
+    /// Get the current adaptive concurrency limit, if enabled
+    pub fn concurrency_limit(&self) -> Option<usize> {
+        self.concurrency_limiter.as_ref().map(|l| l.limit())
+    }
+
     /// Check if request deduplication is enabled
     ///
     /// # Returns
@@ -483,4 +1410,98 @@ impl HttpClient {
     pub fn deduplication_enabled(&self) -> bool {
         self.request_deduplicator.is_some()
     }
+
+    /// Get a snapshot of all performance features in one call
+    ///
+    /// # Returns
+    /// Report with metrics, cache and deduplicator data; disabled features are `None`
+    pub async fn performance_report(&self) -> super::metrics::PerformanceReport {
+        let deduplication = match &self.request_deduplicator {
+            Some(dedup) => Some(dedup.get_stats().await),
+            None => None,
+        };
+
+        super::metrics::PerformanceReport {
+            metrics: self.get_metrics_summary(),
+            cache: self.get_cache_metrics(),
+            cache_hit_rate: self.get_cache_hit_rate(),
+            deduplication,
+        }
+    }
+}
+
+/// Deserialize a JSON response body
+///
+/// An empty body reads as `null`, so calls returning `()` or an `Option`
+/// succeed, while calls expecting a value fail with a `SerializationError`
+/// saying the body was empty.
+fn decode_json<T: serde::de::DeserializeOwned>(status: reqwest::StatusCode, body: &[u8]) -> Result<T> {
+    if body.iter().all(u8::is_ascii_whitespace) {
+        serde_json::from_slice(b"null").map_err(|e| {
+            SecureNotifyError::SerializationError(
+                format!("Expected a response body but the server sent an empty {} response", status),
+                Some(ErrorSource::new(e)),
+            )
+        })
+    } else {
+        Ok(serde_json::from_slice(body)?)
+    }
+}
+
+/// Reject a non-empty body whose declared content type is not JSON
+///
+/// Responses without a `Content-Type` header are decoded as usual.
+fn ensure_json_content_type(headers: &reqwest::header::HeaderMap, body: &[u8]) -> Result<()> {
+    if body.iter().all(u8::is_ascii_whitespace) {
+        return Ok(());
+    }
+    let Some(content_type) = headers.get(reqwest::header::CONTENT_TYPE) else {
+        return Ok(());
+    };
+    let content_type = String::from_utf8_lossy(content_type.as_bytes());
+    if crate::types::error::is_json_content_type(&content_type) {
+        return Ok(());
+    }
+    Err(SecureNotifyError::UnexpectedContentType {
+        content_type: content_type.into_owned(),
+        snippet: crate::types::error::body_snippet(body),
+    })
+}
+
+/// Decode a response body as UTF-8 text
+fn decode_text(_status: reqwest::StatusCode, body: &[u8]) -> Result<String> {
+    String::from_utf8(body.to_vec()).map_err(|e| {
+        SecureNotifyError::SerializationError(
+            format!("Response body is not valid UTF-8: {}", e),
+            Some(ErrorSource::new(e)),
+        )
+    })
+}
+
+/// Convert `http` crate headers to the header types used by reqwest
+fn to_reqwest_headers(headers: &http::HeaderMap) -> reqwest::header::HeaderMap {
+    let mut converted = reqwest::header::HeaderMap::with_capacity(headers.len());
+    for (name, value) in headers {
+        if let (Ok(name), Ok(value)) = (
+            reqwest::header::HeaderName::from_bytes(name.as_str().as_bytes()),
+            reqwest::header::HeaderValue::from_bytes(value.as_bytes()),
+        ) {
+            converted.append(name, value);
+        }
+    }
+    converted
+}
+
+/// Convert reqwest headers to `http` crate headers
+fn from_reqwest_headers(headers: &reqwest::header::HeaderMap) -> http::HeaderMap {
+    let mut converted = http::HeaderMap::with_capacity(headers.len());
+    for (name, value) in headers {
+        if let (Ok(name), Ok(value)) = (
+            http::HeaderName::from_bytes(name.as_str().as_bytes()),
+            http::HeaderValue::from_bytes(value.as_bytes()),
+        ) {
+            converted.append(name, value);
+        }
+    }
+    converted
 }
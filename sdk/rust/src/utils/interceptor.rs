@@ -0,0 +1,176 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (c) 2026 KirkyX. All rights reserved.
+
+//! Request interceptors and hooks for SDK operations
+//!
+//! Interceptors work with the framework-agnostic `http` crate types so that
+//! middleware can be shared with other HTTP stacks.
+
+use std::sync::Arc;
+use std::time::Duration;
+use crate::types::error::ErrorKind;
+use crate::SecureNotifyError;
+
+/// Hook invoked for every outgoing request before it is sent
+///
+/// The request parts carry the method, URI and headers as `http` types.
+/// Changes made to them are applied to the request.
+pub trait RequestInterceptor: Send + Sync {
+    /// Inspect or modify the outgoing request
+    fn intercept(&self, parts: &mut http::request::Parts);
+}
+
+impl<F> RequestInterceptor for F
+where
+    F: Fn(&mut http::request::Parts) + Send + Sync,
+{
+    fn intercept(&self, parts: &mut http::request::Parts) {
+        self(parts)
+    }
+}
+
+/// Ordered list of request interceptors
+#[derive(Clone, Default)]
+pub struct InterceptorChain {
+    interceptors: Vec<Arc<dyn RequestInterceptor>>,
+}
+
+impl std::fmt::Debug for InterceptorChain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InterceptorChain")
+            .field("len", &self.interceptors.len())
+            .finish()
+    }
+}
+
+impl InterceptorChain {
+    /// Create an empty chain
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an interceptor to the chain
+    pub fn push(&mut self, interceptor: Arc<dyn RequestInterceptor>) {
+        self.interceptors.push(interceptor);
+    }
+
+    /// Check if the chain has no interceptors
+    pub fn is_empty(&self) -> bool {
+        self.interceptors.is_empty()
+    }
+
+    /// Get the number of interceptors
+    pub fn len(&self) -> usize {
+        self.interceptors.len()
+    }
+
+    /// Run every interceptor in order
+    pub fn apply(&self, parts: &mut http::request::Parts) {
+        for interceptor in &self.interceptors {
+            interceptor.intercept(parts);
+        }
+    }
+}
+
+/// Outcome of a completed logical request, including all retries
+#[derive(Debug, Clone)]
+pub struct RequestOutcome {
+    /// HTTP method
+    pub method: http::Method,
+    /// Request path
+    pub endpoint: String,
+    /// Status code of the last response, if one was received
+    pub status: Option<u16>,
+    /// Total duration including retries
+    pub duration: Duration,
+    /// Number of attempts made
+    pub attempts: u32,
+    /// Kind of the final error, if the request failed
+    pub error_kind: Option<ErrorKind>,
+    /// Trace id sent in the `traceparent` header, when trace propagation is enabled
+    pub trace_id: Option<String>,
+}
+
+impl RequestOutcome {
+    /// Check if the request succeeded
+    pub fn is_success(&self) -> bool {
+        self.error_kind.is_none()
+    }
+}
+
+/// Callback invoked once per completed logical request
+#[derive(Clone)]
+pub struct RequestCompleteHook(pub Arc<dyn Fn(&RequestOutcome) + Send + Sync>);
+
+impl std::fmt::Debug for RequestCompleteHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("RequestCompleteHook")
+    }
+}
+
+impl RequestCompleteHook {
+    /// Invoke the callback
+    pub fn call(&self, outcome: &RequestOutcome) {
+        (self.0)(outcome)
+    }
+}
+
+/// Retries-exhausted callback, receiving the endpoint path, the error of
+/// the final attempt and the number of attempts made
+pub type RetriesExhaustedFn = dyn Fn(&str, &SecureNotifyError, u32) + Send + Sync;
+
+/// Callback invoked when a request fails after exhausting its retries
+#[derive(Clone)]
+pub struct RetriesExhaustedHook(pub Arc<RetriesExhaustedFn>);
+
+impl std::fmt::Debug for RetriesExhaustedHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("RetriesExhaustedHook")
+    }
+}
+
+impl RetriesExhaustedHook {
+    /// Invoke the callback
+    pub fn call(&self, endpoint: &str, error: &SecureNotifyError, attempts: u32) {
+        (self.0)(endpoint, error, attempts)
+    }
+}
+
+/// Source of `X-Request-Id` values, called once per logical request
+#[derive(Clone)]
+pub struct RequestIdGenerator(pub Arc<dyn Fn() -> String + Send + Sync>);
+
+impl std::fmt::Debug for RequestIdGenerator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("RequestIdGenerator")
+    }
+}
+
+impl RequestIdGenerator {
+    /// Generate the next request ID
+    pub fn generate(&self) -> String {
+        (self.0)()
+    }
+}
+
+/// Metadata validation callback, returning the rejection reason on failure
+pub type MetadataValidatorFn = dyn Fn(&serde_json::Value) -> std::result::Result<(), String> + Send + Sync;
+
+/// Validator run on user-supplied metadata before it is sent
+#[derive(Clone)]
+pub struct MetadataValidator(pub Arc<MetadataValidatorFn>);
+
+impl std::fmt::Debug for MetadataValidator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("MetadataValidator")
+    }
+}
+
+impl MetadataValidator {
+    /// Validate metadata, returning a `ValidationError` if it is rejected
+    pub fn validate(&self, metadata: &serde_json::Value) -> crate::Result<()> {
+        (self.0)(metadata).map_err(|reason| {
+            crate::SecureNotifyError::ValidationError(format!("Invalid metadata: {}", reason))
+        })
+    }
+}
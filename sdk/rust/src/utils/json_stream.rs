@@ -0,0 +1,191 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (c) 2026 KirkyX. All rights reserved.
+
+//! Incremental parsing of JSON array responses
+
+use futures::stream::{self, BoxStream, Stream, StreamExt};
+use crate::{SecureNotifyError, Result};
+
+/// Position of the parser within the top-level array
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArrayState {
+    /// Waiting for the opening `[`
+    Start,
+    /// After `[`, before the first element or `]`
+    First,
+    /// After a `,`, expecting an element
+    Element,
+    /// After an element, expecting `,` or `]`
+    Separator,
+    /// After the closing `]`
+    Done,
+}
+
+/// Splits a JSON array arriving in chunks into its raw elements
+///
+/// Only the bytes of the element currently being received are buffered, so
+/// memory use is bounded by the largest element rather than the whole array.
+#[derive(Debug)]
+pub struct JsonArrayParser {
+    buffer: Vec<u8>,
+    pos: usize,
+    state: ArrayState,
+}
+
+impl Default for JsonArrayParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JsonArrayParser {
+    /// Create a new parser
+    pub fn new() -> Self {
+        Self {
+            buffer: Vec::new(),
+            pos: 0,
+            state: ArrayState::Start,
+        }
+    }
+
+    /// Append received bytes
+    pub fn push(&mut self, chunk: &[u8]) {
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    /// Take the next complete element, if one has been received
+    pub fn next_element(&mut self) -> Result<Option<Vec<u8>>> {
+        loop {
+            while self.pos < self.buffer.len() && self.buffer[self.pos].is_ascii_whitespace() {
+                self.pos += 1;
+            }
+            let Some(&byte) = self.buffer.get(self.pos) else {
+                return Ok(None);
+            };
+
+            match self.state {
+                ArrayState::Start if byte == b'[' => {
+                    self.pos += 1;
+                    self.state = ArrayState::First;
+                }
+                ArrayState::Start => return Err(Self::error("expected a JSON array")),
+                ArrayState::First if byte == b']' => {
+                    self.pos += 1;
+                    self.state = ArrayState::Done;
+                }
+                ArrayState::First | ArrayState::Element => {
+                    if byte == b',' || byte == b']' {
+                        return Err(Self::error("expected an array element"));
+                    }
+                    let Some(end) = self.element_end() else {
+                        return Ok(None);
+                    };
+                    let element = self.buffer[self.pos..end].to_vec();
+                    self.buffer.drain(..end);
+                    self.pos = 0;
+                    self.state = ArrayState::Separator;
+                    return Ok(Some(element));
+                }
+                ArrayState::Separator if byte == b',' => {
+                    self.pos += 1;
+                    self.state = ArrayState::Element;
+                }
+                ArrayState::Separator if byte == b']' => {
+                    self.pos += 1;
+                    self.state = ArrayState::Done;
+                }
+                ArrayState::Separator => return Err(Self::error("expected ',' or ']'")),
+                ArrayState::Done => return Ok(None),
+            }
+        }
+    }
+
+    /// Check that the array was closed once the body has ended
+    pub fn finish(&self) -> Result<()> {
+        if self.state == ArrayState::Done {
+            Ok(())
+        } else {
+            Err(Self::error("unexpected end of JSON array"))
+        }
+    }
+
+    /// Find the end of the element starting at `pos`, if it is complete
+    fn element_end(&self) -> Option<usize> {
+        let bytes = &self.buffer[self.pos..];
+        let mut depth = 0usize;
+        let mut in_string = false;
+        let mut escaped = false;
+
+        for (i, &byte) in bytes.iter().enumerate() {
+            if in_string {
+                if escaped {
+                    escaped = false;
+                } else if byte == b'\\' {
+                    escaped = true;
+                } else if byte == b'"' {
+                    in_string = false;
+                    if depth == 0 {
+                        return Some(self.pos + i + 1);
+                    }
+                }
+                continue;
+            }
+
+            match byte {
+                b'"' => in_string = true,
+                b'{' | b'[' => depth += 1,
+                b'}' | b']' if depth > 0 => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(self.pos + i + 1);
+                    }
+                }
+                b',' | b']' if depth == 0 => return Some(self.pos + i),
+                byte if depth == 0 && byte.is_ascii_whitespace() => return Some(self.pos + i),
+                _ => {}
+            }
+        }
+        None
+    }
+
+    fn error(message: &str) -> SecureNotifyError {
+        SecureNotifyError::SerializationError(format!("Invalid JSON array stream: {}", message), None)
+    }
+}
+
+/// Deserialize the elements of a JSON array body as they arrive
+pub fn json_array_stream<T, S, B, E>(body: S) -> BoxStream<'static, Result<T>>
+where
+    T: serde::de::DeserializeOwned + Send + 'static,
+    S: Stream<Item = std::result::Result<B, E>> + Send + 'static,
+    B: AsRef<[u8]> + Send + 'static,
+    E: Into<SecureNotifyError> + Send + 'static,
+{
+    stream::unfold(
+        (body.boxed(), JsonArrayParser::new(), false),
+        |(mut body, mut parser, failed)| async move {
+            if failed {
+                return None;
+            }
+            loop {
+                match parser.next_element() {
+                    Ok(Some(element)) => {
+                        let item = serde_json::from_slice(&element).map_err(SecureNotifyError::from);
+                        return Some((item, (body, parser, false)));
+                    }
+                    Ok(None) => {}
+                    Err(e) => return Some((Err(e), (body, parser, true))),
+                }
+
+                match body.next().await {
+                    Some(Ok(chunk)) => parser.push(chunk.as_ref()),
+                    Some(Err(e)) => return Some((Err(e.into()), (body, parser, true))),
+                    None => {
+                        return parser.finish().err().map(|e| (Err(e), (body, parser, true)));
+                    }
+                }
+            }
+        },
+    )
+    .boxed()
+}
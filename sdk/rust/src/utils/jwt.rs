@@ -0,0 +1,115 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (c) 2026 KirkyX. All rights reserved.
+
+//! Local decoding of JWT API keys
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use crate::{Result, SecureNotifyError};
+use crate::types::api::Permission;
+
+/// Claims carried by a JWT API key
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiKeyClaims {
+    /// Expiry, in seconds since the Unix epoch
+    #[serde(default)]
+    pub exp: Option<i64>,
+    /// Space-separated scopes granted to the key
+    #[serde(default)]
+    pub scope: Option<String>,
+    /// Subject the key was issued to
+    #[serde(default)]
+    pub sub: Option<String>,
+}
+
+/// JWT header fields needed for verification
+#[derive(Debug, Deserialize)]
+struct JwtHeader {
+    alg: String,
+}
+
+impl ApiKeyClaims {
+    /// Decode the claims of a JWT without checking its signature
+    ///
+    /// Returns a `ValidationError` if the key is not a well-formed JWT.
+    pub fn decode(token: &str) -> Result<Self> {
+        let (_, _, payload, _) = Self::split(token)?;
+        Self::decode_segment(payload, "payload")
+    }
+
+    /// Decode the claims of a JWT after verifying its HS256 signature
+    ///
+    /// Returns an `AuthError` if the signature does not match `key`, and a
+    /// `ValidationError` for malformed tokens or other algorithms.
+    pub fn decode_verified(token: &str, key: &[u8]) -> Result<Self> {
+        let (signing_input, header, payload, signature) = Self::split(token)?;
+        let header: JwtHeader = Self::decode_segment(header, "header")?;
+        if header.alg != "HS256" {
+            return Err(SecureNotifyError::ValidationError(format!(
+                "Unsupported JWT algorithm `{}`; only HS256 can be verified",
+                header.alg
+            )));
+        }
+
+        let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| {
+            SecureNotifyError::ValidationError("API key JWT signature is not valid base64url".to_string())
+        })?;
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+        mac.update(signing_input.as_bytes());
+        mac.verify_slice(&signature)
+            .map_err(|_| SecureNotifyError::AuthError("API key JWT signature is invalid".to_string()))?;
+
+        Self::decode_segment(payload, "payload")
+    }
+
+    /// Check whether the key has expired
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(time::OffsetDateTime::now_utc().unix_timestamp())
+    }
+
+    /// Check whether the key is expired at the given Unix time
+    pub fn is_expired_at(&self, unix_seconds: i64) -> bool {
+        self.exp.is_some_and(|exp| exp <= unix_seconds)
+    }
+
+    /// Iterate over the granted scopes
+    pub fn scopes(&self) -> impl Iterator<Item = &str> {
+        self.scope.as_deref().unwrap_or_default().split_whitespace()
+    }
+
+    /// Get the recognized permissions among the granted scopes
+    ///
+    /// Returns `None` when the key carries no `scope` claim.
+    pub fn permissions(&self) -> Option<Vec<Permission>> {
+        self.scope.as_ref()?;
+        Some(self.scopes().filter_map(Permission::parse).collect())
+    }
+
+    /// Split a token into its signing input and three segments
+    fn split(token: &str) -> Result<(&str, &str, &str, &str)> {
+        let not_jwt = || {
+            SecureNotifyError::ValidationError(
+                "API key is not a JWT: expected three dot-separated segments".to_string(),
+            )
+        };
+        let (signing_input, signature) = token.rsplit_once('.').ok_or_else(not_jwt)?;
+        let (header, payload) = signing_input.split_once('.').ok_or_else(not_jwt)?;
+        if header.is_empty() || payload.is_empty() || payload.contains('.') {
+            return Err(not_jwt());
+        }
+        Ok((signing_input, header, payload, signature))
+    }
+
+    /// Decode a base64url JSON segment
+    fn decode_segment<T: serde::de::DeserializeOwned>(segment: &str, name: &str) -> Result<T> {
+        let bytes = URL_SAFE_NO_PAD.decode(segment).map_err(|_| {
+            SecureNotifyError::ValidationError(format!("API key JWT {} is not valid base64url", name))
+        })?;
+        serde_json::from_slice(&bytes).map_err(|e| {
+            SecureNotifyError::ValidationError(format!("API key JWT {} is not valid JSON: {}", name, e))
+        })
+    }
+}
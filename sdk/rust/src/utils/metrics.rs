@@ -4,8 +4,13 @@
 //! Performance metrics collector for SDK operations.
 
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::Instant;
+use serde::{Serialize, Deserialize};
+use tokio::sync::broadcast;
+use super::cache::CacheMetrics;
+use super::request_deduplicator::DeduplicatorStats;
 
 /// A single metric sample
 #[derive(Debug, Clone)]
@@ -14,6 +19,8 @@ pub struct MetricSample {
     pub duration_ms: f64,
     pub success: bool,
     pub endpoint: String,
+    /// Tags from the `RequestOptions` the request was made with
+    pub tags: HashMap<String, String>,
 }
 
 /// Statistics for a metric
@@ -90,7 +97,7 @@ impl Default for MetricStats {
 }
 
 /// Metrics summary
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetricsSummary {
     pub total_requests: u64,
     pub total_success: u64,
@@ -99,10 +106,36 @@ pub struct MetricsSummary {
     pub endpoint_count: usize,
 }
 
+/// Snapshot of every performance feature in a single report
+///
+/// Disabled features are reported as `None`; the cache hit rate is 0.0
+/// when the cache is disabled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformanceReport {
+    /// Request metrics summary
+    pub metrics: Option<MetricsSummary>,
+    /// Response cache metrics
+    pub cache: Option<CacheMetrics>,
+    /// Response cache hit rate (0.0 - 1.0)
+    pub cache_hit_rate: f64,
+    /// Request deduplicator statistics
+    pub deduplication: Option<DeduplicatorStats>,
+}
+
+/// Samples buffered in a shard before they are merged into the collector
+const SHARD_FLUSH_THRESHOLD: usize = 64;
+
+/// Samples a metrics subscriber may fall behind by before it misses some
+const SUBSCRIBER_CAPACITY: usize = 256;
+
 /// Performance metrics collector
 pub struct MetricsCollector {
     max_samples: usize,
     samples: Arc<RwLock<HashMap<String, Vec<MetricSample>>>>,
+    /// Per-thread sample buffers; empty when recording goes straight to `samples`
+    shards: Vec<Mutex<Vec<MetricSample>>>,
+    /// Live feed of recorded samples
+    updates: broadcast::Sender<MetricSample>,
 }
 
 impl MetricsCollector {
@@ -111,35 +144,122 @@ impl MetricsCollector {
         Self {
             max_samples,
             samples: Arc::new(RwLock::new(HashMap::new())),
+            shards: Vec::new(),
+            updates: broadcast::channel(SUBSCRIBER_CAPACITY).0,
         }
     }
 
-    /// Create a metrics collector with default max samples (1000)
-    pub fn default() -> Self {
-        Self::new(1000)
+    /// Create a metrics collector that buffers samples in `shards` shards
+    ///
+    /// Each recording thread writes to one shard, so concurrent recorders
+    /// rarely contend. A shard is merged into the collector once it holds
+    /// a batch of samples, and every shard is merged before statistics are
+    /// read, so reported figures are the same as for an unsharded collector.
+    /// Keeps the default 1000 samples per endpoint.
+    pub fn new_sharded(shards: usize) -> Self {
+        Self {
+            shards: (0..shards.max(1)).map(|_| Mutex::new(Vec::new())).collect(),
+            ..Self::default()
+        }
     }
 
     /// Record a metric sample
     pub fn record(&self, endpoint: &str, duration_ms: f64, success: bool) {
+        self.record_tagged(endpoint, duration_ms, success, HashMap::new());
+    }
+
+    /// Record a metric sample labeled with tags
+    pub fn record_tagged(&self, endpoint: &str, duration_ms: f64, success: bool, tags: HashMap<String, String>) {
         let sample = MetricSample {
             timestamp: Instant::now(),
             duration_ms,
             success,
             endpoint: endpoint.to_string(),
+            tags,
         };
+        if self.updates.receiver_count() > 0 {
+            // Never blocks; subscribers that lag miss the oldest samples
+            let _ = self.updates.send(sample.clone());
+        }
+
+        if self.shards.is_empty() {
+            let mut samples = self.samples.write().unwrap();
+            let entry = samples.entry(endpoint.to_string()).or_default();
+            entry.push(sample);
+
+            // Trim to max samples
+            while entry.len() > self.max_samples {
+                entry.remove(0);
+            }
+            return;
+        }
+
+        let batch = {
+            let mut shard = self.current_shard().lock().unwrap();
+            shard.push(sample);
+            if shard.len() < SHARD_FLUSH_THRESHOLD {
+                return;
+            }
+            std::mem::take(&mut *shard)
+        };
+        self.merge(batch);
+    }
+
+    /// Receive each sample as it is recorded, for live dashboards
+    ///
+    /// Delivery is lossy so recording never waits: a subscriber more than
+    /// 256 samples behind gets `RecvError::Lagged` and continues with the
+    /// newest samples. Only samples recorded after subscribing are sent.
+    pub fn subscribe(&self) -> broadcast::Receiver<MetricSample> {
+        self.updates.subscribe()
+    }
+
+    /// Get the shard used by the calling thread
+    fn current_shard(&self) -> &Mutex<Vec<MetricSample>> {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        std::thread::current().id().hash(&mut hasher);
+        &self.shards[hasher.finish() as usize % self.shards.len()]
+    }
+
+    /// Merge every shard's buffered samples into the collector
+    fn flush(&self) {
+        for shard in &self.shards {
+            let batch = std::mem::take(&mut *shard.lock().unwrap());
+            if !batch.is_empty() {
+                self.merge(batch);
+            }
+        }
+    }
+
+    /// Add samples to their endpoints, keeping the newest `max_samples` of each
+    fn merge(&self, batch: Vec<MetricSample>) {
+        let mut by_endpoint: HashMap<String, Vec<MetricSample>> = HashMap::new();
+        for sample in batch {
+            by_endpoint.entry(sample.endpoint.clone()).or_default().push(sample);
+        }
 
         let mut samples = self.samples.write().unwrap();
-        let entry = samples.entry(endpoint.to_string()).or_insert_with(Vec::new);
-        entry.push(sample);
+        for (endpoint, batch) in by_endpoint {
+            let entry = samples.entry(endpoint).or_default();
+            // Batches from different shards can overlap in time
+            let in_order = match (entry.last(), batch.first()) {
+                (Some(last), Some(first)) => last.timestamp <= first.timestamp,
+                _ => true,
+            };
+            entry.extend(batch);
+            if !in_order {
+                entry.sort_by_key(|sample| sample.timestamp);
+            }
 
-        // Trim to max samples
-        while entry.len() > self.max_samples {
-            entry.remove(0);
+            // Trim to max samples
+            let excess = entry.len().saturating_sub(self.max_samples);
+            entry.drain(..excess);
         }
     }
 
     /// Get statistics for an endpoint
     pub fn get_stats(&self, endpoint: &str) -> Option<MetricStats> {
+        self.flush();
         let samples = self.samples.read().unwrap();
         let entry = samples.get(endpoint)?;
 
@@ -157,6 +277,7 @@ impl MetricsCollector {
 
     /// Get statistics for all endpoints
     pub fn get_all_stats(&self) -> HashMap<String, MetricStats> {
+        self.flush();
         let samples = self.samples.read().unwrap();
         let mut result = HashMap::new();
 
@@ -174,6 +295,32 @@ impl MetricsCollector {
         result
     }
 
+    /// Get statistics across all endpoints, grouped by the value of tag `key`
+    ///
+    /// Samples without the tag are left out.
+    pub fn get_stats_by_tag(&self, key: &str) -> HashMap<String, MetricStats> {
+        self.flush();
+        let samples = self.samples.read().unwrap();
+        let mut groups: HashMap<String, Vec<MetricSample>> = HashMap::new();
+        for sample in samples.values().flatten() {
+            if let Some(value) = sample.tags.get(key) {
+                groups.entry(value.clone()).or_default().push(sample.clone());
+            }
+        }
+
+        groups
+            .into_iter()
+            .map(|(value, group)| {
+                let mut stats = MetricStats::new();
+                for sample in &group {
+                    stats.add_sample(sample);
+                }
+                stats.calculate_percentiles(&group);
+                (value, stats)
+            })
+            .collect()
+    }
+
     /// Get a summary of all metrics
     pub fn get_summary(&self) -> MetricsSummary {
         let all_stats = self.get_all_stats();
@@ -202,9 +349,120 @@ impl MetricsCollector {
 
     /// Reset all metrics
     pub fn reset(&self) {
+        for shard in &self.shards {
+            shard.lock().unwrap().clear();
+        }
         let mut samples = self.samples.write().unwrap();
         samples.clear();
     }
+
+    /// Render the current metrics in Prometheus text exposition format
+    ///
+    /// Totals are exported as counters, the success rate as a gauge and
+    /// request durations as a summary with p50/p95/p99 quantiles, each
+    /// labeled by endpoint. Endpoints are sorted for stable output.
+    pub fn export_prometheus(&self) -> String {
+        use std::fmt::Write;
+
+        let summary = self.get_summary();
+        let mut all_stats: Vec<_> = self.get_all_stats().into_iter().collect();
+        all_stats.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut out = String::new();
+        let mut family = |name: &str, kind: &str, help: &str, lines: Vec<String>| {
+            let _ = writeln!(out, "# HELP {} {}", name, help);
+            let _ = writeln!(out, "# TYPE {} {}", name, kind);
+            for line in lines {
+                let _ = writeln!(out, "{}", line);
+            }
+        };
+        let per_endpoint = |name: &str, value: fn(&MetricStats) -> f64| -> Vec<String> {
+            all_stats
+                .iter()
+                .map(|(endpoint, stats)| {
+                    format!("{}{{endpoint=\"{}\"}} {}", name, escape_label(endpoint), value(stats))
+                })
+                .collect()
+        };
+
+        family(
+            "securenotify_requests_total",
+            "counter",
+            "Requests recorded across all endpoints.",
+            vec![format!("securenotify_requests_total {}", summary.total_requests)],
+        );
+        family(
+            "securenotify_requests_failed_total",
+            "counter",
+            "Failed requests recorded across all endpoints.",
+            vec![format!("securenotify_requests_failed_total {}", summary.total_failures)],
+        );
+        family(
+            "securenotify_endpoint_requests_total",
+            "counter",
+            "Requests recorded per endpoint.",
+            per_endpoint("securenotify_endpoint_requests_total", |s| s.count as f64),
+        );
+        family(
+            "securenotify_endpoint_requests_failed_total",
+            "counter",
+            "Failed requests recorded per endpoint.",
+            per_endpoint("securenotify_endpoint_requests_failed_total", |s| s.failure_count as f64),
+        );
+        family(
+            "securenotify_endpoint_success_ratio",
+            "gauge",
+            "Share of successful requests per endpoint (0-1).",
+            per_endpoint("securenotify_endpoint_success_ratio", |s| s.success_rate()),
+        );
+
+        let mut durations = Vec::new();
+        for (endpoint, stats) in &all_stats {
+            let endpoint = escape_label(endpoint);
+            for (quantile, value) in [
+                ("0.5", stats.p50_duration_ms),
+                ("0.95", stats.p95_duration_ms),
+                ("0.99", stats.p99_duration_ms),
+            ] {
+                durations.push(format!(
+                    "securenotify_request_duration_ms{{endpoint=\"{}\",quantile=\"{}\"}} {}",
+                    endpoint, quantile, value
+                ));
+            }
+            durations.push(format!(
+                "securenotify_request_duration_ms_sum{{endpoint=\"{}\"}} {}",
+                endpoint,
+                stats.avg_duration_ms * stats.count as f64
+            ));
+            durations.push(format!(
+                "securenotify_request_duration_ms_count{{endpoint=\"{}\"}} {}",
+                endpoint, stats.count
+            ));
+        }
+        family(
+            "securenotify_request_duration_ms",
+            "summary",
+            "Request duration in milliseconds per endpoint.",
+            durations,
+        );
+
+        out
+    }
+}
+
+/// Escape a Prometheus label value
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+impl Default for MetricsCollector {
+    /// Create a metrics collector with default max samples (1000)
+    fn default() -> Self {
+        Self::new(1000)
+    }
 }
 
 /// Context manager for measuring operation duration
@@ -213,6 +471,7 @@ pub struct MetricsContext<'a> {
     endpoint: String,
     start_time: Instant,
     success: bool,
+    tags: HashMap<String, String>,
 }
 
 impl<'a> MetricsContext<'a> {
@@ -223,24 +482,33 @@ impl<'a> MetricsContext<'a> {
             endpoint: endpoint.to_string(),
             start_time: Instant::now(),
             success: false,
+            tags: HashMap::new(),
         }
     }
 
+    /// Label the sample with tags
+    pub fn with_tags(mut self, tags: HashMap<String, String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
     /// Mark the operation as successful
     pub fn mark_success(&mut self) {
         self.success = true;
     }
 
     /// Record the metric
+    ///
+    /// Consumes the context; the sample is written exactly once by `Drop`.
     pub fn record(self) {
-        let duration_ms = self.start_time.elapsed().as_secs_f64() * 1000.0;
-        self.collector.record(&self.endpoint, duration_ms, self.success);
+        drop(self);
     }
 }
 
 impl<'a> Drop for MetricsContext<'a> {
     fn drop(&mut self) {
         let duration_ms = self.start_time.elapsed().as_secs_f64() * 1000.0;
-        self.collector.record(&self.endpoint, duration_ms, self.success);
+        self.collector
+            .record_tagged(&self.endpoint, duration_ms, self.success, std::mem::take(&mut self.tags));
     }
 }
\ No newline at end of file
@@ -9,10 +9,39 @@ pub mod connection;
 pub mod metrics;
 pub mod cache;
 pub mod request_deduplicator;
+pub mod rate_limiter;
+pub mod interceptor;
+pub mod signing;
+pub mod json_stream;
+pub mod concurrency;
+pub mod query;
+pub mod sequencer;
+pub mod jwt;
+pub mod trace;
+pub mod subscriptions;
+pub mod quota;
+pub mod publish_throttle;
+pub mod timing;
+pub mod request_options;
+pub(crate) mod tls;
 
-pub use http::{HttpClient, HttpClientConfig};
-pub use retry::{RetryConfig, with_retry, calculate_backoff};
-pub use connection::{SseConnection, SseConfig, SseMessage, SseState};
-pub use metrics::{MetricsCollector, MetricsContext, MetricSample, MetricStats, MetricsSummary};
-pub use cache::{ResponseCache, CacheMetrics};
-pub use request_deduplicator::{RequestDeduplicator, DeduplicatorStats};
+pub use http::{BatchPolicy, FieldCase, HttpClient, HttpClientConfig, HttpVersion};
+pub use retry::{RetryConfig, RetryPreset, with_retry, calculate_backoff};
+pub use connection::{ConnectionQuality, DisconnectReason, OverflowPolicy, PausePolicy, ReconnectAttempt, SseConnection, SseConfig, SseMessage, SseState, SubscriptionHealth, TokenProvider, TokenProviderFn};
+pub use metrics::{MetricsCollector, MetricsContext, MetricSample, MetricStats, MetricsSummary, PerformanceReport};
+pub use cache::{ResponseCache, CacheMetrics, CachePolicy, CacheSnapshot, CacheSnapshotEntry};
+pub use request_deduplicator::{RequestDeduplicator, DeduplicatorStats, DeduplicatorSnapshot, DeduplicatorSnapshotEntry, EvictionPolicy, canonical_json};
+pub use rate_limiter::{TokenBucket, PriorityRateLimiter};
+pub use interceptor::{RequestInterceptor, InterceptorChain, RequestOutcome, RequestCompleteHook, RetriesExhaustedHook, RetriesExhaustedFn, RequestIdGenerator, MetadataValidator, MetadataValidatorFn};
+pub use signing::{RequestSigner, HmacSigner, MessageSigner, ResponseVerifier};
+pub use json_stream::{JsonArrayParser, json_array_stream};
+pub use concurrency::{AdaptiveConcurrencyLimiter, ConcurrencyPermit};
+pub use query::QueryBuilder;
+pub use sequencer::ChannelSequencer;
+pub use jwt::ApiKeyClaims;
+pub use trace::TraceContext;
+pub use subscriptions::SubscriptionRegistry;
+pub use quota::Operation;
+pub use publish_throttle::PublishThrottle;
+pub use timing::{timed, Timed};
+pub use request_options::RequestOptions;
@@ -0,0 +1,89 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (c) 2026 KirkyX. All rights reserved.
+
+//! Client-side suppression of repeated identical publishes
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use sha2::{Digest, Sha256};
+use crate::types::api::{MessagePublishRequest, MessagePublishResponse};
+use crate::Result;
+use super::request_deduplicator::canonical_json;
+
+/// The last successful publish of one message, if any
+type Slot = Arc<tokio::sync::Mutex<Option<(Instant, MessagePublishResponse)>>>;
+
+/// Answers repeats of a recent publish with the original response
+///
+/// A publish is identified by its channel and request body. When the same
+/// publish succeeded less than `window` ago, the earlier response is
+/// returned instead of sending it again. Identical publishes made
+/// concurrently wait for the first one; failures are not remembered, so a
+/// failed publish can be retried straight away.
+#[derive(Debug)]
+pub struct PublishThrottle {
+    window: Duration,
+    slots: Mutex<HashMap<String, Slot>>,
+}
+
+impl PublishThrottle {
+    /// Create a throttle suppressing repeats within `window`
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            slots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Get the window within which repeats are suppressed
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Run `send` unless the same publish succeeded within the window
+    pub async fn publish<F>(
+        &self,
+        channel: &str,
+        request: &MessagePublishRequest,
+        send: F,
+    ) -> Result<MessagePublishResponse>
+    where
+        F: Future<Output = Result<MessagePublishResponse>>,
+    {
+        let slot = self.slot(Self::key(channel, request)?);
+        let mut last = slot.lock().await;
+        if let Some((published_at, response)) = last.as_ref() {
+            if published_at.elapsed() < self.window {
+                return Ok(response.clone());
+            }
+        }
+
+        let response = send.await?;
+        *last = Some((Instant::now(), response.clone()));
+        Ok(response)
+    }
+
+    fn key(channel: &str, request: &MessagePublishRequest) -> Result<String> {
+        let body = serde_json::to_value(request)?;
+        let mut hasher = Sha256::new();
+        hasher.update(channel.as_bytes());
+        hasher.update([0]);
+        hasher.update(canonical_json(&body).as_bytes());
+        Ok(format!("{:x}", hasher.finalize()))
+    }
+
+    fn slot(&self, key: String) -> Slot {
+        let mut slots = self.slots.lock().unwrap();
+        // Drop publishes nobody is waiting on whose window has passed
+        slots.retain(|_, slot| {
+            Arc::strong_count(slot) > 1
+                || slot.try_lock().map_or(true, |last| {
+                    last.as_ref()
+                        .is_some_and(|(published_at, _)| published_at.elapsed() < self.window)
+                })
+        });
+        slots.entry(key).or_default().clone()
+    }
+}
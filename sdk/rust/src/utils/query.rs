@@ -0,0 +1,56 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (c) 2026 KirkyX. All rights reserved.
+
+//! Query string construction for API endpoints
+
+/// Builds an endpoint with a URL-encoded query string
+///
+/// Keys and values are encoded with `application/x-www-form-urlencoded`
+/// rules, so values containing `&`, `=`, spaces or non-ASCII characters are
+/// passed through intact.
+#[derive(Debug, Clone, Default)]
+pub struct QueryBuilder {
+    params: Vec<(String, String)>,
+}
+
+impl QueryBuilder {
+    /// Create an empty query
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a parameter
+    pub fn param(mut self, key: &str, value: impl ToString) -> Self {
+        self.params.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Add a parameter if a value is present
+    pub fn optional(self, key: &str, value: Option<impl ToString>) -> Self {
+        match value {
+            Some(value) => self.param(key, value),
+            None => self,
+        }
+    }
+
+    /// Check whether no parameters have been added
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+
+    /// Get the encoded query string, without the leading `?`
+    pub fn query_string(&self) -> String {
+        url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(&self.params)
+            .finish()
+    }
+
+    /// Append the query string to a path
+    pub fn build(&self, path: &str) -> String {
+        if self.is_empty() {
+            path.to_string()
+        } else {
+            format!("{}?{}", path, self.query_string())
+        }
+    }
+}
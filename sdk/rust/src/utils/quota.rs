@@ -0,0 +1,30 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (c) 2026 KirkyX. All rights reserved.
+
+//! Quota cost accounting for SDK operations
+
+/// An operation whose quota cost can be estimated
+///
+/// Every API request consumes one quota unit. A subscription is charged a
+/// single unit when it is opened, however long it stays connected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    /// Publish one message
+    Publish,
+    /// Publish one message to this many channels with `publish_fanout`
+    PublishFanout(u32),
+    /// Open a subscription
+    Subscribe,
+    /// Any other single API request
+    Request,
+}
+
+impl Operation {
+    /// Get the number of quota units the operation consumes
+    pub fn cost(&self) -> u32 {
+        match self {
+            Self::Publish | Self::Subscribe | Self::Request => 1,
+            Self::PublishFanout(channels) => *channels,
+        }
+    }
+}
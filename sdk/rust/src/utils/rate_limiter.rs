@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (c) 2026 KirkyX. All rights reserved.
+
+//! Client-side rate limiting for SDK operations
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use crate::MessagePriority;
+
+/// Mutable state of a token bucket
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Token bucket refilled continuously at a fixed rate
+#[derive(Debug)]
+pub struct TokenBucket {
+    rate: f64,
+    burst: f64,
+    state: Mutex<BucketState>,
+}
+
+impl TokenBucket {
+    /// Create a new token bucket
+    ///
+    /// # Arguments
+    /// * `rate` - Tokens added per second
+    /// * `burst` - Maximum number of tokens the bucket can hold
+    pub fn new(rate: f64, burst: u32) -> Self {
+        let burst = f64::from(burst.max(1));
+        Self {
+            rate: rate.max(f64::EPSILON),
+            burst,
+            state: Mutex::new(BucketState {
+                tokens: burst,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Take a token if one is available
+    ///
+    /// # Returns
+    /// `None` if a token was taken, otherwise the time until one becomes available
+    fn take(&self) -> Option<Duration> {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.rate).min(self.burst);
+        state.last_refill = now;
+
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - state.tokens) / self.rate))
+        }
+    }
+
+    /// Try to take a token without waiting
+    pub fn try_acquire(&self) -> bool {
+        self.take().is_none()
+    }
+
+    /// Wait until a token is available and take it
+    pub async fn acquire(&self) {
+        while let Some(wait) = self.take() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Per-priority rate limiter for message publishing
+///
+/// Priorities without a configured bucket are not limited, so critical
+/// messages bypass limiting unless a limit is set for them explicitly.
+#[derive(Debug, Default)]
+pub struct PriorityRateLimiter {
+    buckets: HashMap<MessagePriority, TokenBucket>,
+}
+
+impl PriorityRateLimiter {
+    /// Create a limiter from `(rate, burst)` settings per priority
+    pub fn new(limits: &HashMap<MessagePriority, (f64, u32)>) -> Self {
+        Self {
+            buckets: limits
+                .iter()
+                .map(|(priority, (rate, burst))| (*priority, TokenBucket::new(*rate, *burst)))
+                .collect(),
+        }
+    }
+
+    /// Check whether a priority is rate limited
+    pub fn is_limited(&self, priority: MessagePriority) -> bool {
+        self.buckets.contains_key(&priority)
+    }
+
+    /// Wait for a publish permit for the given priority
+    pub async fn acquire(&self, priority: MessagePriority) {
+        if let Some(bucket) = self.buckets.get(&priority) {
+            bucket.acquire().await;
+        }
+    }
+}
@@ -3,12 +3,13 @@
 
 //! Request deduplication utility for SDK operations
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use sha2::{Sha256, Digest};
 use serde::{Serialize, Deserialize};
 use tokio::sync::Mutex as TokioMutex;
+use super::cache::unix_millis;
 
 /// A pending request waiting for completion
 #[derive(Debug)]
@@ -17,6 +18,104 @@ struct PendingRequest {
     result: Option<Result<String, String>>,
 }
 
+/// A completed request result kept for reuse
+#[derive(Debug, Clone)]
+struct CompletedRequest {
+    value: String,
+    completed_at: SystemTime,
+}
+
+/// Which completed result is evicted when the cache is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EvictionPolicy {
+    /// Evict the result completed longest ago
+    #[default]
+    Fifo,
+    /// Evict the result reused least recently
+    Lru,
+}
+
+/// Completed results in eviction order, oldest first
+#[derive(Debug, Default)]
+struct CompletedCache {
+    entries: HashMap<String, CompletedRequest>,
+    order: VecDeque<String>,
+}
+
+impl CompletedCache {
+    /// Look up a result, marking it as recently used under LRU
+    fn get(&mut self, key: &str, policy: EvictionPolicy) -> Option<&CompletedRequest> {
+        if policy == EvictionPolicy::Lru && self.entries.contains_key(key) {
+            self.touch(key);
+        }
+        self.entries.get(key)
+    }
+
+    /// Add or replace a result, evicting the oldest while over `capacity`
+    fn insert(&mut self, key: String, request: CompletedRequest, capacity: usize) {
+        if self.entries.insert(key.clone(), request).is_some() {
+            self.touch(&key);
+        } else {
+            self.order.push_back(key);
+        }
+        while self.entries.len() > capacity {
+            if !self.evict_oldest() {
+                break;
+            }
+        }
+    }
+
+    /// Move a key to the newest end of the order
+    fn touch(&mut self, key: &str) {
+        if let Some(position) = self.order.iter().position(|k| k == key) {
+            if let Some(key) = self.order.remove(position) {
+                self.order.push_back(key);
+            }
+        }
+    }
+
+    fn evict_oldest(&mut self) -> bool {
+        match self.order.pop_front() {
+            Some(key) => {
+                self.entries.remove(&key);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Results in eviction order, oldest first
+    fn iter(&self) -> impl Iterator<Item = (&String, &CompletedRequest)> {
+        self.order
+            .iter()
+            .filter_map(|key| self.entries.get_key_value(key))
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+}
+
+/// Serializable copy of a deduplicator's completed results, for persisting across restarts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeduplicatorSnapshot {
+    pub entries: Vec<DeduplicatorSnapshotEntry>,
+}
+
+/// A single completed result in a [`DeduplicatorSnapshot`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeduplicatorSnapshotEntry {
+    pub key: String,
+    pub value: String,
+    /// Completion time in milliseconds since the Unix epoch
+    pub completed_at_ms: u64,
+}
+
 /// Deduplicator statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeduplicatorStats {
@@ -35,10 +134,11 @@ pub struct DeduplicatorStats {
 /// concurrent requests for the same endpoint and parameters.
 pub struct RequestDeduplicator {
     pending: Arc<TokioMutex<HashMap<String, PendingRequest>>>,
-    completed: Arc<TokioMutex<HashMap<String, String>>>,
-    _ttl: Duration,
+    completed: Arc<TokioMutex<CompletedCache>>,
+    ttl: Duration,
     max_pending: usize,
     max_completed: usize,
+    eviction: EvictionPolicy,
     stats: Arc<TokioMutex<DeduplicatorStats>>,
 }
 
@@ -47,10 +147,11 @@ impl RequestDeduplicator {
     pub fn new(ttl_seconds: f64, max_pending: usize, max_completed: usize) -> Self {
         Self {
             pending: Arc::new(TokioMutex::new(HashMap::new())),
-            completed: Arc::new(TokioMutex::new(HashMap::new())),
-            _ttl: Duration::from_secs_f64(ttl_seconds),
+            completed: Arc::new(TokioMutex::new(CompletedCache::default())),
+            ttl: Duration::from_secs_f64(ttl_seconds),
             max_pending,
             max_completed,
+            eviction: EvictionPolicy::Fifo,
             stats: Arc::new(TokioMutex::new(DeduplicatorStats {
                 hits: 0,
                 misses: 0,
@@ -63,16 +164,20 @@ impl RequestDeduplicator {
         }
     }
 
-    /// Create a deduplicator with default settings
-    pub fn default() -> Self {
-        Self::new(5.0, 1000, 10000)
+    /// Set which completed result is evicted once `max_completed` is reached
+    pub fn with_eviction_policy(mut self, policy: EvictionPolicy) -> Self {
+        self.eviction = policy;
+        self
     }
 
     /// Generate a unique key for the request
-    fn generate_key(&self, endpoint: &str, params: &Option<serde_json::Value>) -> String {
+    ///
+    /// Parameters are canonicalized first so logically identical requests
+    /// always produce the same key.
+    pub(crate) fn generate_key(&self, endpoint: &str, params: &Option<serde_json::Value>) -> String {
         // Create a deterministic string from the parameters
         let params_str = if let Some(p) = params {
-            serde_json::to_string(p).unwrap_or_default()
+            canonical_json(p)
         } else {
             String::new()
         };
@@ -113,12 +218,12 @@ impl RequestDeduplicator {
 
         // Check completed cache first
         if use_cache {
-            let completed = self.completed.lock().await;
-            if let Some(result) = completed.get(&key) {
+            let mut completed = self.completed.lock().await;
+            if let Some(result) = completed.get(&key, self.eviction) {
                 let mut stats = self.stats.lock().await;
                 stats.hits += 1;
                 stats.hit_rate = stats.hits as f64 / (stats.hits + stats.misses) as f64;
-                return Ok(result.clone());
+                return Ok(result.value.clone());
             }
         }
 
@@ -181,14 +286,12 @@ impl RequestDeduplicator {
         // Store result in completed cache
         if use_cache && result.is_ok() {
             let mut completed = self.completed.lock().await;
-            if completed.len() >= self.max_completed {
-                // Remove oldest entry (simple FIFO)
-                if let Some(oldest_key) = completed.keys().next().cloned() {
-                    completed.remove(&oldest_key);
-                }
-            }
             if let Ok(ref value) = result {
-                completed.insert(key, value.clone());
+                let request = CompletedRequest {
+                    value: value.clone(),
+                    completed_at: SystemTime::now(),
+                };
+                completed.insert(key, request, self.max_completed);
             }
         }
 
@@ -212,11 +315,8 @@ impl RequestDeduplicator {
 
         // Remove oldest entries if we exceed max_completed
         let mut completed = self.completed.lock().await;
-        while completed.len() > self.max_completed * 2 {
-            if let Some(oldest_key) = completed.keys().next().cloned() {
-                completed.remove(&oldest_key);
-                removed += 1;
-            }
+        while completed.len() > self.max_completed * 2 && completed.evict_oldest() {
+            removed += 1;
         }
 
         removed
@@ -252,6 +352,48 @@ impl RequestDeduplicator {
         self.clear_pending().await + self.clear_completed().await
     }
 
+    /// Export all completed results, oldest first
+    pub async fn export(&self) -> DeduplicatorSnapshot {
+        let completed = self.completed.lock().await;
+        let entries = completed
+            .iter()
+            .map(|(key, request)| DeduplicatorSnapshotEntry {
+                key: key.clone(),
+                value: request.value.clone(),
+                completed_at_ms: unix_millis(request.completed_at),
+            })
+            .collect();
+        DeduplicatorSnapshot { entries }
+    }
+
+    /// Load completed results from a snapshot
+    ///
+    /// Results that completed longer ago than the deduplicator TTL are
+    /// dropped.
+    ///
+    /// # Returns
+    /// Number of results imported
+    pub async fn import(&self, snapshot: DeduplicatorSnapshot) -> usize {
+        let now = SystemTime::now();
+        let mut completed = self.completed.lock().await;
+        let mut imported = 0;
+
+        for entry in snapshot.entries {
+            let completed_at = UNIX_EPOCH + Duration::from_millis(entry.completed_at_ms);
+            let age = now.duration_since(completed_at).unwrap_or_default();
+            if age >= self.ttl || completed.len() >= self.max_completed {
+                continue;
+            }
+            let request = CompletedRequest {
+                value: entry.value,
+                completed_at,
+            };
+            completed.insert(entry.key, request, self.max_completed);
+            imported += 1;
+        }
+        imported
+    }
+
     /// Get statistics about the deduplicator
     ///
     /// # Returns
@@ -280,4 +422,65 @@ impl RequestDeduplicator {
         stats.errors = 0;
         stats.hit_rate = 0.0;
     }
-}
\ No newline at end of file
+}
+
+impl Default for RequestDeduplicator {
+    /// Create a deduplicator with default settings
+    fn default() -> Self {
+        Self::new(5.0, 1000, 10000)
+    }
+}
+
+/// Serialize a JSON value in canonical form
+///
+/// Object keys are sorted and integral floating point numbers are written as
+/// integers (`1.0` becomes `1`), so equivalent values serialize identically.
+pub fn canonical_json(value: &serde_json::Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &serde_json::Value, out: &mut String) {
+    use serde_json::Value;
+
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            out.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(value, out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        Value::Number(number) => out.push_str(&canonical_number(number)),
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+fn canonical_number(number: &serde_json::Number) -> String {
+    if number.is_f64() {
+        if let Some(f) = number.as_f64() {
+            if f.fract() == 0.0 && f.abs() < 9_007_199_254_740_992.0 {
+                return format!("{}", f as i64);
+            }
+        }
+    }
+    number.to_string()
+}
@@ -0,0 +1,48 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (c) 2026 KirkyX. All rights reserved.
+
+//! Local per-request context that is never sent to the server
+
+use std::collections::HashMap;
+use std::future::Future;
+
+tokio::task_local! {
+    static CURRENT: RequestOptions;
+}
+
+/// Client-side context for the requests made within a scope
+///
+/// Tags label the metric samples and, with the `tracing` feature, the
+/// request spans of every request made inside `scope`, so metrics can be
+/// grouped by tenant, feature flag and so on. They stay local: no header
+/// or body field carries them to the server.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestOptions {
+    /// Labels for metrics and tracing
+    pub tags: HashMap<String, String>,
+}
+
+impl RequestOptions {
+    /// Create options without tags
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a tag, replacing any previous value for `key`
+    pub fn with_tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags.insert(key.into(), value.into());
+        self
+    }
+
+    /// Run a future with these options applied to the requests it makes
+    ///
+    /// Scopes do not merge: an inner scope replaces the outer options.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT.scope(self, future).await
+    }
+
+    /// Get the options of the enclosing scope, if any
+    pub fn current() -> Option<Self> {
+        CURRENT.try_with(Clone::clone).ok()
+    }
+}
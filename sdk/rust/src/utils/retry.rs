@@ -7,6 +7,7 @@ use std::time::Duration;
 use rand::Rng;
 use rand::rngs::OsRng;
 use crate::{SecureNotifyError, Result};
+use crate::types::error::is_pre_response_error;
 
 /// Retry configuration
 #[derive(Debug, Clone)]
@@ -21,6 +22,10 @@ pub struct RetryConfig {
     pub backoff_multiplier: f64,
     /// Whether to add random jitter to delays
     pub jitter: bool,
+    /// Whether serialization errors are retried
+    pub retry_serialization_errors: bool,
+    /// Whether to retry only failures that happened before the request was sent
+    pub pre_response_only: bool,
 }
 
 impl Default for RetryConfig {
@@ -31,6 +36,30 @@ impl Default for RetryConfig {
             max_delay: Duration::from_secs(30),
             backoff_multiplier: 2.0,
             jitter: true,
+            retry_serialization_errors: false,
+            pre_response_only: false,
+        }
+    }
+}
+
+/// Named retry configurations for common needs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryPreset {
+    /// See [`RetryConfig::aggressive`]
+    Aggressive,
+    /// See [`RetryConfig::conservative`]
+    Conservative,
+    /// See [`RetryConfig::none`]
+    None,
+}
+
+impl RetryPreset {
+    /// Get the retry configuration for this preset
+    pub fn config(&self) -> RetryConfig {
+        match self {
+            Self::Aggressive => RetryConfig::aggressive(),
+            Self::Conservative => RetryConfig::conservative(),
+            Self::None => RetryConfig::none(),
         }
     }
 }
@@ -41,6 +70,36 @@ impl RetryConfig {
         Self::default()
     }
 
+    /// Retry often and quickly: 5 retries from 200ms, growing 1.5x up to 5s
+    pub fn aggressive() -> Self {
+        Self {
+            max_retries: 5,
+            initial_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
+            backoff_multiplier: 1.5,
+            ..Self::default()
+        }
+    }
+
+    /// Retry rarely and patiently: 2 retries from 2s, growing 3x up to 60s
+    pub fn conservative() -> Self {
+        Self {
+            max_retries: 2,
+            initial_delay: Duration::from_secs(2),
+            max_delay: Duration::from_secs(60),
+            backoff_multiplier: 3.0,
+            ..Self::default()
+        }
+    }
+
+    /// Never retry; every failure is returned as-is
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
     /// Set the maximum number of retries
     pub fn with_max_retries(mut self, max_retries: u32) -> Self {
         self.max_retries = max_retries;
@@ -70,9 +129,37 @@ impl RetryConfig {
         self.jitter = jitter;
         self
     }
+
+    /// Enable or disable retrying serialization errors
+    ///
+    /// Only enable this where serialization errors come from decoding
+    /// successful responses, as a retry may then fetch a complete body.
+    pub fn with_retry_serialization_errors(mut self, enabled: bool) -> Self {
+        self.retry_serialization_errors = enabled;
+        self
+    }
+
+    /// Retry only failures that happened before the request was sent
+    ///
+    /// For non-idempotent requests: error responses, timeouts after sending
+    /// and interrupted responses are returned without retrying, since the
+    /// server may already have acted on the request.
+    pub fn with_pre_response_only(mut self, enabled: bool) -> Self {
+        self.pre_response_only = enabled;
+        self
+    }
 }
 
 /// Execute an async operation with retry logic
+///
+/// With the `tracing` feature, each retry emits a `WARN` event carrying the
+/// failed attempt number, the error kind and status, and the delay before
+/// the next attempt.
+///
+/// When the last allowed retry fails with a retryable error, that error is
+/// wrapped in `SecureNotifyError::RetriesExhausted` with the number of
+/// attempts made and the total time spent. A non-retryable error ends the
+/// loop early and is returned unwrapped.
 pub async fn with_retry<T, F, Fut>(
     operation: F,
     config: &RetryConfig,
@@ -83,14 +170,20 @@ where
 {
     let mut last_error: Option<SecureNotifyError> = None;
     let mut delay = config.initial_delay;
+    let start = tokio::time::Instant::now();
 
     for attempt in 0..=config.max_retries {
         match operation(attempt).await {
             Ok(result) => return Ok(result),
             Err(error) => {
-                if attempt < config.max_retries && is_retryable(&error) {
-                    last_error = Some(error);
-
+                let retryable = if config.pre_response_only {
+                    is_pre_response_error(&error)
+                } else {
+                    is_retryable(&error)
+                        || (config.retry_serialization_errors
+                            && matches!(error, SecureNotifyError::SerializationError(..)))
+                };
+                if attempt < config.max_retries && retryable {
                     // Add jitter if enabled (using cryptographically secure random)
                     let actual_delay = if config.jitter {
                         let jitter_range = delay.as_millis() as f64 * 0.1;
@@ -101,12 +194,36 @@ where
                         delay
                     };
 
+                    // Wait at least as long as the server asked, within the max delay
+                    let actual_delay = match error.retry_after() {
+                        Some(retry_after) => actual_delay.max(retry_after.min(config.max_delay)),
+                        None => actual_delay,
+                    };
+
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(
+                        attempt = attempt + 1,
+                        error_kind = error.kind().as_str(),
+                        status = error.status(),
+                        delay_ms = actual_delay.as_millis() as u64,
+                        error = %error,
+                        "retrying after failed attempt"
+                    );
+
+                    last_error = Some(error);
                     tokio::time::sleep(actual_delay).await;
 
                     // Exponential backoff
                     let delay_secs = (delay.as_secs_f64() * config.backoff_multiplier)
                         .min(config.max_delay.as_secs_f64());
                     delay = Duration::from_secs_f64(delay_secs);
+                } else if retryable && attempt > 0 {
+                    // Every allowed retry was spent on this error
+                    return Err(SecureNotifyError::RetriesExhausted {
+                        source: Box::new(error),
+                        attempts: attempt + 1,
+                        elapsed: start.elapsed(),
+                    });
                 } else {
                     return Err(error);
                 }
@@ -116,19 +233,20 @@ where
 
     // biome-ignore lint: last_error is guaranteed to be Some here if we reach this point
     Err(last_error.unwrap_or_else(|| {
-        SecureNotifyError::ConnectionError("Retry exhausted without error".to_string())
+        SecureNotifyError::ConnectionError("Retry exhausted without error".to_string(), None)
     }))
 }
 
 /// Check if an error is retryable
 fn is_retryable(error: &SecureNotifyError) -> bool {
     match error {
-        SecureNotifyError::NetworkError(_) => true,
-        SecureNotifyError::ConnectionError(_) => true,
-        SecureNotifyError::TimeoutError(_) => true,
+        SecureNotifyError::NetworkError(..) => true,
+        SecureNotifyError::ConnectionError(..) => true,
+        SecureNotifyError::TimeoutError(..) => true,
         SecureNotifyError::ApiError { status, .. } => {
             matches!(status, 429 | 500 | 502 | 503 | 504)
         }
+        SecureNotifyError::ServiceUnavailable { .. } => true,
         _ => false,
     }
 }
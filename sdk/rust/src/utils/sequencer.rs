@@ -0,0 +1,41 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (c) 2026 KirkyX. All rights reserved.
+
+//! Per-channel ordering of publish requests
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::OwnedMutexGuard;
+
+/// Serializes operations per channel in call order
+///
+/// Each channel has its own async mutex. Tokio mutexes are fair, so callers
+/// acquire a channel in the order they started waiting for it. Entries for
+/// channels nobody holds or waits on are dropped on the next lock.
+#[derive(Debug, Default)]
+pub struct ChannelSequencer {
+    channels: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl ChannelSequencer {
+    /// Create a new sequencer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wait for exclusive access to a channel, held until the guard is dropped
+    pub async fn lock(&self, channel: &str) -> OwnedMutexGuard<()> {
+        let mutex = {
+            let mut channels = self.channels.lock().unwrap();
+            channels.retain(|_, mutex| Arc::strong_count(mutex) > 1);
+            channels.entry(channel.to_string()).or_default().clone()
+        };
+        mutex.lock_owned().await
+    }
+
+    /// Get the number of channels currently held or waited on
+    pub fn active_channels(&self) -> usize {
+        let channels = self.channels.lock().unwrap();
+        channels.values().filter(|mutex| Arc::strong_count(mutex) > 1).count()
+    }
+}
@@ -0,0 +1,210 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (c) 2026 KirkyX. All rights reserved.
+
+//! Request signing for gateway deployments and response signature verification
+
+use std::time::SystemTime;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ed25519_dalek::{Signature, VerifyingKey};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use time::OffsetDateTime;
+use time::macros::format_description;
+use crate::{Result, SecureNotifyError};
+
+/// Signature algorithm identifier used by [`HmacSigner`]
+pub const HMAC_SIGNING_ALGORITHM: &str = "SN-HMAC-SHA256";
+
+/// Header carrying the signing timestamp
+pub const SIGNING_DATE_HEADER: &str = "x-sn-date";
+
+/// Header carrying the hex SHA-256 of the request body
+pub const CONTENT_SHA256_HEADER: &str = "x-sn-content-sha256";
+
+/// Signs outgoing requests
+///
+/// The signer runs after the body is set and immediately before each send,
+/// so every retry attempt is signed with a fresh timestamp.
+pub trait RequestSigner: Send + Sync + std::fmt::Debug {
+    /// Add signature headers to the request
+    fn sign(&self, parts: &mut http::request::Parts, body: &[u8]);
+}
+
+/// HMAC-SHA256 request signer in the style of AWS SigV4
+///
+/// The canonical request is built from the method, path, query string,
+/// the `host`, `x-sn-date` and `x-sn-content-sha256` headers (plus
+/// `content-type` when present) and the body hash. The `Authorization`
+/// header has the form:
+///
+/// ```text
+/// SN-HMAC-SHA256 Credential=<key id>, SignedHeaders=<names>, Signature=<hex>
+/// ```
+pub struct HmacSigner {
+    key_id: String,
+    secret: Vec<u8>,
+}
+
+impl std::fmt::Debug for HmacSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HmacSigner")
+            .field("key_id", &self.key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl HmacSigner {
+    /// Create a new signer
+    pub fn new(key_id: impl Into<String>, secret: impl AsRef<[u8]>) -> Self {
+        Self {
+            key_id: key_id.into(),
+            secret: secret.as_ref().to_vec(),
+        }
+    }
+
+    /// Sign the request as of the given time
+    pub fn sign_at(&self, parts: &mut http::request::Parts, body: &[u8], timestamp: SystemTime) {
+        let timestamp = OffsetDateTime::from(timestamp)
+            .format(format_description!("[year][month][day]T[hour][minute][second]Z"))
+            .unwrap_or_default();
+        let body_hash = format!("{:x}", Sha256::digest(body));
+
+        for (name, value) in [(SIGNING_DATE_HEADER, &timestamp), (CONTENT_SHA256_HEADER, &body_hash)] {
+            if let Ok(value) = http::HeaderValue::from_str(value) {
+                parts.headers.insert(name, value);
+            }
+        }
+
+        let host = parts.uri.authority().map(|a| a.as_str()).unwrap_or_default().to_string();
+        let mut signed: Vec<(&str, String)> = vec![
+            ("host", host),
+            (SIGNING_DATE_HEADER, timestamp.clone()),
+            (CONTENT_SHA256_HEADER, body_hash.clone()),
+        ];
+        if let Some(content_type) = parts.headers.get(http::header::CONTENT_TYPE) {
+            signed.push(("content-type", content_type.to_str().unwrap_or_default().trim().to_string()));
+        }
+        signed.sort_by(|a, b| a.0.cmp(b.0));
+
+        let signed_headers = signed.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(";");
+        let canonical_headers: String = signed
+            .iter()
+            .map(|(name, value)| format!("{}:{}\n", name, value))
+            .collect();
+
+        let canonical_request = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            parts.method.as_str(),
+            parts.uri.path(),
+            parts.uri.query().unwrap_or_default(),
+            canonical_headers,
+            signed_headers,
+            body_hash,
+        );
+        let string_to_sign = format!(
+            "{}\n{}\n{:x}",
+            HMAC_SIGNING_ALGORITHM,
+            timestamp,
+            Sha256::digest(canonical_request.as_bytes()),
+        );
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.secret)
+            .expect("HMAC accepts keys of any length");
+        mac.update(string_to_sign.as_bytes());
+        let signature = format!("{:x}", mac.finalize().into_bytes());
+
+        let authorization = format!(
+            "{} Credential={}, SignedHeaders={}, Signature={}",
+            HMAC_SIGNING_ALGORITHM, self.key_id, signed_headers, signature
+        );
+        if let Ok(value) = http::HeaderValue::from_str(&authorization) {
+            parts.headers.insert(http::header::AUTHORIZATION, value);
+        }
+    }
+}
+
+impl RequestSigner for HmacSigner {
+    fn sign(&self, parts: &mut http::request::Parts, body: &[u8]) {
+        self.sign_at(parts, body, SystemTime::now());
+    }
+}
+
+/// HMAC-SHA256 signer for message content
+///
+/// Produces the hex signature sent in the `signature` field of a publish.
+/// The signed data is the channel ID and the message joined by a newline.
+/// The keyed HMAC state is prepared once and reused for every message.
+#[derive(Clone)]
+pub struct MessageSigner {
+    mac: Hmac<Sha256>,
+}
+
+impl std::fmt::Debug for MessageSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MessageSigner").finish_non_exhaustive()
+    }
+}
+
+impl MessageSigner {
+    /// Create a new signer
+    pub fn new(secret: impl AsRef<[u8]>) -> Self {
+        Self {
+            mac: Hmac::<Sha256>::new_from_slice(secret.as_ref())
+                .expect("HMAC accepts keys of any length"),
+        }
+    }
+
+    /// Sign a message for a channel
+    pub fn sign(&self, channel: &str, message: &str) -> String {
+        let mut mac = self.mac.clone();
+        mac.update(channel.as_bytes());
+        mac.update(b"\n");
+        mac.update(message.as_bytes());
+        format!("{:x}", mac.finalize().into_bytes())
+    }
+
+    /// Sign `(channel, message)` pairs, returning signatures in the same order
+    pub fn sign_batch(&self, items: &[(&str, &str)]) -> Vec<String> {
+        items
+            .iter()
+            .map(|(channel, message)| self.sign(channel, message))
+            .collect()
+    }
+}
+
+/// Header carrying the server's signature of the response body
+pub const RESPONSE_SIGNATURE_HEADER: &str = "x-signature";
+
+/// Verifies server signatures on response bodies
+///
+/// The server signs the raw response body with Ed25519 and sends the
+/// base64-encoded 64-byte signature in the `X-Signature` header.
+#[derive(Debug, Clone)]
+pub struct ResponseVerifier {
+    key: VerifyingKey,
+}
+
+impl ResponseVerifier {
+    /// Create a verifier from the server's 32-byte Ed25519 public key
+    pub fn new(public_key: &[u8; 32]) -> Result<Self> {
+        VerifyingKey::from_bytes(public_key)
+            .map(|key| Self { key })
+            .map_err(|e| SecureNotifyError::ValidationError(format!("Invalid response verification key: {}", e)))
+    }
+
+    /// Check that the response carries a valid signature of `body`
+    pub fn verify(&self, headers: &reqwest::header::HeaderMap, body: &[u8]) -> Result<()> {
+        let failure = |reason: &str| SecureNotifyError::SignatureVerificationError(reason.to_string());
+        let header = headers
+            .get(RESPONSE_SIGNATURE_HEADER)
+            .ok_or_else(|| failure("response has no X-Signature header"))?;
+        let bytes = STANDARD
+            .decode(header.as_bytes())
+            .map_err(|_| failure("signature is not valid base64"))?;
+        let signature = Signature::from_slice(&bytes).map_err(|_| failure("signature is not 64 bytes"))?;
+        self.key
+            .verify_strict(body, &signature)
+            .map_err(|_| failure("signature does not match the response body"))
+    }
+}
@@ -0,0 +1,136 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (c) 2026 KirkyX. All rights reserved.
+
+//! Tracking of active subscriptions
+
+use std::sync::Mutex;
+use tokio::task::AbortHandle;
+use crate::utils::connection::{HealthProbe, SubscriptionHealth};
+
+/// Background tasks feeding one subscription's receiver
+#[derive(Debug)]
+struct TrackedSubscription {
+    channel_id: String,
+    tasks: Vec<AbortHandle>,
+    /// The SSE connection's health, absent for long-poll-only subscriptions
+    health: Option<HealthProbe>,
+}
+
+impl TrackedSubscription {
+    fn is_finished(&self) -> bool {
+        self.tasks.iter().all(AbortHandle::is_finished)
+    }
+}
+
+/// Registry of the subscriptions opened through a client
+///
+/// Each subscription is recorded with the tasks delivering its messages.
+/// Aborting those tasks drops every sender of the subscription's channel,
+/// so its receiver closes.
+#[derive(Debug, Default)]
+pub struct SubscriptionRegistry {
+    subscriptions: Mutex<Vec<TrackedSubscription>>,
+}
+
+impl SubscriptionRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a subscription and the tasks feeding it
+    pub fn track(&self, channel_id: &str, tasks: Vec<AbortHandle>) {
+        self.track_with_health(channel_id, tasks, None);
+    }
+
+    /// Record a subscription as `track` does, along with its connection's health
+    pub(crate) fn track_with_health(&self, channel_id: &str, tasks: Vec<AbortHandle>, health: Option<HealthProbe>) {
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        // Subscriptions that ended on their own need no teardown
+        subscriptions.retain(|subscription| !subscription.is_finished());
+        subscriptions.push(TrackedSubscription {
+            channel_id: channel_id.to_string(),
+            tasks,
+            health,
+        });
+    }
+
+    /// Forget a subscription, identified by any of its tasks
+    ///
+    /// The tasks are not stopped; abort them first.
+    pub(crate) fn untrack(&self, tasks: &[AbortHandle]) {
+        let ids: Vec<_> = tasks.iter().map(AbortHandle::id).collect();
+        self.subscriptions
+            .lock()
+            .unwrap()
+            .retain(|subscription| !subscription.tasks.iter().any(|task| ids.contains(&task.id())));
+    }
+
+    /// Get the connection health of the newest running subscription to a channel
+    ///
+    /// Returns `None` if the channel has no running SSE subscription.
+    pub async fn health(&self, channel_id: &str) -> Option<SubscriptionHealth> {
+        let probe = {
+            let subscriptions = self.subscriptions.lock().unwrap();
+            subscriptions
+                .iter()
+                .rev()
+                .filter(|s| s.channel_id == channel_id && !s.is_finished())
+                .find_map(|s| s.health.clone())
+        }?;
+        Some(probe.health().await)
+    }
+
+    /// Get the channels with subscriptions still running
+    ///
+    /// A channel subscribed to more than once is listed once.
+    pub fn active_channels(&self) -> Vec<String> {
+        let subscriptions = self.subscriptions.lock().unwrap();
+        let mut channels: Vec<String> = Vec::new();
+        for subscription in subscriptions.iter().filter(|s| !s.is_finished()) {
+            if !channels.contains(&subscription.channel_id) {
+                channels.push(subscription.channel_id.clone());
+            }
+        }
+        channels
+    }
+
+    /// Stop every tracked subscription to one channel
+    ///
+    /// # Returns
+    /// Whether the channel had a subscription running
+    pub fn cancel(&self, channel_id: &str) -> bool {
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        let mut running = false;
+        subscriptions.retain(|subscription| {
+            if subscription.channel_id != channel_id {
+                return true;
+            }
+            if !subscription.is_finished() {
+                subscription.tasks.iter().for_each(AbortHandle::abort);
+                running = true;
+            }
+            false
+        });
+        running
+    }
+
+    /// Stop every tracked subscription
+    ///
+    /// # Returns
+    /// The channels that had a subscription running, each listed once
+    pub fn cancel_all(&self) -> Vec<String> {
+        let subscriptions = std::mem::take(&mut *self.subscriptions.lock().unwrap());
+        let mut channels: Vec<String> = Vec::new();
+        for subscription in subscriptions {
+            if subscription.is_finished() {
+                continue;
+            }
+            subscription.tasks.iter().for_each(AbortHandle::abort);
+            if !channels.contains(&subscription.channel_id) {
+                channels.push(subscription.channel_id);
+            }
+        }
+        channels
+    }
+}
@@ -0,0 +1,55 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (c) 2026 KirkyX. All rights reserved.
+
+//! Per-call timing of SDK operations
+
+use std::future::Future;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use crate::Result;
+
+tokio::task_local! {
+    static ATTEMPTS: Arc<AtomicU32>;
+}
+
+/// A value together with how long it took to obtain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timed<T> {
+    /// The result of the call
+    pub value: T,
+    /// Wall-clock time of the whole call, including retries and backoff
+    pub duration: Duration,
+    /// HTTP attempts made, counting retries (0 when no request was sent)
+    pub attempts: u32,
+}
+
+impl<T> Timed<T> {
+    /// Discard the timing and keep the value
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+/// Run an SDK call, reporting its duration and the attempts it made
+///
+/// Attempts are counted across every request the call sends. Works
+/// without the metrics collector being enabled.
+pub async fn timed<T, F>(future: F) -> Result<Timed<T>>
+where
+    F: Future<Output = Result<T>>,
+{
+    let attempts = Arc::new(AtomicU32::new(0));
+    let start = Instant::now();
+    let value = ATTEMPTS.scope(attempts.clone(), future).await?;
+    Ok(Timed {
+        value,
+        duration: start.elapsed(),
+        attempts: attempts.load(Ordering::Relaxed),
+    })
+}
+
+/// Add the attempts of a finished request to the enclosing `timed` call, if any
+pub(crate) fn record_attempts(attempts: u32) {
+    let _ = ATTEMPTS.try_with(|total| total.fetch_add(attempts, Ordering::Relaxed));
+}
@@ -0,0 +1,122 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (c) 2026 KirkyX. All rights reserved.
+
+//! TLS settings shared by the HTTP and SSE clients
+
+use crate::types::error::ErrorSource;
+use crate::{Result, SecureNotifyError};
+
+/// Reason given when the server's certificate does not match the pin
+const PIN_MISMATCH: &str = "Server certificate does not match the pinned fingerprint";
+
+/// Apply the certificate pin and any extra trusted roots to a client
+///
+/// A pinned client uses rustls with the Mozilla root set, and checks the
+/// pin during the handshake, so no request reaches a server that fails it.
+/// It also refuses plain HTTP, which would skip the check. `roots` are DER
+/// encoded and trusted in addition to the default roots. Pinning requires
+/// the `cert-pinning` feature; without it a pinned client fails to build.
+pub(crate) fn configure(
+    builder: reqwest::ClientBuilder,
+    pin: Option<[u8; 32]>,
+    roots: &[Vec<u8>],
+    http1_only: bool,
+) -> Result<reqwest::ClientBuilder> {
+    let Some(pin) = pin else {
+        return roots.iter().try_fold(builder, |builder, der| {
+            Ok(builder.add_root_certificate(reqwest::Certificate::from_der(der)?))
+        });
+    };
+
+    #[cfg(feature = "cert-pinning")]
+    return pinning::configure(builder, pin, roots, http1_only);
+
+    #[cfg(not(feature = "cert-pinning"))]
+    {
+        let _ = (builder, pin, http1_only);
+        Err(SecureNotifyError::ConnectionError(
+            "Certificate pinning requires the `cert-pinning` feature".to_string(),
+            None,
+        ))
+    }
+}
+
+/// Convert an error from sending a request, naming a pin mismatch found among its causes
+pub(crate) fn send_error(error: reqwest::Error) -> SecureNotifyError {
+    let mismatch = std::iter::successors(Some(&error as &(dyn std::error::Error + 'static)), |e| e.source())
+        .any(|e| e.to_string().contains(PIN_MISMATCH));
+    if mismatch {
+        SecureNotifyError::ConnectionError(PIN_MISMATCH.to_string(), Some(ErrorSource::new(error)))
+    } else {
+        error.into()
+    }
+}
+
+#[cfg(feature = "cert-pinning")]
+mod pinning {
+    use std::sync::Arc;
+    use std::time::SystemTime;
+    use rustls::client::{ServerCertVerified, ServerCertVerifier, WebPkiVerifier};
+    use rustls::{Certificate, ClientConfig, OwnedTrustAnchor, RootCertStore, ServerName};
+    use sha2::{Digest, Sha256};
+    use crate::{Result, SecureNotifyError};
+    use super::PIN_MISMATCH;
+
+    /// Validates the chain as usual, then requires the leaf certificate to hash to the pin
+    struct PinnedCertVerifier {
+        inner: WebPkiVerifier,
+        pin: [u8; 32],
+    }
+
+    impl ServerCertVerifier for PinnedCertVerifier {
+        fn verify_server_cert(
+            &self,
+            end_entity: &Certificate,
+            intermediates: &[Certificate],
+            server_name: &ServerName,
+            scts: &mut dyn Iterator<Item = &[u8]>,
+            ocsp_response: &[u8],
+            now: SystemTime,
+        ) -> std::result::Result<ServerCertVerified, rustls::Error> {
+            self.inner
+                .verify_server_cert(end_entity, intermediates, server_name, scts, ocsp_response, now)?;
+            if Sha256::digest(&end_entity.0).as_slice() != self.pin {
+                return Err(rustls::Error::General(PIN_MISMATCH.to_string()));
+            }
+            Ok(ServerCertVerified::assertion())
+        }
+    }
+
+    /// Switch the client to rustls with a verifier that checks `pin`
+    pub(super) fn configure(
+        builder: reqwest::ClientBuilder,
+        pin: [u8; 32],
+        roots: &[Vec<u8>],
+        http1_only: bool,
+    ) -> Result<reqwest::ClientBuilder> {
+        let mut store = RootCertStore::empty();
+        store.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|anchor| {
+            OwnedTrustAnchor::from_subject_spki_name_constraints(anchor.subject, anchor.spki, anchor.name_constraints)
+        }));
+        for der in roots {
+            store.add(&Certificate(der.clone())).map_err(|e| {
+                SecureNotifyError::ConnectionError(format!("Invalid root certificate: {}", e), None)
+            })?;
+        }
+
+        let verifier = PinnedCertVerifier {
+            inner: WebPkiVerifier::new(store, None),
+            pin,
+        };
+        let mut tls = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_custom_certificate_verifier(Arc::new(verifier))
+            .with_no_client_auth();
+        tls.alpn_protocols = if http1_only {
+            vec![b"http/1.1".to_vec()]
+        } else {
+            vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+        };
+        Ok(builder.use_preconfigured_tls(tls).https_only(true))
+    }
+}
@@ -0,0 +1,140 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright (c) 2026 KirkyX. All rights reserved.
+
+//! W3C trace context propagation for SDK requests
+
+use std::future::Future;
+use rand::RngCore;
+
+tokio::task_local! {
+    static CURRENT: TraceContext;
+}
+
+/// W3C trace context carried by outgoing requests
+///
+/// Serialized as a `traceparent` header of the form
+/// `00-<32 hex trace id>-<16 hex parent id>-<2 hex flags>`, optionally
+/// accompanied by a vendor-specific `tracestate` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    /// 16-byte trace identifier shared by all spans of a trace
+    pub trace_id: [u8; 16],
+    /// 8-byte identifier of the calling span
+    pub parent_id: [u8; 8],
+    /// Whether the trace is sampled
+    pub sampled: bool,
+    /// Opaque `tracestate` header value, if any
+    pub tracestate: Option<String>,
+}
+
+impl TraceContext {
+    /// Start a new sampled trace with random identifiers
+    pub fn new_random() -> Self {
+        let mut rng = rand::thread_rng();
+        let mut trace_id = [0u8; 16];
+        let mut parent_id = [0u8; 8];
+        // All-zero identifiers are invalid, so redraw on the (unlikely) zero value
+        while trace_id == [0; 16] {
+            rng.fill_bytes(&mut trace_id);
+        }
+        while parent_id == [0; 8] {
+            rng.fill_bytes(&mut parent_id);
+        }
+        Self {
+            trace_id,
+            parent_id,
+            sampled: true,
+            tracestate: None,
+        }
+    }
+
+    /// Parse a `traceparent` header value
+    ///
+    /// Returns `None` for malformed values and for the all-zero trace or
+    /// parent ids the specification marks invalid.
+    pub fn parse(traceparent: &str) -> Option<Self> {
+        let mut parts = traceparent.trim().split('-');
+        let version = parts.next()?;
+        let trace_id = parts.next()?;
+        let parent_id = parts.next()?;
+        let flags = parts.next()?;
+        if version != "00" || parts.next().is_some() {
+            return None;
+        }
+
+        let trace_id: [u8; 16] = decode_hex(trace_id)?.try_into().ok()?;
+        let parent_id: [u8; 8] = decode_hex(parent_id)?.try_into().ok()?;
+        let flags: [u8; 1] = decode_hex(flags)?.try_into().ok()?;
+        if trace_id == [0; 16] || parent_id == [0; 8] {
+            return None;
+        }
+
+        Some(Self {
+            trace_id,
+            parent_id,
+            sampled: flags[0] & 0x01 != 0,
+            tracestate: None,
+        })
+    }
+
+    /// Attach a `tracestate` header value
+    pub fn with_tracestate(mut self, tracestate: impl Into<String>) -> Self {
+        self.tracestate = Some(tracestate.into());
+        self
+    }
+
+    /// Derive the context for an outgoing request
+    ///
+    /// Keeps the trace id, sampling flag and `tracestate` but assigns a fresh
+    /// parent id identifying the request.
+    pub fn child(&self) -> Self {
+        let mut child = Self::new_random();
+        child.trace_id = self.trace_id;
+        child.sampled = self.sampled;
+        child.tracestate = self.tracestate.clone();
+        child
+    }
+
+    /// Get the trace id as lowercase hex
+    pub fn trace_id_hex(&self) -> String {
+        encode_hex(&self.trace_id)
+    }
+
+    /// Format the `traceparent` header value
+    pub fn traceparent(&self) -> String {
+        format!(
+            "00-{}-{}-{:02x}",
+            encode_hex(&self.trace_id),
+            encode_hex(&self.parent_id),
+            u8::from(self.sampled)
+        )
+    }
+
+    /// Run a future with this context as the ambient trace
+    ///
+    /// Requests made inside the future join this trace instead of starting
+    /// new ones when trace propagation is enabled.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT.scope(self, future).await
+    }
+
+    /// Get the ambient trace context, if one is set
+    pub fn current() -> Option<Self> {
+        CURRENT.try_with(Clone::clone).ok()
+    }
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    // The specification only allows lowercase hex
+    if !s.len().is_multiple_of(2) || !s.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b)) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).ok())
+        .collect()
+}